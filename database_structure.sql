@@ -24,18 +24,82 @@ CREATE TABLE `bot_admins` (
 
 -- --------------------------------------------------------
 
+--
+-- Table structure for table `bug_digests`
+--
+
+CREATE TABLE `bug_digests` (
+  `server_id` bigint(20) UNSIGNED NOT NULL,
+  `channel_id` bigint(20) UNSIGNED NOT NULL,
+  `weekday` tinyint(3) UNSIGNED NOT NULL DEFAULT '0',
+  `hour` tinyint(3) UNSIGNED NOT NULL DEFAULT '12',
+  `last_digest` timestamp NULL DEFAULT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
 --
 -- Table structure for table `bug_reports`
 --
 
 CREATE TABLE `bug_reports` (
   `bug_id` int(11) NOT NULL,
+  `guild_id` bigint(20) UNSIGNED NOT NULL DEFAULT '405091134327619587',
   `channel_id` bigint(20) NOT NULL,
   `message_id` bigint(20) NOT NULL,
   `title` tinytext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
-  `status` enum('closed','forgevanilla','resolved','low','medium','high','critical') CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL DEFAULT 'medium',
+  `status` varchar(32) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL DEFAULT 'medium',
   `timestamp` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
-  `legacy` tinyint(1) NOT NULL DEFAULT '0'
+  `legacy` tinyint(1) NOT NULL DEFAULT '0',
+  `duplicate_of` int(11) DEFAULT NULL,
+  `thread_id` bigint(20) UNSIGNED DEFAULT NULL,
+  `mod_version` tinytext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci,
+  `forge_version` tinytext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci,
+  `minecraft_edition` tinytext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci,
+  `reproduction` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci,
+  `expected` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci,
+  `actual` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci,
+  `snapshot_content` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci,
+  `snapshot_author_id` bigint(20) UNSIGNED DEFAULT NULL,
+  `snapshot_author_name` tinytext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_issue_sync`
+--
+
+CREATE TABLE `bug_issue_sync` (
+  `server_id` bigint(20) UNSIGNED NOT NULL,
+  `api_url` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__issues`
+--
+
+CREATE TABLE `bug_reports__issues` (
+  `bug_id` int(11) NOT NULL,
+  `issue_number` int(10) UNSIGNED NOT NULL,
+  `issue_url` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `synced_title` tinytext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `synced_open` tinyint(1) NOT NULL,
+  `synced_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__labels`
+--
+
+CREATE TABLE `bug_reports__labels` (
+  `label_id` int(10) UNSIGNED NOT NULL,
+  `bug_id` int(11) NOT NULL,
+  `label` varchar(32) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
 
 -- --------------------------------------------------------
@@ -53,6 +117,76 @@ CREATE TABLE `bug_reports__links` (
 
 -- --------------------------------------------------------
 
+--
+-- Table structure for table `bug_reports__assignees`
+--
+
+CREATE TABLE `bug_reports__assignees` (
+  `assignee_id` int(10) UNSIGNED NOT NULL,
+  `bug_id` int(11) NOT NULL,
+  `user_id` bigint(20) UNSIGNED NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__attachments`
+--
+
+CREATE TABLE `bug_reports__attachments` (
+  `attachment_id` int(10) UNSIGNED NOT NULL,
+  `bug_id` int(11) NOT NULL,
+  `url` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `filename` tinytext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `content_type` tinytext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci,
+  `data` mediumblob
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__delivery_failures`
+--
+
+CREATE TABLE `bug_reports__delivery_failures` (
+  `user_id` bigint(20) UNSIGNED NOT NULL,
+  `failures` int(10) UNSIGNED NOT NULL DEFAULT '0',
+  `last_error` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `last_failure` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `unsubscribed` timestamp NULL DEFAULT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__history`
+--
+
+CREATE TABLE `bug_reports__history` (
+  `history_id` int(10) UNSIGNED NOT NULL,
+  `bug_id` int(11) NOT NULL,
+  `user_id` bigint(20) UNSIGNED NOT NULL,
+  `timestamp` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `change_type` enum('status','title','category','link_added','link_removed','duplicate','assigned','unassigned','label_added','label_removed','field') NOT NULL,
+  `old_value` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci,
+  `new_value` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__notification_settings`
+--
+
+CREATE TABLE `bug_reports__notification_settings` (
+  `user_id` bigint(20) UNSIGNED NOT NULL,
+  `mode` enum('immediate','daily','muted') NOT NULL DEFAULT 'immediate',
+  `last_digest` timestamp NULL DEFAULT NULL,
+  `last_failed_digest` timestamp NULL DEFAULT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
 --
 -- Table structure for table `bug_reports__notifications`
 --
@@ -60,7 +194,81 @@ CREATE TABLE `bug_reports__links` (
 CREATE TABLE `bug_reports__notifications` (
   `notification_id` int(10) UNSIGNED NOT NULL,
   `bug_id` int(10) UNSIGNED NOT NULL,
-  `user_id` bigint(20) UNSIGNED NOT NULL
+  `user_id` bigint(20) UNSIGNED NOT NULL,
+  `timestamp` timestamp NULL DEFAULT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__pending_notifications`
+--
+
+CREATE TABLE `bug_reports__pending_notifications` (
+  `pending_id` int(10) UNSIGNED NOT NULL,
+  `user_id` bigint(20) UNSIGNED NOT NULL,
+  `bug_id` int(11) NOT NULL,
+  `message` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `timestamp` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__statuses`
+--
+
+CREATE TABLE `bug_reports__statuses` (
+  `server_id` bigint(20) UNSIGNED NOT NULL,
+  `name` varchar(32) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `colour` int(10) UNSIGNED NOT NULL,
+  `marker` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `reaction` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `is_open` tinyint(1) NOT NULL DEFAULT '1',
+  `position` int(10) UNSIGNED NOT NULL DEFAULT '0'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__submissions`
+--
+
+CREATE TABLE `bug_reports__submissions` (
+  `submission_id` int(10) UNSIGNED NOT NULL,
+  `guild_id` bigint(20) UNSIGNED NOT NULL,
+  `channel_id` bigint(20) UNSIGNED NOT NULL,
+  `message_id` bigint(20) UNSIGNED NOT NULL,
+  `queue_message_id` bigint(20) UNSIGNED DEFAULT NULL,
+  `submitter_id` bigint(20) UNSIGNED NOT NULL,
+  `title` tinytext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `category` varchar(16) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL DEFAULT 'renewed',
+  `timestamp` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `review_started` timestamp NULL DEFAULT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_reports__votes`
+--
+
+CREATE TABLE `bug_reports__votes` (
+  `bug_id` int(11) NOT NULL,
+  `user_id` bigint(20) UNSIGNED NOT NULL,
+  `timestamp` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `bug_trackers`
+--
+
+CREATE TABLE `bug_trackers` (
+  `server_id` bigint(20) UNSIGNED NOT NULL,
+  `prefix` varchar(16) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `enabled` tinyint(1) NOT NULL DEFAULT '1'
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
 
 -- --------------------------------------------------------
@@ -91,6 +299,36 @@ CREATE TABLE `custom_commands` (
 
 -- --------------------------------------------------------
 
+--
+-- Table structure for table `custom_commands__history`
+--
+
+CREATE TABLE `custom_commands__history` (
+  `history_id` int(10) UNSIGNED NOT NULL,
+  `server_id` bigint(20) NOT NULL,
+  `name` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `revision` int(10) UNSIGNED NOT NULL,
+  `author_id` bigint(20) UNSIGNED DEFAULT NULL,
+  `timestamp` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `command_json` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `documentation` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `custom_commands__usage`
+--
+
+CREATE TABLE `custom_commands__usage` (
+  `server_id` bigint(20) NOT NULL,
+  `name` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `day` date NOT NULL,
+  `uses` int(10) UNSIGNED NOT NULL DEFAULT '0'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- --------------------------------------------------------
+
 --
 -- Table structure for table `floppa_images`
 --
@@ -208,24 +446,116 @@ CREATE TABLE `user_blacklist` (
 ALTER TABLE `bot_admins`
   ADD PRIMARY KEY (`perm_id`);
 
+--
+-- Indexes for table `bug_digests`
+--
+ALTER TABLE `bug_digests`
+  ADD PRIMARY KEY (`server_id`);
+
 --
 -- Indexes for table `bug_reports`
 --
 ALTER TABLE `bug_reports`
+  ADD PRIMARY KEY (`bug_id`),
+  ADD KEY `guild_id` (`guild_id`);
+
+--
+-- Indexes for table `bug_issue_sync`
+--
+ALTER TABLE `bug_issue_sync`
+  ADD PRIMARY KEY (`server_id`);
+
+--
+-- Indexes for table `bug_reports__issues`
+--
+ALTER TABLE `bug_reports__issues`
   ADD PRIMARY KEY (`bug_id`);
 
+--
+-- Indexes for table `bug_reports__labels`
+--
+ALTER TABLE `bug_reports__labels`
+  ADD PRIMARY KEY (`label_id`),
+  ADD UNIQUE KEY `bug_label` (`bug_id`,`label`),
+  ADD KEY `label` (`label`);
+
 --
 -- Indexes for table `bug_reports__links`
 --
 ALTER TABLE `bug_reports__links`
   ADD PRIMARY KEY (`link_id`);
 
+--
+-- Indexes for table `bug_reports__assignees`
+--
+ALTER TABLE `bug_reports__assignees`
+  ADD PRIMARY KEY (`assignee_id`),
+  ADD UNIQUE KEY `bug_user` (`bug_id`,`user_id`);
+
+--
+-- Indexes for table `bug_reports__attachments`
+--
+ALTER TABLE `bug_reports__attachments`
+  ADD PRIMARY KEY (`attachment_id`),
+  ADD KEY `bug_id` (`bug_id`);
+
+--
+-- Indexes for table `bug_reports__delivery_failures`
+--
+ALTER TABLE `bug_reports__delivery_failures`
+  ADD PRIMARY KEY (`user_id`);
+
+--
+-- Indexes for table `bug_reports__history`
+--
+ALTER TABLE `bug_reports__history`
+  ADD PRIMARY KEY (`history_id`),
+  ADD KEY `bug_id` (`bug_id`);
+
+--
+-- Indexes for table `bug_reports__notification_settings`
+--
+ALTER TABLE `bug_reports__notification_settings`
+  ADD PRIMARY KEY (`user_id`);
+
 --
 -- Indexes for table `bug_reports__notifications`
 --
 ALTER TABLE `bug_reports__notifications`
   ADD PRIMARY KEY (`notification_id`);
 
+--
+-- Indexes for table `bug_reports__pending_notifications`
+--
+ALTER TABLE `bug_reports__pending_notifications`
+  ADD PRIMARY KEY (`pending_id`),
+  ADD KEY `user_id` (`user_id`);
+
+--
+-- Indexes for table `bug_reports__statuses`
+--
+ALTER TABLE `bug_reports__statuses`
+  ADD PRIMARY KEY (`server_id`,`name`);
+
+--
+-- Indexes for table `bug_reports__submissions`
+--
+ALTER TABLE `bug_reports__submissions`
+  ADD PRIMARY KEY (`submission_id`),
+  ADD KEY `guild_id` (`guild_id`);
+
+--
+-- Indexes for table `bug_reports__votes`
+--
+ALTER TABLE `bug_reports__votes`
+  ADD PRIMARY KEY (`bug_id`,`user_id`);
+
+--
+-- Indexes for table `bug_trackers`
+--
+ALTER TABLE `bug_trackers`
+  ADD PRIMARY KEY (`server_id`);
+
 --
 -- Indexes for table `channel_blacklist`
 --
@@ -238,6 +568,19 @@ ALTER TABLE `channel_blacklist`
 ALTER TABLE `custom_commands`
   ADD PRIMARY KEY (`command_id`);
 
+--
+-- Indexes for table `custom_commands__history`
+--
+ALTER TABLE `custom_commands__history`
+  ADD PRIMARY KEY (`history_id`),
+  ADD UNIQUE KEY `revision` (`server_id`,`name`,`revision`);
+
+--
+-- Indexes for table `custom_commands__usage`
+--
+ALTER TABLE `custom_commands__usage`
+  ADD PRIMARY KEY (`server_id`,`name`,`day`);
+
 --
 -- Indexes for table `floppa_images`
 --
@@ -308,18 +651,54 @@ ALTER TABLE `bot_admins`
 ALTER TABLE `bug_reports`
   MODIFY `bug_id` int(11) NOT NULL AUTO_INCREMENT;
 
+--
+-- AUTO_INCREMENT for table `bug_reports__labels`
+--
+ALTER TABLE `bug_reports__labels`
+  MODIFY `label_id` int(10) UNSIGNED NOT NULL AUTO_INCREMENT;
+
 --
 -- AUTO_INCREMENT for table `bug_reports__links`
 --
 ALTER TABLE `bug_reports__links`
   MODIFY `link_id` int(11) NOT NULL AUTO_INCREMENT;
 
+--
+-- AUTO_INCREMENT for table `bug_reports__assignees`
+--
+ALTER TABLE `bug_reports__assignees`
+  MODIFY `assignee_id` int(10) UNSIGNED NOT NULL AUTO_INCREMENT;
+
+--
+-- AUTO_INCREMENT for table `bug_reports__attachments`
+--
+ALTER TABLE `bug_reports__attachments`
+  MODIFY `attachment_id` int(10) UNSIGNED NOT NULL AUTO_INCREMENT;
+
+--
+-- AUTO_INCREMENT for table `bug_reports__history`
+--
+ALTER TABLE `bug_reports__history`
+  MODIFY `history_id` int(10) UNSIGNED NOT NULL AUTO_INCREMENT;
+
 --
 -- AUTO_INCREMENT for table `bug_reports__notifications`
 --
 ALTER TABLE `bug_reports__notifications`
   MODIFY `notification_id` int(10) UNSIGNED NOT NULL AUTO_INCREMENT;
 
+--
+-- AUTO_INCREMENT for table `bug_reports__pending_notifications`
+--
+ALTER TABLE `bug_reports__pending_notifications`
+  MODIFY `pending_id` int(10) UNSIGNED NOT NULL AUTO_INCREMENT;
+
+--
+-- AUTO_INCREMENT for table `bug_reports__submissions`
+--
+ALTER TABLE `bug_reports__submissions`
+  MODIFY `submission_id` int(10) UNSIGNED NOT NULL AUTO_INCREMENT;
+
 --
 -- AUTO_INCREMENT for table `channel_blacklist`
 --
@@ -332,6 +711,12 @@ ALTER TABLE `channel_blacklist`
 ALTER TABLE `custom_commands`
   MODIFY `command_id` int(11) NOT NULL AUTO_INCREMENT;
 
+--
+-- AUTO_INCREMENT for table `custom_commands__history`
+--
+ALTER TABLE `custom_commands__history`
+  MODIFY `history_id` int(10) UNSIGNED NOT NULL AUTO_INCREMENT;
+
 --
 -- AUTO_INCREMENT for table `floppa_images`
 --
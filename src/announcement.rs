@@ -179,6 +179,77 @@ pub struct Announcement {
     pub extra: serde_json::Value,
 }
 
+impl AnnouncementEmbed {
+    fn try_map_text<E>(
+        &mut self,
+        f: &mut impl FnMut(&mut String) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if let Some(AnnouncementEmbedAuthor::Object { name, url, icon }) = &mut self.author {
+            f(name)?;
+            url.iter_mut().chain(icon).try_for_each(&mut *f)?;
+        }
+        [
+            &mut self.title,
+            &mut self.url,
+            &mut self.description,
+            &mut self.image,
+            &mut self.thumbnail,
+        ]
+        .into_iter()
+        .flatten()
+        .try_for_each(&mut *f)?;
+        for field in self
+            .field
+            .iter_mut()
+            .chain(self.fields.iter_mut().flatten())
+        {
+            f(&mut field.title)?;
+            f(&mut field.content)?;
+        }
+        if let Some(footer) = &mut self.footer {
+            f(&mut footer.text)?;
+            footer.icon.iter_mut().try_for_each(&mut *f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Announcement {
+    /// Applies `f` to every text field of the message: content, file urls,
+    /// embed texts and urls, and link button labels and urls
+    pub fn try_map_text<E>(
+        &mut self,
+        mut f: impl FnMut(&mut String) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.content
+            .iter_mut()
+            .chain(&mut self.file)
+            .chain(self.files.iter_mut().flatten())
+            .try_for_each(&mut f)?;
+        for embed in self
+            .embed
+            .iter_mut()
+            .chain(self.embeds.iter_mut().flatten())
+        {
+            embed.try_map_text(&mut f)?;
+        }
+        for button in self.link_buttons.iter_mut().flatten() {
+            f(&mut button.url)?;
+            button.label.iter_mut().try_for_each(&mut f)?;
+        }
+        Ok(())
+    }
+
+    /// Infallible version of [`Announcement::try_map_text`]
+    pub fn map_text(&mut self, mut f: impl FnMut(&mut String)) {
+        self.try_map_text(|text| {
+            f(text);
+            Ok::<(), std::convert::Infallible>(())
+        })
+        .unwrap_or_else(|e| match e {});
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum AnnouncementError {
     InvalidColour(String),
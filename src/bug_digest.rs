@@ -0,0 +1,242 @@
+//! Weekly bugtracker digests, posted by a background task in the channel
+//! configured with `!bug digest`, and daily notification digests for users
+//! who chose them with `!bug notifications settings`.
+
+use chrono::Utc;
+use mysql_async::Conn;
+use serenity::http::Http;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::commands::bug_reports::{format_bug_statistics, tracker_name};
+use crate::constants::MAX_DELIVERY_FAILURES;
+use crate::database::bug_digest::{get_bug_digest, get_digest_configs, set_last_digest, BugDigest};
+use crate::database::bug_reports::{
+    clear_pending_notifications, get_due_pending_notifications, set_failed_digest, track_delivery,
+    Delivery, PartialBugReport, PendingNotification,
+};
+use crate::database::DatabasePool;
+
+/// How often the task checks for due digests
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+fn format_bug_section(bugs: &[PartialBugReport]) -> String {
+    if bugs.is_empty() {
+        return "_None_".into();
+    }
+    let mut res = String::new();
+    for (i, bug) in bugs.iter().enumerate() {
+        let line = format!("{} {}\n", bug.status.marker(), bug);
+        if res.len() + line.len() > 1000 {
+            res.push_str(&format!("_…and {} more_", bugs.len() - i));
+            break;
+        }
+        res.push_str(&line);
+    }
+    res
+}
+
+async fn post_digest(
+    http: &Http,
+    server_id: GuildId,
+    channel_id: ChannelId,
+    digest: &BugDigest,
+) -> Result<(), serenity::Error> {
+    let name = tracker_name(http, server_id).await;
+    channel_id
+        .send_message(http, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.name(&name);
+                    a.icon_url(crate::constants::TERMITE_IMAGE);
+                    a
+                });
+                e.colour(serenity::utils::Colour::TEAL);
+                e.title("Weekly bugtracker digest");
+                e.description(format!(
+                    "Activity since {}: **{}** new, **{}** resolved, **{}** re-opened",
+                    digest.since.format("<t:%s:f>"),
+                    digest.new.len(),
+                    digest.resolved.len(),
+                    digest.reopened.len(),
+                ));
+                e.field(
+                    "Bugtracker statistics",
+                    format_bug_statistics(&digest.counts),
+                    false,
+                );
+                e.field("New bugs", format_bug_section(&digest.new), false);
+                e.field("Resolved bugs", format_bug_section(&digest.resolved), false);
+                e.field(
+                    "Re-opened bugs",
+                    format_bug_section(&digest.reopened),
+                    false,
+                );
+                e.timestamp(Utc::now());
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+async fn post_due_digests(pool: &DatabasePool, http: &Http) -> Result<(), mysql_async::Error> {
+    let mut conn = pool.get_conn().await?;
+    let now = Utc::now();
+
+    for config in get_digest_configs(&mut conn).await? {
+        if !config.is_due(now) {
+            continue;
+        }
+        let since = config
+            .last_digest
+            .unwrap_or_else(|| now - chrono::Duration::days(7));
+
+        if let Some(digest) = get_bug_digest(&mut conn, config.server_id, since).await? {
+            if let Err(e) = post_digest(http, config.server_id, config.channel_id, &digest).await {
+                println!(
+                    "=== ERROR ===\nCould not post bug digest in {:?}: {}\n=== END ===",
+                    config.channel_id, e
+                );
+            }
+        }
+        // do not retry a failed digest every few minutes
+        set_last_digest(&mut conn, config.server_id, now).await?;
+    }
+
+    Ok(())
+}
+
+/// Maximum number of messages of a notification digest. The remaining
+/// notifications are kept for the next digest.
+const MAX_DIGEST_MESSAGES: usize = 5;
+
+/// Splits the notifications of a digest into messages, each with the id of the
+/// last notification it contains
+fn notification_digest_pages(notifications: &[PendingNotification]) -> Vec<(String, u64)> {
+    let mut pages = Vec::new();
+    let mut content = format!(
+        "**Daily bug notifications digest {}**\n",
+        ReactionType::from(EmojiIdentifier {
+            animated: false,
+            id: crate::commands::bug_reports::TERMITE_EMOJI,
+            name: "bug".into(),
+        })
+    );
+    let mut last_id = None;
+
+    for (i, notification) in notifications.iter().enumerate() {
+        let mut line = format!(
+            "**{}-{}** ({}) — {}\n",
+            notification.prefix,
+            notification.bug_id,
+            notification.timestamp.format("<t:%s:R>"),
+            notification.message
+        );
+        if line.len() > 1950 {
+            line = format!(
+                "{}…\n",
+                line.char_indices()
+                    .take_while(|(i, _)| *i < 1900)
+                    .map(|(_, c)| c)
+                    .collect::<String>()
+            );
+        }
+        if content.len() + line.len() > 1950 {
+            if let Some(last_id) = last_id {
+                pages.push((std::mem::take(&mut content), last_id));
+            }
+            if pages.len() == MAX_DIGEST_MESSAGES {
+                let (content, _) = pages.last_mut().unwrap();
+                content.push_str(&format!(
+                    "_…and {} more in your next digest._",
+                    notifications.len() - i
+                ));
+                return pages;
+            }
+        }
+        content.push_str(&line);
+        last_id = Some(notification.pending_id);
+    }
+    if let Some(last_id) = last_id {
+        pages.push((content, last_id));
+    }
+
+    pages
+}
+
+/// Sends the digest of a user, and removes the notifications of each message
+/// that was delivered
+async fn send_notification_digest(
+    conn: &mut Conn,
+    http: &Http,
+    user_id: UserId,
+    notifications: &[PendingNotification],
+) -> Result<Result<(), serenity::Error>, mysql_async::Error> {
+    let channel = match user_id.create_dm_channel(http).await {
+        Ok(channel) => channel,
+        Err(e) => return Ok(Err(e)),
+    };
+    for (content, last_id) in notification_digest_pages(notifications) {
+        if let Err(e) = channel.say(http, content).await {
+            return Ok(Err(e));
+        }
+        clear_pending_notifications(conn, user_id, last_id).await?;
+    }
+
+    Ok(Ok(()))
+}
+
+async fn send_notification_digests(
+    pool: &DatabasePool,
+    http: &Http,
+) -> Result<(), mysql_async::Error> {
+    let mut conn = pool.get_conn().await?;
+
+    for (user_id, notifications) in get_due_pending_notifications(&mut conn).await? {
+        let sent = send_notification_digest(&mut conn, http, user_id, &notifications).await?;
+        if let Err(e) = &sent {
+            println!(
+                "=== ERROR ===\nCould not send notification digest to {:?}: {}\n=== END ===",
+                user_id, e
+            );
+        }
+        let delivery = Delivery::from_result(&sent);
+        if let Delivery::Unreachable(_) = delivery {
+            // otherwise the digest would be retried and counted as failed at every check
+            set_failed_digest(&mut conn, user_id).await?;
+        }
+        if track_delivery(&mut conn, user_id, &delivery).await? {
+            println!(
+                "Unsubscribed {user_id} from all bugs after {MAX_DELIVERY_FAILURES} failed notifications"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks for due digests at a regular interval. Never returns.
+pub async fn digest_task(data: Arc<RwLock<TypeMap>>, http: Arc<Http>) {
+    let pool = {
+        let data_read = data.read().await;
+        data_read
+            .get::<DatabasePool>()
+            .expect("Expected a database pool in the type map")
+            .clone()
+    };
+
+    let mut interval = tokio::time::interval(DIGEST_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = post_due_digests(&pool, &http).await {
+            println!("=== ERROR ===\nCould not post bug digests: {e}\n=== END ===");
+        }
+        if let Err(e) = send_notification_digests(&pool, &http).await {
+            println!("=== ERROR ===\nCould not send notification digests: {e}\n=== END ===");
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Minimal in-process PNG charts, without any image dependency.
+//!
+//! Labels use a tiny built-in bitmap font, which only has digits and a few
+//! separators. Images are palette-based and stored uncompressed: charts are
+//! small and made of a few flat colours, so this keeps them well under the
+//! Discord attachment limit.
+
+const WIDTH: usize = 640;
+const HEIGHT: usize = 320;
+const MARGIN: usize = 20;
+/// Space left of the plot for the axis values
+const LEFT_MARGIN: usize = 48;
+/// Space under the plot for the group labels
+const BOTTOM_MARGIN: usize = 32;
+
+const BACKGROUND: u8 = 0;
+const AXIS: u8 = 1;
+const GRID: u8 = 2;
+const FIRST_SERIES: u8 = 3;
+
+/// Palette of the charts. The series colours come after the background,
+/// axis and grid colours.
+const PALETTE: [[u8; 3]; 5] = [
+    [0x2f, 0x31, 0x36], // background
+    [0xdc, 0xdd, 0xde], // axis
+    [0x40, 0x44, 0x4b], // grid
+    [0xed, 0x42, 0x45], // first series, red
+    [0x57, 0xf2, 0x87], // second series, green
+];
+
+/// Size of a font pixel in image pixels
+const FONT_SCALE: usize = 2;
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+
+/// 3x5 bitmap glyph of a character, one row per byte from the top, with the
+/// leftmost pixel in the highest of the 3 bits. Only digits and a few
+/// separators are used in the labels.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        _ => [0; GLYPH_HEIGHT],
+    }
+}
+
+/// Width of a text in image pixels, with one font pixel between characters
+fn text_width(text: &str) -> usize {
+    let len = text.chars().count();
+    (len * (GLYPH_WIDTH + 1)).saturating_sub(1) * FONT_SCALE
+}
+
+struct Canvas {
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn fill(&mut self, x0: usize, y0: usize, x1: usize, y1: usize, colour: u8) {
+        for y in y0..y1.min(HEIGHT) {
+            for x in x0..x1.min(WIDTH) {
+                self.pixels[y * WIDTH + x] = colour;
+            }
+        }
+    }
+
+    /// Draws a text with its top left corner at the given position
+    fn text(&mut self, x: usize, y: usize, text: &str, colour: u8) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i * (GLYPH_WIDTH + 1) * FONT_SCALE;
+            for (row, bits) in glyph(c).into_iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        let px = left + col * FONT_SCALE;
+                        let py = y + row * FONT_SCALE;
+                        self.fill(px, py, px + FONT_SCALE, py + FONT_SCALE, colour);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draws a bar chart with one group of two bars per entry, such as bugs
+/// opened and resolved per week, each group labelled under the x axis.
+/// Returns the PNG file.
+///
+/// Labels are drawn with a built-in digit font, and some of them are skipped
+/// when there is not enough room for all of them.
+pub fn grouped_bar_chart(groups: &[(&str, u32, u32)]) -> Vec<u8> {
+    const GRID_LINES: usize = 4;
+
+    let mut canvas = Canvas {
+        pixels: vec![BACKGROUND; WIDTH * HEIGHT],
+    };
+
+    let plot_height = HEIGHT - MARGIN - BOTTOM_MARGIN;
+    let plot_width = WIDTH - LEFT_MARGIN - MARGIN;
+    let bottom = HEIGHT - BOTTOM_MARGIN;
+
+    // round the scale up so that every grid line has a whole value
+    let max = groups
+        .iter()
+        .map(|&(_, a, b)| a.max(b))
+        .max()
+        .unwrap_or_default()
+        .max(1) as usize;
+    let max = max.div_ceil(GRID_LINES) * GRID_LINES;
+
+    let label_height = GLYPH_HEIGHT * FONT_SCALE;
+    for i in 0..=GRID_LINES {
+        let y = bottom - plot_height * i / GRID_LINES;
+        if i != 0 {
+            canvas.fill(LEFT_MARGIN, y, WIDTH - MARGIN, y + 1, GRID);
+        }
+        let value = (max * i / GRID_LINES).to_string();
+        canvas.text(
+            LEFT_MARGIN - 8 - text_width(&value),
+            y - label_height / 2,
+            &value,
+            AXIS,
+        );
+    }
+
+    if !groups.is_empty() {
+        let group_width = plot_width / groups.len();
+        let bar_width = (group_width * 2 / 5).max(1);
+        let padding = group_width.saturating_sub(2 * bar_width) / 2;
+
+        let widest_label = groups
+            .iter()
+            .map(|(label, _, _)| text_width(label) + 4 * FONT_SCALE)
+            .max()
+            .unwrap_or_default();
+        let label_step = widest_label.div_ceil(group_width.max(1)).max(1);
+        // keep the latest group labelled
+        let first_label = (groups.len() - 1) % label_step;
+
+        for (i, &(label, a, b)) in groups.iter().enumerate() {
+            let x = LEFT_MARGIN + i * group_width + padding;
+            for (j, value) in [a, b].into_iter().enumerate() {
+                let height = plot_height * value as usize / max;
+                let x = x + j * bar_width;
+                canvas.fill(
+                    x,
+                    bottom - height,
+                    x + bar_width,
+                    bottom,
+                    FIRST_SERIES + j as u8,
+                );
+            }
+
+            if i % label_step == first_label {
+                let centre = LEFT_MARGIN + i * group_width + group_width / 2;
+                let x = centre
+                    .saturating_sub(text_width(label) / 2)
+                    .min(WIDTH - text_width(label));
+                canvas.text(x, bottom + 10, label, AXIS);
+            }
+        }
+    }
+
+    canvas.fill(LEFT_MARGIN, MARGIN, LEFT_MARGIN + 1, bottom, AXIS);
+    canvas.fill(LEFT_MARGIN, bottom, WIDTH - MARGIN, bottom + 1, AXIS);
+
+    encode_png(WIDTH, HEIGHT, &PALETTE, &canvas.pixels)
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Encodes an 8-bit palette image, with stored (uncompressed) deflate blocks
+fn encode_png(width: usize, height: usize, palette: &[[u8; 3]], pixels: &[u8]) -> Vec<u8> {
+    let mut raw = Vec::with_capacity((width + 1) * height);
+    for row in pixels.chunks(width) {
+        // filter type: none
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut zlib = vec![0x78, 0x01];
+    let blocks = raw.chunks(u16::MAX as usize);
+    let count = blocks.len();
+    for (i, block) in blocks.enumerate() {
+        zlib.push((i + 1 == count) as u8);
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // bit depth 8, palette colour type, default compression, filter and interlace
+    header.extend_from_slice(&[8, 3, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"PLTE", &palette.concat());
+    write_chunk(&mut png, b"IDAT", &zlib);
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png_encoding() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+
+        let png = grouped_bar_chart(&[("06/05", 3, 1), ("13/05", 0, 2)]);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(text_width("1"), 6);
+        assert_eq!(text_width("06/05"), 38);
+        assert_eq!(glyph('8')[1], 0b101);
+        assert_eq!(glyph('x'), [0; GLYPH_HEIGHT]);
+
+        // many groups only label some of them, up to the latest
+        let weeks = (0..26)
+            .map(|i| format!("{:02}/01", i + 1))
+            .collect::<Vec<_>>();
+        let groups = weeks
+            .iter()
+            .map(|week| (week.as_str(), 10, 5))
+            .collect::<Vec<_>>();
+        assert!(grouped_bar_chart(&groups).starts_with(b"\x89PNG"));
+        assert!(grouped_bar_chart(&[]).starts_with(b"\x89PNG"));
+    }
+}
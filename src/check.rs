@@ -11,6 +11,9 @@
 //! [`is_admin`] checks wether the user is either the owner, a bot admin,
 //! or has the [`struct@MANAGE_BOT_PERMS`] permissions.
 //!
+//! [`is_bugtracker`] checks wether the guild has enabled its
+//! [bugtracker][crate::database::bug_trackers]. The owner bypasses it.
+//!
 //! [`is_minecraft_server`] checks wether there is a server IP registered
 //! with the guild. It fails if there is none, but is bypassed by bot
 //! admins.
@@ -29,7 +32,9 @@ use serenity::model::prelude::*;
 use serenity::prelude::*;
 
 use crate::constants::{LOTR_DISCORD, MANAGE_BOT_PERMS, OWNER_ID};
-use crate::database::{blacklist::check_blacklist, config::get_minecraft_ip};
+use crate::database::{
+    blacklist::check_blacklist, bug_trackers::get_bug_tracker, config::get_minecraft_ip,
+};
 use crate::is_admin;
 use crate::utils::has_permission;
 
@@ -162,6 +167,29 @@ pub async fn is_lotr_discord(_: &Context, msg: &Message) -> Result<(), Reason> {
     }
 }
 
+#[check]
+#[name = "is_bugtracker"]
+pub async fn is_bugtracker(ctx: &Context, msg: &Message) -> Result<(), Reason> {
+    if msg.author.id == OWNER_ID {
+        return Ok(());
+    }
+    let Some(server_id) = msg.guild_id else {
+        return Err(Reason::Log(
+            "Tried to use a bugtracker command outside a guild".into(),
+        ));
+    };
+    if get_bug_tracker(ctx, server_id)
+        .await
+        .map_or(false, |tracker| tracker.enabled)
+    {
+        Ok(())
+    } else {
+        Err(Reason::Log(
+            "Tried to use a bugtracker command in a guild without bugtracker".into(),
+        ))
+    }
+}
+
 #[hook]
 pub async fn dispatch_error_hook(
     ctx: &Context,
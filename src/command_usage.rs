@@ -0,0 +1,114 @@
+//! Usage counters of the custom commands. Uses are counted in memory and
+//! written to the database in batches by a background task.
+
+use chrono::Utc;
+use dashmap::DashMap;
+use serenity::client::Context;
+use serenity::model::id::GuildId;
+use serenity::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::database::command_usage::{add_usage, UsageKey};
+use crate::database::DatabasePool;
+
+/// How often the usage counters are written to the database
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone)]
+pub struct CommandUsage(Arc<DashMap<UsageKey, u32>>);
+
+impl TypeMapKey for CommandUsage {
+    type Value = Self;
+}
+
+impl std::ops::Deref for CommandUsage {
+    type Target = DashMap<UsageKey, u32>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Default for CommandUsage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandUsage {
+    pub fn new() -> Self {
+        Self(Arc::new(DashMap::new()))
+    }
+
+    fn add(&self, key: UsageKey, uses: u32) {
+        *self.entry(key).or_insert(0) += uses;
+    }
+
+    /// Removes and returns the pending counts
+    fn take(&self) -> Vec<(UsageKey, u32)> {
+        let keys: Vec<UsageKey> = self.iter().map(|entry| entry.key().clone()).collect();
+        keys.into_iter()
+            .filter_map(|key| self.remove(&key))
+            .collect()
+    }
+
+    /// Writes the pending counts to the database. They are kept for the next
+    /// flush if the database cannot be reached.
+    pub async fn flush(&self, pool: &DatabasePool) -> Result<(), mysql_async::Error> {
+        let counts = self.take();
+        if counts.is_empty() {
+            return Ok(());
+        }
+
+        let res = match pool.get_conn().await {
+            Ok(mut conn) => add_usage(&mut conn, &counts).await,
+            Err(e) => Err(e),
+        };
+        if res.is_err() {
+            for (key, uses) in counts {
+                self.add(key, uses);
+            }
+        }
+        res
+    }
+}
+
+/// Counts a successful use of a custom command
+pub async fn record_use(ctx: &Context, server_id: GuildId, name: &str) {
+    if let Some(usage) = ctx.data.read().await.get::<CommandUsage>() {
+        usage.add(
+            UsageKey {
+                server_id,
+                name: name.to_string(),
+                day: Utc::now().date_naive(),
+            },
+            1,
+        );
+    }
+}
+
+/// Writes the pending counts to the database, for example before shutting down
+pub async fn flush_usage(data: &RwLock<TypeMap>) {
+    let (usage, pool) = {
+        let data_read = data.read().await;
+        (
+            data_read.get::<CommandUsage>().cloned(),
+            data_read.get::<DatabasePool>().cloned(),
+        )
+    };
+    if let (Some(usage), Some(pool)) = (usage, pool) {
+        if let Err(e) = usage.flush(&pool).await {
+            println!("=== ERROR ===\nCould not save custom command usage: {e}\n=== END ===");
+        }
+    }
+}
+
+/// Flushes the usage counters at a regular interval. Never returns.
+pub async fn flush_task(data: Arc<RwLock<TypeMap>>) {
+    let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+    loop {
+        interval.tick().await;
+        flush_usage(&data).await;
+    }
+}
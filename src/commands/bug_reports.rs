@@ -2,6 +2,7 @@ use serenity::builder::{CreateComponents, CreateEmbed};
 use serenity::client::Context;
 use serenity::collector::CollectComponentInteraction;
 use serenity::framework::standard::{macros::command, Args, CommandResult};
+use serenity::http::CacheHttp;
 use serenity::model::application::{
     component::ButtonStyle,
     interaction::{
@@ -10,17 +11,47 @@ use serenity::model::application::{
 };
 use serenity::model::prelude::*;
 use serenity::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use crate::check::*;
-use crate::constants::{LOTR_DISCORD, MANAGE_BOT_PERMS, OWNER_ID};
+use crate::constants::{
+    BIT_FILTER_24BITS, MANAGE_BOT_PERMS, MAX_ARCHIVED_ATTACHMENT_SIZE, MAX_DELIVERY_FAILURES,
+    OWNER_ID,
+};
 use crate::database::admin_data::is_admin_function;
+use crate::database::bug_digest::{delete_digest_config, get_digest_config, set_digest_config};
 use crate::database::bug_reports::{
-    add_bug_report, add_link, add_notified_user, change_bug_status, change_category, change_title,
-    get_bug_from_id, get_bug_list, get_bug_statistics, get_notifications_for_user,
-    get_notified_users, is_notified_user, remove_link, BugCategory, BugOrder, BugReport, BugStatus,
+    add_assignee, add_bug_report, add_label, add_link, add_missing_snapshot, add_notified_user,
+    add_pending_notification, change_bug_status, change_bug_statuses, change_category,
+    change_field, change_title, export_bugs, get_archived_attachments, get_assignees_of,
+    get_bug_from_id, get_bug_history, get_bug_list, get_bug_statistics, get_bugs_without_snapshot,
+    get_delivery_failures, get_notification_mode, get_notification_modes,
+    get_notifications_for_user, get_notified_users, import_bugs, is_assignee, is_notified_user,
+    merge_duplicate, record_delivery, remove_assignee, remove_label, remove_link, search_bugs,
+    set_bug_thread, set_notification_mode, toggle_vote, BugCategory, BugCounts, BugExport,
+    BugField, BugFilter, BugHistoryEntry, BugOrder, BugReport, Delivery, ExportedBug,
+    NotificationMode, StatusChange,
+};
+use crate::database::bug_statistics::{get_time_statistics, BugTimeStatistics};
+use crate::database::bug_statuses::{
+    get_status_workflow, parse_status_name, remove_bug_status, set_bug_status, BugStatus,
+    StatusWorkflow, CLOSED, RESOLVED,
+};
+use crate::database::bug_submissions::{
+    add_submission, claim_submission, get_submissions, release_submission, remove_submission,
+    set_queue_message, BugSubmission,
+};
+use crate::database::bug_trackers::{
+    get_bug_guild, get_bug_prefix, get_bug_tracker, parse_prefix, set_bug_tracker, BugTracker,
 };
-use crate::failure;
+use crate::database::issue_sync::{
+    add_bug_issue, delete_issue_sync_url, get_bug_issue, get_issue_sync_url, set_issue_sync_url,
+    SyncState,
+};
+use crate::issue_sync::{backend_for, is_allowed_api_url, Issue, IssueLink};
+use crate::utils::{get_json_from_message, InteractionEasyResponse, NotInGuild};
+use crate::{failure, get_reqwest_client, handle_json_error};
 
 pub const TERMITE_EMOJI: EmojiId = EmojiId(938135367486410792);
 
@@ -51,20 +82,122 @@ macro_rules! termite_success {
     }};
 }
 
+/// Parses a bug id, with or without its bugtracker prefix
+fn parse_bug_id(bug_id: &str) -> Option<u64> {
+    bug_id
+        .rsplit_once('-')
+        .map_or(bug_id, |(_, id)| id)
+        .parse::<u64>()
+        .ok()
+}
+
+/// Bugtracker of a guild, with the default settings if it cannot be fetched
+async fn guild_tracker(ctx: &Context, server_id: GuildId) -> BugTracker {
+    get_bug_tracker(ctx, server_id)
+        .await
+        .unwrap_or_else(|| BugTracker::default_for(server_id))
+}
+
+/// Name of a bugtracker in embeds and notifications, from the name of its guild
+pub async fn tracker_name(cache_http: impl CacheHttp, server_id: GuildId) -> String {
+    let name = match cache_http.cache().and_then(|cache| server_id.name(cache)) {
+        Some(name) => Some(name),
+        None => server_id
+            .to_partial_guild(cache_http.http())
+            .await
+            .ok()
+            .map(|guild| guild.name),
+    };
+    match name {
+        Some(name) => format!("{name} Bugtracker"),
+        None => "Bugtracker".into(),
+    }
+}
+
+/// Bugtracker of the guild the command is used in. Only for commands marked
+/// with `#[only_in(guilds)]`.
+async fn current_tracker(ctx: &Context, msg: &Message) -> BugTracker {
+    guild_tracker(ctx, msg.guild_id.expect("Expected a guild only command")).await
+}
+
+/// Bugtracker a command about a bug applies to: the one of the guild it is
+/// used in, or outside of guilds the one the bug was tracked in. `None` if
+/// the bug does not exist.
+async fn tracker_of_bug(ctx: &Context, msg: &Message, bug_id: u64) -> Option<BugTracker> {
+    let server_id = match msg.guild_id {
+        Some(server_id) => server_id,
+        None => get_bug_guild(ctx, bug_id).await?,
+    };
+    Some(guild_tracker(ctx, server_id).await)
+}
+
+/// Gets the status workflow of a bugtracker
+async fn tracker_workflow(ctx: &Context, tracker: &BugTracker) -> StatusWorkflow {
+    get_status_workflow(ctx, tracker.server_id)
+        .await
+        .unwrap_or_default()
+}
+
+/// Checks that a bug was tracked in the given bugtracker
+async fn is_tracked_in(ctx: &Context, tracker: &BugTracker, bug_id: u64) -> bool {
+    get_bug_guild(ctx, bug_id).await == Some(tracker.server_id)
+}
+
+/// Normalizes a label to lowercase, returning `None` if it is not a valid label
+fn parse_label(label: &str) -> Option<String> {
+    let label = label.trim().to_lowercase();
+    if label.is_empty()
+        || label.len() > 32
+        || !label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        None
+    } else {
+        Some(label)
+    }
+}
+
+/// Fetches the message a bug was reported from, if it still exists. Imported
+/// bugs without a message are stored with a zero message id.
+async fn fetch_reported_message(ctx: &Context, bug: &BugReport) -> Option<Message> {
+    if bug.message_id.0 == 0 {
+        return None;
+    }
+    let mut message = bug.channel_id.message(ctx, bug.message_id).await.ok()?;
+    message.guild_id = Some(bug.guild_id);
+    Some(message)
+}
+
+/// Creates the embed of a bug report. When the reported message is unavailable,
+/// `archived_image` is the filename of an archived image uploaded with the embed.
 fn create_bug_embed<'a>(
     bug: &'a BugReport,
+    tracker_name: &'a str,
     linked_message: Option<&'a Message>,
+    archived_image: Option<&'a str>,
 ) -> impl Fn(&mut CreateEmbed) -> &mut CreateEmbed + 'a {
+    let duplicate = if let Some(original_id) = bug.duplicate_of {
+        format!(" • Duplicate of {}-{original_id}", bug.prefix)
+    } else {
+        String::new()
+    };
+    let labels = if bug.labels.is_empty() {
+        String::new()
+    } else {
+        format!(" • Labels: {}", bug.labels.join(", "))
+    };
     move |e| {
         e.author(|a| {
-            a.name("LOTR Mod Bugtracker");
+            a.name(tracker_name);
             a.icon_url(crate::constants::TERMITE_IMAGE);
             a
         });
         e.colour(bug.status.colour());
         e.title(format!(
-            "{} LOTR-{}: {} [{}]",
+            "{} {}-{}: {} [{}]",
             bug.status.marker(),
+            bug.prefix,
             bug.bug_id,
             bug.title,
             bug.category
@@ -76,12 +209,54 @@ fn create_bug_embed<'a>(
             }
             e.footer(|f| {
                 f.text(format!(
-                    "Status: {} • Submitted by {}",
-                    bug.status, &message.author.name
+                    "Status: {}{}{} • Submitted by {}",
+                    bug.status, duplicate, labels, &message.author.name
+                ))
+            });
+        } else if let Some(snapshot) = &bug.snapshot {
+            e.description(&snapshot.content);
+            // the original attachment urls are gone with the message
+            if let Some(filename) = archived_image {
+                e.image(format!("attachment://{filename}"));
+            }
+            e.footer(|f| {
+                f.text(format!(
+                    "Status: {}{}{} • Submitted by {} (original message unavailable)",
+                    bug.status, duplicate, labels, &snapshot.author_name
                 ))
             });
         } else {
-            e.footer(|f| f.text(format!("Status: {}", bug.status)));
+            e.footer(|f| f.text(format!("Status: {}{}{}", bug.status, duplicate, labels)));
+        }
+        for field in BugField::ALL {
+            if let Some(value) = bug.details.get(field) {
+                e.field(field, value, field.is_short());
+            }
+        }
+        if let Some(thread_id) = bug.thread_id {
+            e.field("Discussion", thread_id.mention(), true);
+        }
+        if bug.votes != 0 {
+            e.field(
+                "Affects",
+                format!(
+                    "{} player{}",
+                    bug.votes,
+                    if bug.votes == 1 { "" } else { "s" }
+                ),
+                true,
+            );
+        }
+        if !bug.assignees.is_empty() {
+            e.field(
+                "Assigned to",
+                bug.assignees
+                    .iter()
+                    .map(|user_id| user_id.mention().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                false,
+            );
         }
         if !bug.links.is_empty() {
             e.field(
@@ -99,12 +274,49 @@ fn create_bug_embed<'a>(
     }
 }
 
+/// Checks that a file is an archived image of the reported message of a bug
+fn is_archived_image(bug: &BugReport, filename: &str) -> bool {
+    bug.snapshot.as_ref().map_or(false, |snapshot| {
+        snapshot
+            .attachments
+            .iter()
+            .any(|a| a.is_image() && a.filename == filename)
+    })
+}
+
+/// Records the outcome of a notification DM, and unsubscribes the users
+/// that could not be reached too many times in a row
+async fn check_delivery<T>(
+    ctx: &Context,
+    user: UserId,
+    result: Result<T, SerenityError>,
+) -> CommandResult {
+    if record_delivery(ctx, user, &Delivery::from_result(&result)).await? {
+        println!(
+            "Unsubscribed {user} from all bugs after {MAX_DELIVERY_FAILURES} failed notifications"
+        );
+    }
+    result?;
+    Ok(())
+}
+
 pub async fn notify_users(
     ctx: &Context,
     bug_id: u64,
     message: impl std::fmt::Display,
 ) -> CommandResult {
     let notified_users = get_notified_users(ctx, bug_id).await?;
+    notify_subscribers(ctx, bug_id, notified_users, message).await
+}
+
+/// Sends a notification about a bug to the given users, according to their
+/// notification settings
+async fn notify_subscribers(
+    ctx: &Context,
+    bug_id: u64,
+    notified_users: Vec<UserId>,
+    message: impl std::fmt::Display,
+) -> CommandResult {
     if notified_users.is_empty() {
         return Ok(());
     }
@@ -112,31 +324,43 @@ pub async fn notify_users(
     let bug = get_bug_from_id(ctx, bug_id).await?;
 
     let mut res = Ok(());
-    let linked_message = bug
-        .channel_id
-        .message(ctx, bug.message_id)
-        .await
-        .map(|mut m| {
-            m.guild_id = Some(LOTR_DISCORD);
-            m
-        });
-    let message_link = linked_message.as_ref().map(Message::link).ok();
+    let linked_message = fetch_reported_message(ctx, &bug).await;
+    let message_link = linked_message.as_ref().map(Message::link);
+    let name = tracker_name(ctx, bug.guild_id).await;
+
+    let modes = get_notification_modes(ctx, &notified_users).await;
+    let message = message.to_string();
 
     for user in notified_users {
+        match modes.get(&user).copied().unwrap_or_default() {
+            NotificationMode::Immediate => (),
+            NotificationMode::Daily => {
+                if let Err(e) = add_pending_notification(ctx, user, bug_id, &message).await {
+                    if res.is_ok() {
+                        res = Err(e);
+                    }
+                }
+                continue;
+            }
+            NotificationMode::Muted => continue,
+        }
+
         let channel = match user.create_dm_channel(ctx).await {
             Ok(channel) => channel,
             Err(e) => {
-                if res.is_ok() {
-                    res = Err(e.into());
+                if let Err(e) = check_delivery(ctx, user, Err::<(), _>(e)).await {
+                    if res.is_ok() {
+                        res = Err(e);
+                    }
                 }
                 continue;
             }
         };
 
-        if let Err(e) = channel
+        let sent = channel
             .send_message(ctx, |m| {
                 m.content(format!(
-                    "**LOTR Mod Bugtracker notification {}**\n{}\n\u{00a0}",
+                    "**{name} notification {}**\n{}\n\u{00a0}",
                     ReactionType::from(EmojiIdentifier {
                         animated: false,
                         id: TERMITE_EMOJI,
@@ -144,7 +368,7 @@ pub async fn notify_users(
                     }),
                     message,
                 ))
-                .embed(create_bug_embed(&bug, linked_message.as_ref().ok()))
+                .embed(create_bug_embed(&bug, &name, linked_message.as_ref(), None))
                 .components(|c| {
                     c.create_action_row(|a| {
                         if let Some(link) = message_link.as_ref() {
@@ -162,10 +386,10 @@ pub async fn notify_users(
                     })
                 })
             })
-            .await
-        {
+            .await;
+        if let Err(e) = check_delivery(ctx, user, sent).await {
             if res.is_ok() {
-                res = Err(e.into());
+                res = Err(e);
             }
         }
     }
@@ -174,11 +398,25 @@ pub async fn notify_users(
 }
 
 #[command]
-#[checks(is_admin, is_lotr_discord)]
+#[checks(is_admin, is_bugtracker)]
+#[only_in(guilds)]
 #[aliases(report)]
 pub async fn track(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    let create_thread = args.current() == Some("thread");
+    if create_thread {
+        args.advance();
+    }
     let category = args.single::<BugCategory>().unwrap_or_default();
-    let status = args.single::<BugStatus>().unwrap_or_default();
+    let workflow = tracker_workflow(ctx, &tracker).await;
+    let status = match args.current().and_then(|s| workflow.parse(s)) {
+        Some(status) => {
+            args.advance();
+            status
+        }
+        None => workflow.default_status(),
+    };
 
     let title = args.rest();
     if title.is_empty() {
@@ -193,19 +431,27 @@ pub async fn track(ctx: &Context, msg: &Message, mut args: Args) -> CommandResul
         return Ok(());
     };
 
-    let bug_id =
-        match add_bug_report(ctx, referenced_message, title.to_string(), status, category).await {
-            Ok(bug_id) => bug_id,
-            Err(e) => {
-                failure!(ctx, msg, "Could not submit the bug report!");
-                return Err(e);
-            }
-        };
+    let bug_id = match add_bug_report(
+        ctx,
+        tracker.server_id,
+        referenced_message,
+        title.to_string(),
+        status.clone(),
+        category,
+    )
+    .await
+    {
+        Ok(bug_id) => bug_id,
+        Err(e) => {
+            failure!(ctx, msg, "Could not submit the bug report!");
+            return Err(e);
+        }
+    };
 
     msg.channel_id
         .send_message(ctx, |m| {
             m.content(format!(
-                "Tracking bug LOTR-{bug_id} (priority: `{status}`, edition: {category})"
+                "Tracking bug {prefix}-{bug_id} (priority: `{status}`, edition: {category})"
             ))
             .reference_message(referenced_message)
             .allowed_mentions(|f| f.empty_parse())
@@ -221,10 +467,45 @@ pub async fn track(ctx: &Context, msg: &Message, mut args: Args) -> CommandResul
         })
         .await?;
 
+    if create_thread {
+        let thread_name = format!("{prefix}-{bug_id}: {title}")
+            .chars()
+            .take(100)
+            .collect::<String>();
+        match referenced_message
+            .channel_id
+            .create_public_thread(ctx, referenced_message.id, |t| {
+                t.name(thread_name).auto_archive_duration(10080)
+            })
+            .await
+        {
+            Ok(thread) => {
+                set_bug_thread(ctx, bug_id, thread.id).await?;
+                thread
+                    .say(
+                        ctx,
+                        format!(
+                            "Discussion thread for {prefix}-{bug_id}. \
+Status changes of this bug report will be posted here."
+                        ),
+                    )
+                    .await?;
+            }
+            Err(e) => {
+                println!("Could not create thread for bug {prefix}-{bug_id}: {e}");
+                failure!(
+                    ctx,
+                    msg,
+                    "Could not create a discussion thread for this bug report."
+                );
+            }
+        }
+    }
+
     if let Err(e) = add_notified_user(ctx, bug_id, referenced_message.author.id).await {
         println!(
             "=== ERROR ===
-Could not subscribe bug author to bug LOTR-{bug_id}
+Could not subscribe bug author to bug {prefix}-{bug_id}
 Error: {e}
 === END ==="
         );
@@ -234,92 +515,507 @@ Error: {e}
     notify_users(
         ctx,
         bug_id,
-        "A bug report you submitted is being tracked in the LOTR Mod bugtracker.
+        "A bug report you submitted is being tracked in the bugtracker.
 You will receive notifications when its status is changed or further information is added.",
     )
     .await
 }
 
-enum Either<'a> {
-    Message(&'a Message),
-    Interaction(&'a MessageComponentInteraction),
-}
+#[command]
+#[checks(is_bugtracker)]
+#[only_in(guilds)]
+#[aliases(submit, propose)]
+pub async fn bug_submit(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+    let category = args.single::<BugCategory>().unwrap_or_default();
 
-impl Either<'_> {
-    async fn failure(&self, ctx: &Context, message: &str) -> Result<(), SerenityError> {
-        match self {
-            Either::Message(msg) => failure!(ctx, msg, message),
-            Either::Interaction(interaction) => {
-                interaction
-                    .create_interaction_response(ctx, |r| {
-                        r.kind(InteractionResponseType::ChannelMessageWithSource)
-                            .interaction_response_data(|d| {
-                                d.flags(MessageFlags::EPHEMERAL).content(message)
-                            })
-                    })
-                    .await?;
-            }
-        }
-        Ok(())
+    let title = args.rest();
+    if title.is_empty() {
+        failure!(ctx, msg, "You must provide a title for the bug report!");
+        return Ok(());
+    }
+    if title.len() > 255 {
+        failure!(
+            ctx,
+            msg,
+            "The title of a bug report must be under 255 characters."
+        );
+        return Ok(());
     }
+
+    // the bug is described by the referenced message, or by the command itself
+    let reported_message = msg.referenced_message.as_deref().unwrap_or(msg);
+
+    let submission_id = match add_submission(
+        ctx,
+        server_id,
+        reported_message,
+        msg.author.id,
+        title,
+        category,
+    )
+    .await
+    {
+        Ok(submission_id) => submission_id,
+        Err(e) => {
+            failure!(ctx, msg, "Could not submit the bug report!");
+            return Err(e);
+        }
+    };
+
+    let queue_message = msg
+        .channel_id
+        .send_message(ctx, |m| {
+            m.content(format!(
+                "{} proposed a bug report: **{title}** (edition: {category})
+An admin can accept it into the bugtracker, or reject it.",
+                msg.author.mention()
+            ))
+            .reference_message(reported_message)
+            .allowed_mentions(|f| f.empty_parse())
+            .components(|c| {
+                c.create_action_row(|a| {
+                    a.create_button(|b| {
+                        b.style(ButtonStyle::Success)
+                            .label("Accept")
+                            .custom_id(format!("bug_accept__{submission_id}"))
+                    })
+                    .create_button(|b| {
+                        b.style(ButtonStyle::Danger)
+                            .label("Reject")
+                            .custom_id(format!("bug_reject__{submission_id}"))
+                    })
+                })
+            })
+        })
+        .await?;
+
+    set_queue_message(ctx, submission_id, queue_message.id).await
 }
 
-fn create_buttons(
-    disable_previous: bool,
-    disable_next: bool,
-) -> impl Fn(&mut CreateComponents) -> &mut CreateComponents {
-    move |c| {
-        c.create_action_row(|a| {
-            a.create_button(|b| {
-                b.style(ButtonStyle::Secondary);
-                b.label("Previous");
-                b.custom_id("previous_page");
-                b.emoji(ReactionType::Unicode("⬅️".into()));
-                b.disabled(disable_previous);
-                b
-            });
-            a.create_button(|b| {
-                b.style(ButtonStyle::Secondary);
-                b.label("Next");
-                b.custom_id("next_page");
-                b.emoji(ReactionType::Unicode("➡️".into()));
-                b.disabled(disable_next);
-                b
-            });
-            a
-        });
-        c
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(queue, submissions)]
+pub async fn bug_queue(ctx: &Context, msg: &Message) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+    let submissions = get_submissions(ctx, server_id).await.unwrap_or_default();
+
+    if submissions.is_empty() {
+        msg.reply(ctx, "There are no proposed bug reports to review.")
+            .await?;
+        return Ok(());
     }
+
+    let total = submissions.len();
+    let list = submissions
+        .iter()
+        .take(20)
+        .map(|submission| {
+            let link = submission
+                .queue_message_id
+                .unwrap_or(submission.message_id)
+                .link(submission.channel_id, Some(server_id));
+            format!(
+                "[#{}]({link}) **{}** — by <@{}>, {}",
+                submission.submission_id,
+                submission.title,
+                submission.submitter_id,
+                submission.timestamp.format("%d/%m/%Y")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                e.title("Proposed bug reports")
+                    .description(list)
+                    .footer(|f| {
+                        f.text(format!(
+                            "{total} pending, oldest first. Use the buttons of each proposal to review it."
+                        ))
+                    })
+                    .colour(serenity::utils::Colour::ORANGE)
+            })
+            .reference_message(msg)
+        })
+        .await?;
+
+    Ok(())
 }
 
-async fn display_bugs(
+/// Accepts or rejects a proposed bug report, from the buttons posted by `!bug submit`
+pub async fn review_submission(
     ctx: &Context,
-    status: Option<BugStatus>,
-    limit: u32,
-    display_order: BugOrder,
-    category: Option<BugCategory>,
-    page: u32,
-    reply_to: Either<'_>,
-) -> Result<Option<Message>, SerenityError> {
-    assert_ne!(page, 0);
+    interaction: &MessageComponentInteraction,
+    submission_id: u64,
+    accept: bool,
+) -> CommandResult {
+    let Some(server_id) = interaction.guild_id else {
+        return Ok(());
+    };
+    let user = &interaction.user;
+    let is_admin = user.id == OWNER_ID
+        || is_admin_function(ctx, server_id, user.id)
+            .await
+            .unwrap_or_default()
+        || crate::utils::has_permission(ctx, server_id, user.id, MANAGE_BOT_PERMS).await;
+    if !is_admin {
+        interaction
+            .say_ephemeral(ctx, ":x: Only admins can review proposed bug reports.")
+            .await;
+        return Ok(());
+    }
 
-    if let Some((bugs, total_bugs)) =
-        get_bug_list(ctx, status, limit, display_order, category, page - 1).await
-    {
-        if total_bugs != 0 && (page - 1) * limit >= total_bugs {
-            reply_to.failure(ctx, "Page number too high, consider calling `!bugs` and using the navigation arrows.").await?;
-            return Err(SerenityError::Other("page_too_high"));
+    let Some(submission) = claim_submission(ctx, submission_id).await? else {
+        interaction
+            .say_ephemeral(
+                ctx,
+                ":x: This bug report has already been reviewed, or is being reviewed.",
+            )
+            .await;
+        return Ok(());
+    };
+
+    let res = handle_submission(ctx, interaction, &submission, accept).await;
+    if res.is_err() {
+        // the submission is only removed once it has been handled
+        if let Err(e) = release_submission(ctx, submission_id).await {
+            println!("Could not release submission {submission_id}: {e}");
         }
+    }
+    res
+}
 
-        let title;
-        let content_alt;
-        let content;
-        let colour;
-        if let Some(status) = status {
-            title = format!(
-                "{} Bug reports (Status: {}){} (Total: {})",
-                status.marker(),
-                status,
+async fn handle_submission(
+    ctx: &Context,
+    interaction: &MessageComponentInteraction,
+    submission: &BugSubmission,
+    accept: bool,
+) -> CommandResult {
+    let user = &interaction.user;
+    let submission_id = submission.submission_id;
+    let server_id = submission.guild_id;
+
+    if !accept {
+        remove_submission(ctx, submission_id).await?;
+        interaction
+            .create_interaction_response(ctx, |r| {
+                r.kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|d| {
+                        d.content(format!(
+                            "Proposed bug report **{}** rejected by {}.",
+                            submission.title,
+                            user.mention()
+                        ))
+                        .allowed_mentions(|f| f.empty_parse())
+                        .components(|c| c)
+                    })
+            })
+            .await?;
+        let notified = match submission.submitter_id.create_dm_channel(ctx).await {
+            Ok(channel) => channel
+                .say(
+                    ctx,
+                    format!(
+                        "Your proposed bug report **{}** was rejected by the moderators.",
+                        submission.title
+                    ),
+                )
+                .await
+                .map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = notified {
+            println!(
+                "Could not notify {:?} of rejected submission {submission_id}: {e}",
+                submission.submitter_id
+            );
+        }
+        return Ok(());
+    }
+
+    let reported_message = match submission
+        .channel_id
+        .message(ctx, submission.message_id)
+        .await
+    {
+        Ok(message) => message,
+        Err(e) => {
+            remove_submission(ctx, submission_id).await?;
+            interaction
+                .create_interaction_response(ctx, |r| {
+                    r.kind(InteractionResponseType::UpdateMessage)
+                        .interaction_response_data(|d| {
+                            d.content(format!(
+                                "Proposed bug report **{}** discarded: the reported message was deleted.",
+                                submission.title
+                            ))
+                            .components(|c| c)
+                        })
+                })
+                .await?;
+            println!("Could not get the message of submission {submission_id}: {e}");
+            return Ok(());
+        }
+    };
+
+    let tracker = get_bug_tracker(ctx, server_id)
+        .await
+        .unwrap_or_else(|| BugTracker::default_for(server_id));
+    let prefix = &tracker.prefix;
+    let status = tracker_workflow(ctx, &tracker).await.default_status();
+    let category = submission.category;
+
+    let bug_id = add_bug_report(
+        ctx,
+        server_id,
+        &reported_message,
+        submission.title.clone(),
+        status.clone(),
+        category,
+    )
+    .await?;
+    // the bug exists now: the submission must not be accepted again
+    if let Err(e) = remove_submission(ctx, submission_id).await {
+        println!("Could not remove accepted submission {submission_id}: {e}");
+    }
+
+    interaction
+        .create_interaction_response(ctx, |r| {
+            r.kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|d| {
+                    d.content(format!(
+                        "Tracking bug {prefix}-{bug_id} (priority: `{status}`, edition: {category}), \
+accepted by {}",
+                        user.mention()
+                    ))
+                    .allowed_mentions(|f| f.empty_parse())
+                    .components(|c| {
+                        c.create_action_row(|a| {
+                            a.create_button(|b| {
+                                b.style(ButtonStyle::Primary)
+                                    .label("Subscribe")
+                                    .custom_id(format!("bug_subscribe__{bug_id}"))
+                            })
+                        })
+                    })
+                })
+        })
+        .await?;
+
+    add_notified_user(ctx, bug_id, submission.submitter_id).await?;
+    if reported_message.author.id != submission.submitter_id {
+        add_notified_user(ctx, bug_id, reported_message.author.id).await?;
+    }
+
+    notify_users(
+        ctx,
+        bug_id,
+        "A bug report you submitted has been accepted into the bugtracker.
+You will receive notifications when its status is changed or further information is added.",
+    )
+    .await
+}
+
+/// Toggles the "affects me" vote of a user from the button of a bug embed,
+/// and updates the vote count in the embed
+pub async fn vote_for_bug(
+    ctx: &Context,
+    interaction: &MessageComponentInteraction,
+    bug_id: u64,
+) -> CommandResult {
+    let (voted, votes) = toggle_vote(ctx, bug_id, interaction.user.id).await?;
+    let bug = get_bug_from_id(ctx, bug_id).await?;
+    let prefix = &bug.prefix;
+
+    interaction
+        .say_ephemeral(
+            ctx,
+            if voted {
+                format!(
+                    "You are now counted as affected by bug {prefix}-{bug_id} ({votes} in total)."
+                )
+            } else {
+                format!("You are no longer counted as affected by bug {prefix}-{bug_id}.")
+            },
+        )
+        .await;
+
+    let linked_message = fetch_reported_message(ctx, &bug).await;
+    let name = tracker_name(ctx, bug.guild_id).await;
+    // archived images re-uploaded with the bug message are kept by the edit
+    let archived_image = interaction
+        .message
+        .attachments
+        .iter()
+        .find(|a| is_archived_image(&bug, &a.filename))
+        .map(|a| a.filename.clone());
+    interaction
+        .message
+        .clone()
+        .edit(ctx, |m| {
+            m.embed(create_bug_embed(
+                &bug,
+                &name,
+                linked_message.as_ref(),
+                archived_image.as_deref(),
+            ))
+        })
+        .await?;
+
+    Ok(())
+}
+
+enum Either<'a> {
+    Message(&'a Message),
+    Interaction(&'a MessageComponentInteraction),
+}
+
+impl Either<'_> {
+    async fn failure(&self, ctx: &Context, message: &str) -> Result<(), SerenityError> {
+        match self {
+            Either::Message(msg) => failure!(ctx, msg, message),
+            Either::Interaction(interaction) => {
+                interaction
+                    .create_interaction_response(ctx, |r| {
+                        r.kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|d| {
+                                d.flags(MessageFlags::EPHEMERAL).content(message)
+                            })
+                    })
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn create_buttons(
+    disable_previous: bool,
+    disable_next: bool,
+) -> impl Fn(&mut CreateComponents) -> &mut CreateComponents {
+    move |c| {
+        c.create_action_row(|a| {
+            a.create_button(|b| {
+                b.style(ButtonStyle::Secondary);
+                b.label("Previous");
+                b.custom_id("previous_page");
+                b.emoji(ReactionType::Unicode("⬅️".into()));
+                b.disabled(disable_previous);
+                b
+            });
+            a.create_button(|b| {
+                b.style(ButtonStyle::Secondary);
+                b.label("Next");
+                b.custom_id("next_page");
+                b.emoji(ReactionType::Unicode("➡️".into()));
+                b.disabled(disable_next);
+                b
+            });
+            a
+        });
+        c
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BugListOptions<'a> {
+    filter: BugFilter,
+    display_order: BugOrder,
+    limit: u32,
+    search: Option<&'a str>,
+}
+
+async fn display_bugs(
+    ctx: &Context,
+    options: &BugListOptions<'_>,
+    page: u32,
+    reply_to: Either<'_>,
+) -> Result<Option<Message>, SerenityError> {
+    assert_ne!(page, 0);
+
+    let BugListOptions {
+        filter,
+        display_order,
+        limit,
+        search,
+    } = options;
+    let limit = *limit;
+    let status = &filter.status;
+    let category = filter.category;
+
+    let bug_list = if let Some(terms) = search {
+        let server_id = filter
+            .guild_id
+            .expect("Expected bug searches to be scoped to a guild");
+        search_bugs(
+            ctx,
+            server_id,
+            terms,
+            status.clone(),
+            category,
+            limit,
+            page - 1,
+        )
+        .await
+    } else {
+        get_bug_list(ctx, filter, limit, *display_order, page - 1).await
+    };
+
+    if let Some((bugs, total_bugs)) = bug_list {
+        if total_bugs != 0 && (page - 1) * limit >= total_bugs {
+            reply_to.failure(ctx, "Page number too high, consider calling `!bugs` and using the navigation arrows.").await?;
+            return Err(SerenityError::Other("page_too_high"));
+        }
+
+        let title;
+        let content_alt;
+        let content;
+        let colour;
+        if let Some(terms) = search {
+            title = format!(
+                "Search results for \"{}\"{}{} (Total: {})",
+                terms,
+                if let Some(s) = status {
+                    format!(" (Status: {s})")
+                } else {
+                    String::new()
+                },
+                if let Some(c) = category {
+                    format!(" [{c}]")
+                } else {
+                    String::new()
+                },
+                total_bugs
+            );
+            content_alt = "_No bugs matching your search!_";
+            content = bugs
+                .iter()
+                .map(|b| {
+                    format!(
+                        "{} {}{}",
+                        b.status.marker(),
+                        b,
+                        if category.is_none() {
+                            format!(" [{}]", b.category)
+                        } else {
+                            String::new()
+                        }
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            colour = status
+                .as_ref()
+                .map_or(serenity::utils::Colour::LIGHT_GREY, BugStatus::colour);
+        } else if let Some(status) = status {
+            title = format!(
+                "{} Bug reports (Status: {}){} (Total: {})",
+                status.marker(),
+                status,
                 if let Some(c) = category {
                     format!(" [{c}]")
                 } else {
@@ -374,6 +1070,35 @@ async fn display_bugs(
             colour = serenity::utils::Colour::LIGHT_GREY;
         }
 
+        let content = if bugs.is_empty() {
+            content_alt.to_string()
+        } else {
+            content
+        };
+        let mut header = Vec::new();
+        if let Some(user_id) = filter.assignee {
+            header.push(format!("_Assigned to {}_", user_id.mention()));
+        }
+        if let Some(days) = filter.inactive_days {
+            header.push(format!("_Inactive for at least {days} days_"));
+        }
+        if !filter.labels.is_empty() {
+            header.push(format!(
+                "_Labelled {}_",
+                filter
+                    .labels
+                    .iter()
+                    .map(|l| format!("`{l}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        let content = if header.is_empty() {
+            content
+        } else {
+            format!("{}\n\n{}", header.join("\n"), content)
+        };
+
         if content.len() > 4096 {
             reply_to
                 .failure(
@@ -384,21 +1109,21 @@ async fn display_bugs(
             return Err(SerenityError::Other("too_many_bugs"));
         }
 
+        let name = match filter.guild_id {
+            Some(server_id) => tracker_name(ctx, server_id).await,
+            None => "Bugtracker".into(),
+        };
         macro_rules! create_embed_reponse {
             () => {
                 |e| {
                     e.author(|a| {
-                        a.name("LOTR Mod Bugtracker");
+                        a.name(&name);
                         a.icon_url(crate::constants::TERMITE_IMAGE);
                         a
                     });
                     e.colour(colour);
                     e.title(title);
-                    e.description(if bugs.is_empty() {
-                        content_alt
-                    } else {
-                        &content
-                    });
+                    e.description(&content);
                     e.footer(|f| {
                         f.text(format!(
                             "Page {}/{}",
@@ -444,47 +1169,47 @@ async fn display_bugs(
     }
 }
 
-#[command]
-#[aliases(bugs)]
-#[sub_commands(bugtracker_help)]
-pub async fn buglist(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let category = args.single::<BugCategory>().ok();
-    let status = args.single::<BugStatus>().ok();
+/// A list displayed one page at a time, with buttons to change the page
+enum PagedList<'a> {
+    Bugs(BugListOptions<'a>),
+    History {
+        tracker: &'a BugTracker,
+        bug_id: u64,
+    },
+}
 
-    let mut display_order = match args.current() {
-        Some("latest") => BugOrder::Chronological(false),
-        Some("oldest") => BugOrder::Chronological(true),
-        Some("highest") => BugOrder::Priority(false),
-        Some("lowest") => BugOrder::Priority(true),
-        _ => BugOrder::None,
-    };
-    if let BugOrder::None = display_order {
-        display_order = BugOrder::Chronological(false);
-    } else {
-        args.advance();
+impl PagedList<'_> {
+    /// The command to call to get the list, for the users who click on the
+    /// buttons of someone else
+    fn command(&self) -> &'static str {
+        match self {
+            PagedList::Bugs(_) => "!bugs",
+            PagedList::History { .. } => "!bug history",
+        }
     }
 
-    let mut page = args.single::<u32>().unwrap_or(1).max(1);
-
-    let limit = if args.current() == Some("limit") {
-        args.advance();
-        args.single::<u32>().ok()
-    } else {
-        None
+    async fn display(
+        &self,
+        ctx: &Context,
+        page: u32,
+        reply_to: Either<'_>,
+    ) -> Result<Option<Message>, SerenityError> {
+        match self {
+            PagedList::Bugs(options) => display_bugs(ctx, options, page, reply_to).await,
+            PagedList::History { tracker, bug_id } => {
+                display_history(ctx, tracker, *bug_id, page, reply_to).await
+            }
+        }
     }
-    .unwrap_or(10);
+}
 
-    let mut response_message = match display_bugs(
-        ctx,
-        status,
-        limit,
-        display_order,
-        category,
-        page,
-        Either::Message(msg),
-    )
-    .await
-    {
+async fn paginate(
+    ctx: &Context,
+    msg: &Message,
+    list: PagedList<'_>,
+    mut page: u32,
+) -> CommandResult {
+    let mut response_message = match list.display(ctx, page, Either::Message(msg)).await {
         Ok(Some(msg)) => msg,
         Ok(None) => unreachable!(),
         Err(SerenityError::Other("page_too_high" | "too_many_bugs")) => return Ok(()),
@@ -501,7 +1226,7 @@ pub async fn buglist(ctx: &Context, msg: &Message, mut args: Args) -> CommandRes
             interaction.create_interaction_response(ctx, |r| {
                 r.kind(InteractionResponseType::ChannelMessageWithSource);
                 r.interaction_response_data(|d| {
-                    d.content("You are not the original user of the command! Call `!bugs` yourself to use the buttons.");
+                    d.content(format!("You are not the original user of the command! Call `{}` yourself to use the buttons.", list.command()));
                     d.flags(MessageFlags::EPHEMERAL)
                 })
             })
@@ -510,7 +1235,7 @@ pub async fn buglist(ctx: &Context, msg: &Message, mut args: Args) -> CommandRes
         }
         match interaction.data.custom_id.as_str() {
             "previous_page" => {
-                page = page.saturating_sub(1);
+                page = page.saturating_sub(1).max(1);
             }
             "next_page" => {
                 page += 1;
@@ -518,16 +1243,8 @@ pub async fn buglist(ctx: &Context, msg: &Message, mut args: Args) -> CommandRes
             _ => (),
         }
 
-        display_bugs(
-            ctx,
-            status,
-            limit,
-            display_order,
-            category,
-            page,
-            Either::Interaction(interaction.as_ref()),
-        )
-        .await?;
+        list.display(ctx, page, Either::Interaction(interaction.as_ref()))
+            .await?;
     }
 
     response_message
@@ -538,15 +1255,517 @@ pub async fn buglist(ctx: &Context, msg: &Message, mut args: Args) -> CommandRes
 }
 
 #[command]
-#[sub_commands(
-    track,
-    bug_status,
-    resolve,
-    bug_close,
-    bug_link,
-    bug_rename,
-    stats,
+#[only_in(guilds)]
+#[aliases(bugs)]
+#[sub_commands(bugtracker_help, bug_export, bug_import, bug_stale)]
+pub async fn buglist(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let assignee = match args.current() {
+        Some(s) if s.eq_ignore_ascii_case("mine") => {
+            args.advance();
+            Some(msg.author.id)
+        }
+        Some(s) if s.eq_ignore_ascii_case("assigned") => {
+            args.advance();
+            if let Ok(user_id) = args.single::<UserId>() {
+                Some(user_id)
+            } else {
+                failure!(ctx, msg, "You must mention the user whose bugs to display!");
+                return Ok(());
+            }
+        }
+        _ => None,
+    };
+    let category = args.single::<BugCategory>().ok();
+    let server_id = current_tracker(ctx, msg).await.server_id;
+    let workflow = get_status_workflow(ctx, server_id)
+        .await
+        .unwrap_or_default();
+    let status = args.current().and_then(|s| workflow.parse(s));
+    if status.is_some() {
+        args.advance();
+    }
+
+    let mut display_order = match args.current() {
+        Some("latest") => BugOrder::Chronological(false),
+        Some("oldest") => BugOrder::Chronological(true),
+        Some("highest") => BugOrder::Priority(false),
+        Some("lowest") => BugOrder::Priority(true),
+        Some("votes") => BugOrder::Votes,
+        _ => BugOrder::None,
+    };
+    if let BugOrder::None = display_order {
+        display_order = BugOrder::Chronological(false);
+    } else {
+        args.advance();
+    }
+
+    let mut labels = Vec::new();
+    while args.current() == Some("label") {
+        args.advance();
+        if let Some(label) = args
+            .single::<String>()
+            .ok()
+            .as_deref()
+            .and_then(parse_label)
+        {
+            labels.push(label);
+        } else {
+            failure!(ctx, msg, "You must provide a valid label to filter by!");
+            return Ok(());
+        }
+    }
+
+    let page = args.single::<u32>().unwrap_or(1).max(1);
+
+    let limit = if args.current() == Some("limit") {
+        args.advance();
+        args.single::<u32>().ok()
+    } else {
+        None
+    }
+    .unwrap_or(10);
+
+    paginate(
+        ctx,
+        msg,
+        PagedList::Bugs(BugListOptions {
+            filter: BugFilter {
+                guild_id: Some(server_id),
+                status,
+                category,
+                assignee,
+                labels,
+                inactive_days: None,
+                any_status: false,
+            },
+            display_order,
+            limit,
+            search: None,
+        }),
+        page,
+    )
+    .await
+}
+
+/// Default inactivity period after which an open bug is considered stale
+const DEFAULT_STALE_DAYS: u32 = 90;
+
+/// DMs assignees the list of their stale bugs. Returns the number of reminded users.
+async fn remind_stale_assignees(ctx: &Context, filter: &BugFilter) -> CommandResult<usize> {
+    let Some((bugs, _)) =
+        get_bug_list(ctx, filter, u32::MAX, BugOrder::Chronological(true), 0).await
+    else {
+        return Err("Could not get bugs from the database!".into());
+    };
+    let bug_ids = bugs.iter().map(|b| b.bug_id).collect::<Vec<_>>();
+    let assignees = get_assignees_of(ctx, &bug_ids)
+        .await
+        .ok_or("Could not get bug assignees from the database!")?;
+
+    let name = match filter.guild_id {
+        Some(server_id) => tracker_name(ctx, server_id).await,
+        None => "Bugtracker".into(),
+    };
+
+    let mut reminded = 0;
+    for (user_id, assigned) in assignees {
+        let mut content = format!(
+            "**{name} reminder {}**\n\
+The following bugs assigned to you have been inactive for at least {} days:\n",
+            ReactionType::from(EmojiIdentifier {
+                animated: false,
+                id: TERMITE_EMOJI,
+                name: "bug".into(),
+            }),
+            filter.inactive_days.unwrap_or(DEFAULT_STALE_DAYS),
+        );
+        for (i, bug) in bugs
+            .iter()
+            .filter(|b| assigned.contains(&b.bug_id))
+            .enumerate()
+        {
+            let line = format!("{} {}\n", bug.status.marker(), bug);
+            if content.len() + line.len() > 1950 {
+                content.push_str(&format!("_…and {} more_\n", assigned.len() - i));
+                break;
+            }
+            content.push_str(&line);
+        }
+
+        match user_id.create_dm_channel(ctx).await {
+            Ok(channel) => match channel.say(ctx, content).await {
+                Ok(_) => reminded += 1,
+                Err(e) => println!("Could not send stale bug reminder to {user_id}: {e}"),
+            },
+            Err(e) => println!("Could not send stale bug reminder to {user_id}: {e}"),
+        }
+    }
+
+    Ok(reminded)
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(stale)]
+pub async fn bug_stale(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let days = args.single::<u32>().unwrap_or(DEFAULT_STALE_DAYS).max(1);
+    let remind = args.current() == Some("remind");
+
+    let filter = BugFilter {
+        guild_id: msg.guild_id,
+        inactive_days: Some(days),
+        ..Default::default()
+    };
+
+    if remind {
+        let reminded = remind_stale_assignees(ctx, &filter).await?;
+        termite_success!(
+            ctx,
+            msg,
+            "Sent a reminder to {} assignee{} of stale bugs.",
+            reminded,
+            if reminded == 1 { "" } else { "s" }
+        );
+        return Ok(());
+    }
+
+    paginate(
+        ctx,
+        msg,
+        PagedList::Bugs(BugListOptions {
+            filter,
+            display_order: BugOrder::Chronological(true),
+            limit: 10,
+            search: None,
+        }),
+        1,
+    )
+    .await
+}
+
+/// Quotes a CSV field if needed
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn bugs_to_csv(tracker: &BugTracker, bugs: &[ExportedBug]) -> String {
+    let mut csv =
+        String::from("id,title,status,edition,timestamp,labels,subscribers,message,links\n");
+    for bug in bugs {
+        let message = match (bug.channel_id, bug.message_id) {
+            (Some(channel_id), Some(message_id)) if message_id != 0 => {
+                MessageId(message_id).link(ChannelId(channel_id), Some(tracker.server_id))
+            }
+            _ => String::new(),
+        };
+        let links = bug
+            .links
+            .iter()
+            .map(|l| format!("{} <{}>", l.title, l.url))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let fields = [
+            format!("{}-{}", tracker.prefix, bug.bug_id.unwrap_or_default()),
+            bug.title.clone(),
+            bug.status.clone(),
+            bug.category.clone(),
+            bug.timestamp.clone().unwrap_or_default(),
+            bug.labels.join(" "),
+            bug.subscribers.to_string(),
+            message,
+            links,
+        ];
+        csv.push_str(
+            &fields
+                .iter()
+                .map(|f| csv_field(f))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(export)]
+pub async fn bug_export(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    // unlike bug lists, exports include resolved and closed bugs by default
+    let mut filter = BugFilter {
+        guild_id: Some(tracker.server_id),
+        any_status: true,
+        ..Default::default()
+    };
+    let workflow = tracker_workflow(ctx, &tracker).await;
+    let mut csv = false;
+    for arg in args.raw() {
+        if let Some(status) = workflow.parse(arg) {
+            filter.status = Some(status);
+        } else if let Ok(category) = arg.parse::<BugCategory>() {
+            filter.category = Some(category);
+        } else if arg.eq_ignore_ascii_case("csv") {
+            csv = true;
+        } else if arg.eq_ignore_ascii_case("json") {
+            csv = false;
+        } else {
+            failure!(
+                ctx,
+                msg,
+                "Unknown argument `{}`. Use `!bugs export [status] [legacy|renewed] [json|csv]`",
+                arg
+            );
+            return Ok(());
+        }
+    }
+
+    let Some(bugs) = export_bugs(ctx, &filter).await else {
+        failure!(ctx, msg, "Could not get bugs from the database!");
+        return Ok(());
+    };
+
+    let count = bugs.len();
+    let (content, extension) = if csv {
+        (bugs_to_csv(&tracker, &bugs), "csv")
+    } else {
+        (serde_json::to_string_pretty(&BugExport { bugs })?, "json")
+    };
+    let file_name = format!(
+        "bugs{}{}.{}",
+        filter
+            .status
+            .as_ref()
+            .map(|s| format!("_{}", s.as_str()))
+            .unwrap_or_default(),
+        filter
+            .category
+            .map(|c| format!("_{}", c.as_str()))
+            .unwrap_or_default(),
+        extension
+    );
+
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.content(format!("Exported {count} bug reports"))
+                .reference_message(msg)
+                .allowed_mentions(|f| f.empty_parse())
+                .add_file((content.as_bytes(), file_name.as_str()))
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[owners_only]
+#[only_in(guilds)]
+#[aliases(import)]
+pub async fn bug_import(ctx: &Context, msg: &Message) -> CommandResult {
+    let export = match get_json_from_message::<BugExport>(msg).await {
+        Ok(export) => export,
+        Err(e) => {
+            handle_json_error!(ctx, msg, e);
+            return Ok(());
+        }
+    };
+
+    if let Some(label) = export
+        .bugs
+        .iter()
+        .flat_map(|b| b.labels.iter())
+        .find(|l| parse_label(l).is_none())
+    {
+        failure!(ctx, msg, "`{}` is not a valid label!", label);
+        return Ok(());
+    }
+
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    match import_bugs(ctx, tracker.server_id, &export.bugs).await {
+        Ok(ids) if ids.is_empty() => {
+            failure!(ctx, msg, "There are no bug reports to import!");
+        }
+        Ok(ids) => {
+            termite_success!(
+                ctx,
+                msg,
+                "Imported {} bug reports ({prefix}-{} to {prefix}-{})",
+                ids.len(),
+                ids[0],
+                ids[ids.len() - 1]
+            );
+        }
+        Err(e) => {
+            failure!(ctx, msg, "Could not import bug reports: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Saves the reported messages of the bugs tracked before snapshots existed,
+/// so that their content can be searched and shown once the message is gone
+#[command]
+#[owners_only]
+#[aliases(snapshots)]
+pub async fn bug_snapshots(ctx: &Context, msg: &Message) -> CommandResult {
+    let bugs = get_bugs_without_snapshot(ctx)
+        .await
+        .ok_or("Could not get the bugs without snapshot from the database!")?;
+
+    let mut saved = 0;
+    for (bug_id, channel_id, message_id) in &bugs {
+        // the message may have been deleted since
+        let Ok(message) = channel_id.message(ctx, *message_id).await else {
+            continue;
+        };
+        add_missing_snapshot(ctx, *bug_id, &message).await?;
+        saved += 1;
+    }
+
+    termite_success!(
+        ctx,
+        msg,
+        "Saved the reported message of {} bugs out of {} without snapshot.",
+        saved,
+        bugs.len()
+    );
+
+    Ok(())
+}
+
+#[command]
+#[owners_only]
+#[aliases(undeliverable, deliveries)]
+pub async fn bug_undeliverable(ctx: &Context, msg: &Message) -> CommandResult {
+    let failures = get_delivery_failures(ctx)
+        .await
+        .ok_or("Could not get notification delivery failures from the database!")?;
+
+    if failures.is_empty() {
+        termite_success!(
+            ctx,
+            msg,
+            "All bug notifications were delivered successfully!"
+        );
+        return Ok(());
+    }
+
+    let mut description = String::new();
+    for (i, failure) in failures.iter().enumerate() {
+        let line = format!(
+            "{} — {} failure{} {}, {}: `{}`\n",
+            failure.user_id.mention(),
+            failure.failures,
+            if failure.failures == 1 { "" } else { "s" },
+            failure.last_failure.format("<t:%s:R>"),
+            match failure.unsubscribed {
+                Some(time) => format!("unsubscribed {}", time.format("<t:%s:R>")),
+                None => format!("{} subscriptions", failure.subscriptions),
+            },
+            failure.last_error,
+        );
+        if description.len() + line.len() > 4000 {
+            description.push_str(&format!("_…and {} more_", failures.len() - i));
+            break;
+        }
+        description.push_str(&line);
+    }
+
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.name("Bugtracker")
+                        .icon_url(crate::constants::TERMITE_IMAGE)
+                })
+                .colour(serenity::utils::Colour::TEAL)
+                .title("Undeliverable bug notifications")
+                .description(description)
+                .footer(|f| {
+                    f.text(format!(
+                        "Users are unsubscribed from all bugs after {MAX_DELIVERY_FAILURES} \
+failed notifications in a row"
+                    ))
+                })
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[aliases(search)]
+pub async fn bug_search(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let category = args.single::<BugCategory>().ok();
+    let server_id = current_tracker(ctx, msg).await.server_id;
+    let workflow = get_status_workflow(ctx, server_id)
+        .await
+        .unwrap_or_default();
+    let status = args.current().and_then(|s| workflow.parse(s));
+    if status.is_some() {
+        args.advance();
+    }
+
+    let terms = args.rest().trim();
+    if terms.is_empty() {
+        failure!(ctx, msg, "You must provide some terms to search for!");
+        return Ok(());
+    }
+
+    paginate(
+        ctx,
+        msg,
+        PagedList::Bugs(BugListOptions {
+            filter: BugFilter {
+                guild_id: Some(server_id),
+                status,
+                category,
+                ..Default::default()
+            },
+            display_order: BugOrder::Chronological(false),
+            limit: 10,
+            search: Some(terms),
+        }),
+        1,
+    )
+    .await
+}
+
+#[command]
+#[sub_commands(
+    track,
+    bug_status,
+    resolve,
+    bug_close,
+    bug_assign,
+    bug_digest,
+    bug_duplicate,
+    bug_edit,
+    bug_history,
+    bug_issues,
+    bug_label,
+    bug_link,
+    bug_mirror,
+    bug_queue,
+    bug_rename,
+    bug_search,
+    bug_submit,
+    stats,
     bug_toggle_edition,
+    bug_tracker,
+    bug_snapshots,
+    bug_undeliverable,
+    bug_workflow,
     bugtracker_help,
     notifications,
     unsubscribe,
@@ -554,11 +1773,7 @@ pub async fn buglist(ctx: &Context, msg: &Message, mut args: Args) -> CommandRes
 )]
 pub async fn bug(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let bug_id = if let Ok(bug_id) = args.single::<String>() {
-        if let Ok(bug_id) = bug_id
-            .to_uppercase()
-            .trim_start_matches("LOTR-")
-            .parse::<u64>()
-        {
+        if let Some(bug_id) = parse_bug_id(&bug_id) {
             bug_id
         } else {
             failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
@@ -568,14 +1783,23 @@ pub async fn bug(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult
         failure!(ctx, msg, "The first argument must be a bug id.");
         return Ok(());
     };
+    let Some(tracker) = tracker_of_bug(ctx, msg, bug_id).await else {
+        failure!(ctx, msg, "Bug {} does not exist!", bug_id);
+        return Ok(());
+    };
+    let prefix = &tracker.prefix;
 
     let mut bug = match get_bug_from_id(ctx, bug_id).await {
         Ok(bug) => bug,
         Err(e) => {
-            failure!(ctx, msg, "Bug LOTR-{} does not exist!", bug_id);
+            failure!(ctx, msg, "Bug {prefix}-{} does not exist!", bug_id);
             return Err(e);
         }
     };
+    if bug.guild_id != tracker.server_id && msg.author.id != OWNER_ID {
+        failure!(ctx, msg, "Bug {prefix}-{} does not exist!", bug_id);
+        return Ok(());
+    }
 
     fn create_action_buttons(
         bug_id: u64,
@@ -597,6 +1821,11 @@ pub async fn bug(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult
                         .label("Subscribe")
                         .custom_id(format!("bug_subscribe__{bug_id}"))
                 });
+                a.create_button(|b| {
+                    b.style(ButtonStyle::Secondary)
+                        .label("Affects me")
+                        .custom_id(format!("bug_vote__{bug_id}"))
+                });
                 if create_buttons {
                     a.create_button(|b| {
                         b.style(ButtonStyle::Success)
@@ -617,17 +1846,11 @@ pub async fn bug(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult
         }
     }
 
-    let linked_message = bug
-        .channel_id
-        .message(ctx, bug.message_id)
-        .await
-        .map(|mut m| {
-            m.guild_id = Some(LOTR_DISCORD);
-            m
-        });
-    let message_link = linked_message.as_ref().map(Message::link).ok();
+    let linked_message = fetch_reported_message(ctx, &bug).await;
+    let message_link = linked_message.as_ref().map(Message::link);
+    let name = tracker_name(ctx, bug.guild_id).await;
 
-    let is_lotr_discord = msg.guild_id == Some(LOTR_DISCORD);
+    let in_bug_guild = msg.guild_id == Some(bug.guild_id);
     let is_admin = if let Some(guild_id) = msg.guild_id {
         is_admin_function(ctx, guild_id, msg.author.id)
             .await
@@ -637,49 +1860,93 @@ pub async fn bug(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult
         false
     };
 
-    let mut create_buttons = bug.status != BugStatus::Resolved
-        && bug.status != BugStatus::Closed
-        && (msg.author.id == OWNER_ID || (is_lotr_discord && is_admin));
+    // if the original message is gone, re-upload the archived attachments
+    let archived_attachments = if linked_message.is_none() && bug.snapshot.is_some() {
+        let mut total_size = 0;
+        get_archived_attachments(ctx, bug_id)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .take(10)
+            .take_while(|(_, data)| {
+                total_size += data.len() as u64;
+                total_size <= MAX_ARCHIVED_ATTACHMENT_SIZE
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let archived_image = archived_attachments
+        .iter()
+        .map(|(filename, _)| filename.as_str())
+        .find(|filename| is_archived_image(&bug, filename));
+
+    let mut create_buttons =
+        bug.status.is_open && (msg.author.id == OWNER_ID || (in_bug_guild && is_admin));
 
     let mut response_message = msg
         .channel_id
         .send_message(ctx, |m| {
-            m.embed(create_bug_embed(&bug, linked_message.as_ref().ok()))
-                .components(create_action_buttons(
-                    bug_id,
-                    message_link.as_deref(),
-                    create_buttons,
-                    false,
-                ))
+            m.embed(create_bug_embed(
+                &bug,
+                &name,
+                linked_message.as_ref(),
+                archived_image,
+            ))
+            .components(create_action_buttons(
+                bug_id,
+                message_link.as_deref(),
+                create_buttons,
+                false,
+            ));
+            for (filename, data) in &archived_attachments {
+                m.add_file((data.as_slice(), filename.as_str()));
+            }
+            m
         })
         .await?;
 
     if create_buttons {
-        // Listen to interactions for 120 seconds
+        // Listen to interactions for 120 seconds. The subscribe and vote buttons are
+        // handled by the event handler, since they stay usable after the timeout
         while let Some(interaction) = CollectComponentInteraction::new(ctx)
             .timeout(Duration::from_secs(120))
             .channel_id(msg.channel_id)
             .message_id(response_message.id)
+            .filter(|interaction| {
+                matches!(
+                    interaction.data.custom_id.as_str(),
+                    "resolve_bug" | "close_bug"
+                )
+            })
             .await
         {
             if interaction.user.id == msg.author.id {
+                let workflow = get_status_workflow(ctx, bug.guild_id)
+                    .await
+                    .unwrap_or_default();
                 let new_status = match interaction.data.custom_id.as_str() {
-                    "resolve_bug" => BugStatus::Resolved,
-                    "close_bug" => BugStatus::Closed,
+                    "resolve_bug" => workflow.resolved(),
+                    "close_bug" => workflow.closed(),
                     _ => continue,
                 };
 
-                change_bug_status(ctx, bug_id, new_status).await?;
+                change_bug_status(ctx, bug_id, new_status.clone(), interaction.user.id).await?;
 
-                let old_status = bug.status;
-                bug.status = new_status;
+                let old_status = std::mem::replace(&mut bug.status, new_status.clone());
 
                 interaction
                     .create_interaction_response(ctx, |r| {
                         r.kind(InteractionResponseType::UpdateMessage)
                             .interaction_response_data(|m| {
                                 m.set_embeds([])
-                                    .embed(create_bug_embed(&bug, linked_message.as_ref().ok()))
+                                    .embed(create_bug_embed(
+                                        &bug,
+                                        &name,
+                                        linked_message.as_ref(),
+                                        archived_image,
+                                    ))
                                     .components(create_action_buttons(
                                         bug_id,
                                         message_link.as_deref(),
@@ -730,138 +1997,1334 @@ pub async fn bug(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult
         }
     }
 
-    Ok(())
+    Ok(())
+}
+
+fn create_history_embed<'a>(
+    tracker_name: &'a str,
+    prefix: &'a str,
+    bug_id: u64,
+    entries: &'a [BugHistoryEntry],
+    page: u32,
+    total: u32,
+    limit: u32,
+) -> impl Fn(&mut CreateEmbed) -> &mut CreateEmbed + 'a {
+    move |e| {
+        e.author(|a| {
+            a.name(tracker_name);
+            a.icon_url(crate::constants::TERMITE_IMAGE);
+            a
+        });
+        e.colour(serenity::utils::Colour::TEAL);
+        e.title(format!("History of {prefix}-{bug_id} (Total: {total})"));
+        e.description(if entries.is_empty() {
+            "_No changes recorded for this bug!_".to_string()
+        } else {
+            entries
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        e.footer(|f| f.text(format!("Page {}/{}", page, (total.max(1) - 1) / limit + 1)));
+        e
+    }
+}
+
+async fn display_history(
+    ctx: &Context,
+    tracker: &BugTracker,
+    bug_id: u64,
+    page: u32,
+    reply_to: Either<'_>,
+) -> Result<Option<Message>, SerenityError> {
+    const LIMIT: u32 = 10;
+
+    let Some((entries, total)) = get_bug_history(ctx, bug_id, LIMIT, page - 1).await else {
+        return Err(SerenityError::Other(
+            "Could not get the bug history from the database!",
+        ));
+    };
+    if total != 0 && (page - 1) * LIMIT >= total {
+        reply_to.failure(ctx, "Page number too high!").await?;
+        return Err(SerenityError::Other("page_too_high"));
+    }
+
+    let name = tracker_name(ctx, tracker.server_id).await;
+    let embed = create_history_embed(&name, &tracker.prefix, bug_id, &entries, page, total, LIMIT);
+    let buttons = create_buttons(page <= 1, page * LIMIT >= total);
+    match reply_to {
+        Either::Interaction(interaction) => {
+            interaction
+                .create_interaction_response(ctx, |r| {
+                    r.kind(InteractionResponseType::UpdateMessage)
+                        .interaction_response_data(|m| {
+                            m.set_embeds([]).embed(embed).components(buttons)
+                        })
+                })
+                .await?;
+
+            Ok(None)
+        }
+        Either::Message(msg) => Ok(Some(
+            msg.channel_id
+                .send_message(ctx, |m| m.embed(embed).components(buttons))
+                .await?,
+        )),
+    }
+}
+
+#[command]
+#[aliases(history)]
+pub async fn bug_history(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let Ok(bug_id) = args.single::<String>() else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+        return Ok(());
+    };
+    let Some(bug_id) = parse_bug_id(&bug_id) else {
+        failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        return Ok(());
+    };
+    let Some(tracker) = tracker_of_bug(ctx, msg, bug_id).await else {
+        failure!(ctx, msg, "Bug {} does not exist!", bug_id);
+        return Ok(());
+    };
+    let prefix = &tracker.prefix;
+
+    if !is_tracked_in(ctx, &tracker, bug_id).await {
+        failure!(ctx, msg, "Bug {prefix}-{} does not exist!", bug_id);
+        return Ok(());
+    }
+
+    let page = args.single::<u32>().unwrap_or(1).max(1);
+
+    paginate(
+        ctx,
+        msg,
+        PagedList::History {
+            tracker: &tracker,
+            bug_id,
+        },
+        page,
+    )
+    .await
+}
+
+/// Maximum number of bugs that can be changed by a single bulk command
+const MAX_BULK_BUGS: usize = 100;
+
+/// Parses a list of bug ids, with ranges such as `LOTR-40..LOTR-45`
+fn parse_bug_ids<'a>(args: impl Iterator<Item = &'a str>) -> Result<Vec<u64>, String> {
+    let mut bug_ids = Vec::new();
+    for arg in args {
+        if let Some((start, end)) = arg.split_once("..") {
+            let (Some(start), Some(end)) = (parse_bug_id(start), parse_bug_id(end)) else {
+                return Err(format!("`{arg}` is not a valid bug range!"));
+            };
+            if start > end || (end - start) as usize >= MAX_BULK_BUGS {
+                return Err(format!("`{arg}` is not a valid bug range!"));
+            }
+            bug_ids.extend(start..=end);
+        } else if let Some(bug_id) = parse_bug_id(arg) {
+            bug_ids.push(bug_id);
+        } else {
+            return Err(format!("`{arg}` is not a valid bug id!"));
+        }
+        if bug_ids.len() > MAX_BULK_BUGS {
+            return Err(format!(
+                "You cannot change more than {MAX_BULK_BUGS} bugs at once!"
+            ));
+        }
+    }
+    let mut seen = HashSet::new();
+    bug_ids.retain(|id| seen.insert(*id));
+    Ok(bug_ids)
+}
+
+/// Sends a single notification to each subscriber of the changed bugs
+async fn notify_status_changes(
+    ctx: &Context,
+    tracker: &BugTracker,
+    changes: &[StatusChange],
+    new_status: &BugStatus,
+) -> CommandResult {
+    let mut subscriptions: HashMap<UserId, Vec<&StatusChange>> = HashMap::new();
+    for change in changes {
+        for user in get_notified_users(ctx, change.bug_id).await? {
+            subscriptions.entry(user).or_default().push(change);
+        }
+    }
+
+    let users = subscriptions.keys().copied().collect::<Vec<_>>();
+    let modes = get_notification_modes(ctx, &users).await;
+    let prefix = &tracker.prefix;
+    let name = tracker_name(ctx, tracker.server_id).await;
+
+    let mut res = Ok(());
+    for (user, changes) in subscriptions {
+        match modes.get(&user).copied().unwrap_or_default() {
+            NotificationMode::Immediate => (),
+            NotificationMode::Daily => {
+                for change in changes {
+                    let message = format!(
+                        "A bug you are subscribed to has been changed from `{}` to `{}`",
+                        change.old_status, new_status
+                    );
+                    if let Err(e) =
+                        add_pending_notification(ctx, user, change.bug_id, &message).await
+                    {
+                        if res.is_ok() {
+                            res = Err(e);
+                        }
+                    }
+                }
+                continue;
+            }
+            NotificationMode::Muted => continue,
+        }
+
+        let mut content = format!(
+            "**{name} notification {}**\n{} you are subscribed to {} been changed to `{}`:\n",
+            ReactionType::from(EmojiIdentifier {
+                animated: false,
+                id: TERMITE_EMOJI,
+                name: "bug".into(),
+            }),
+            if changes.len() == 1 {
+                "A bug".to_string()
+            } else {
+                format!("{} bugs", changes.len())
+            },
+            if changes.len() == 1 { "has" } else { "have" },
+            new_status,
+        );
+        for (i, change) in changes.iter().enumerate() {
+            let line = format!(
+                "• {prefix}-{} — {} (was `{}`)\n",
+                change.bug_id, change.title, change.old_status
+            );
+            if content.len() + line.len() > 1950 {
+                content.push_str(&format!("_…and {} more_\n", changes.len() - i));
+                break;
+            }
+            content.push_str(&line);
+        }
+
+        let channel = match user.create_dm_channel(ctx).await {
+            Ok(channel) => channel,
+            Err(e) => {
+                if let Err(e) = check_delivery(ctx, user, Err::<(), _>(e)).await {
+                    if res.is_ok() {
+                        res = Err(e);
+                    }
+                }
+                continue;
+            }
+        };
+
+        let sent = channel.say(ctx, content).await;
+        if let Err(e) = check_delivery(ctx, user, sent).await {
+            if res.is_ok() {
+                res = Err(e);
+            }
+        }
+    }
+
+    res
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases("status")]
+pub async fn bug_status(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    let workflow = tracker_workflow(ctx, &tracker).await;
+    if let Some(new_status) = args.current().and_then(|s| workflow.parse(s)) {
+        args.advance();
+        let bug_ids = args
+            .iter::<String>()
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+        let bug_ids = match parse_bug_ids(bug_ids.iter().map(String::as_str)) {
+            Ok(bug_ids) if bug_ids.is_empty() => {
+                failure!(ctx, msg, "You must provide the bugs to change!");
+                return Ok(());
+            }
+            Ok(bug_ids) => bug_ids,
+            Err(e) => {
+                failure!(ctx, msg, e);
+                return Ok(());
+            }
+        };
+
+        let changes =
+            match change_bug_statuses(ctx, &tracker, &bug_ids, new_status.clone(), msg.author.id)
+                .await
+            {
+                Ok(changes) => changes,
+                Err(e) => {
+                    failure!(ctx, msg, "Could not change the bug statuses: {}", e);
+                    return Ok(());
+                }
+            };
+
+        if changes.is_empty() {
+            failure!(
+                ctx,
+                msg,
+                "These bugs already have the status `{}`",
+                new_status
+            );
+        } else {
+            termite_success!(
+                ctx,
+                msg,
+                "Status changed to `{}` for {}",
+                new_status,
+                changes
+                    .iter()
+                    .map(|c| format!("{prefix}-{}", c.bug_id))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            notify_status_changes(ctx, &tracker, &changes, &new_status).await?;
+        }
+        return Ok(());
+    }
+
+    if let Ok(bug_id) = args.single::<String>() {
+        if let Some(bug_id) = parse_bug_id(&bug_id) {
+            if !is_tracked_in(ctx, &tracker, bug_id).await {
+                failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                return Ok(());
+            }
+            if let Some(new_status) = args.current().and_then(|s| workflow.parse(s)) {
+                let old_status =
+                    match change_bug_status(ctx, bug_id, new_status.clone(), msg.author.id).await {
+                        Ok(old_status) => {
+                            termite_success!(
+                                ctx,
+                                msg,
+                                "Status changed for {prefix}-{} from `{}` to `{}`!",
+                                bug_id,
+                                old_status,
+                                new_status
+                            );
+                            old_status
+                        }
+                        Err(e) => {
+                            failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                            return Err(e);
+                        }
+                    };
+
+                if old_status != new_status {
+                    notify_users(
+                        ctx,
+                        bug_id,
+                        format!(
+                            "A bug you are subscribed to has been changed from `{old_status}` to `{new_status}`"
+                        ),
+                    )
+                    .await?;
+                }
+            } else {
+                failure!(ctx, msg, "The second argument must be a bug status.");
+            }
+        } else {
+            failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        }
+    } else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+    }
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+pub async fn resolve(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    if let Ok(bug_id) = args.single::<String>() {
+        if let Some(bug_id) = parse_bug_id(&bug_id) {
+            if !is_tracked_in(ctx, &tracker, bug_id).await {
+                failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                return Ok(());
+            }
+            let resolved = tracker_workflow(ctx, &tracker).await.resolved();
+            if let Err(e) = change_bug_status(ctx, bug_id, resolved, msg.author.id).await {
+                failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                return Err(e);
+            }
+            termite_success!(ctx, msg, "{prefix}-{} has been marked as resolved.", bug_id);
+            notify_users(
+                ctx,
+                bug_id,
+                "A bug you are subscribed to has been marked as resolved.",
+            )
+            .await?;
+        } else {
+            failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        }
+    } else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+    }
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(close)]
+pub async fn bug_close(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    if let Ok(bug_id) = args.single::<String>() {
+        if let Some(bug_id) = parse_bug_id(&bug_id) {
+            if !is_tracked_in(ctx, &tracker, bug_id).await {
+                failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                return Ok(());
+            }
+            let closed = tracker_workflow(ctx, &tracker).await.closed();
+            if let Err(e) = change_bug_status(ctx, bug_id, closed, msg.author.id).await {
+                failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                return Err(e);
+            }
+            termite_success!(ctx, msg, "{prefix}-{} has been marked as closed.", bug_id);
+            notify_users(
+                ctx,
+                bug_id,
+                "A bug you are subscribed to has been marked as closed.",
+            )
+            .await?;
+        } else {
+            failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        }
+    } else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+    }
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[sub_commands(bug_assign_remove)]
+#[aliases(assign)]
+pub async fn bug_assign(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    let Ok(bug_id) = args.single::<String>() else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+        return Ok(());
+    };
+    let Some(bug_id) = parse_bug_id(&bug_id) else {
+        failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        return Ok(());
+    };
+
+    let users = args
+        .iter::<UserId>()
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    if users.is_empty() {
+        failure!(
+            ctx,
+            msg,
+            "You must mention the users to assign to {prefix}-{}",
+            bug_id
+        );
+        return Ok(());
+    }
+
+    if !is_tracked_in(ctx, &tracker, bug_id).await {
+        failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+        return Ok(());
+    }
+
+    let mut assigned = Vec::with_capacity(users.len());
+    for user_id in users {
+        if is_assignee(ctx, bug_id, user_id).await != Some(false) {
+            continue;
+        }
+        add_assignee(ctx, bug_id, user_id, msg.author.id).await?;
+        if is_notified_user(ctx, bug_id, user_id).await == Some(false) {
+            add_notified_user(ctx, bug_id, user_id).await?;
+        }
+        assigned.push(user_id.mention().to_string());
+    }
+
+    if assigned.is_empty() {
+        failure!(
+            ctx,
+            msg,
+            "These users are already assigned to {prefix}-{}",
+            bug_id
+        );
+    } else {
+        msg.channel_id
+            .send_message(ctx, |m| {
+                m.content(format!(
+                    "Assigned {} to {prefix}-{}",
+                    assigned.join(", "),
+                    bug_id
+                ))
+                .reference_message(msg)
+                .allowed_mentions(|f| f.empty_parse())
+            })
+            .await?;
+        termite_success!(ctx, msg);
+    }
+
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(remove)]
+pub async fn bug_assign_remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    let Ok(bug_id) = args.single::<String>() else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+        return Ok(());
+    };
+    let Some(bug_id) = parse_bug_id(&bug_id) else {
+        failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        return Ok(());
+    };
+    if !is_tracked_in(ctx, &tracker, bug_id).await {
+        failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+        return Ok(());
+    }
+
+    let users = args
+        .iter::<UserId>()
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    if users.is_empty() {
+        failure!(
+            ctx,
+            msg,
+            "You must mention the users to unassign from {prefix}-{}",
+            bug_id
+        );
+        return Ok(());
+    }
+
+    let mut unassigned = 0;
+    for user_id in users {
+        if is_assignee(ctx, bug_id, user_id).await == Some(true) {
+            remove_assignee(ctx, bug_id, user_id, msg.author.id).await?;
+            unassigned += 1;
+        }
+    }
+
+    if unassigned == 0 {
+        failure!(
+            ctx,
+            msg,
+            "These users are not assigned to {prefix}-{}",
+            bug_id
+        );
+    } else {
+        termite_success!(
+            ctx,
+            msg,
+            "Unassigned {} user{} from {prefix}-{}",
+            unassigned,
+            if unassigned == 1 { "" } else { "s" },
+            bug_id
+        );
+    }
+
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(digest)]
+pub async fn bug_digest(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+
+    if args.is_empty() {
+        if let Some(config) = get_digest_config(ctx, server_id).await {
+            msg.reply(
+                ctx,
+                format!(
+                    "Bugtracker digests are posted in {} every {} at {}:00 UTC.",
+                    config.channel_id.mention(),
+                    config.weekday,
+                    config.hour
+                ),
+            )
+            .await?;
+        } else {
+            msg.reply(ctx, "Bugtracker digests are disabled in this server.")
+                .await?;
+        }
+        return Ok(());
+    }
+
+    if args.current() == Some("disable") {
+        delete_digest_config(ctx, server_id).await?;
+        termite_success!(ctx, msg, "Bugtracker digests disabled.");
+        return Ok(());
+    }
+
+    let Ok(channel_id) = args.single::<ChannelId>() else {
+        failure!(ctx, msg, "The first argument must be a channel mention.");
+        return Ok(());
+    };
+    let weekday = if let Ok(weekday) = args.parse::<chrono::Weekday>() {
+        args.advance();
+        weekday
+    } else {
+        chrono::Weekday::Mon
+    };
+    let hour = match args.single::<u32>() {
+        Ok(hour) if hour < 24 => hour,
+        Ok(_) => {
+            failure!(ctx, msg, "The hour must be between 0 and 23.");
+            return Ok(());
+        }
+        Err(_) => 12,
+    };
+
+    set_digest_config(ctx, server_id, channel_id, weekday, hour).await?;
+    termite_success!(
+        ctx,
+        msg,
+        "Bugtracker digests will be posted in {} every {} at {}:00 UTC.",
+        channel_id.mention(),
+        weekday,
+        hour
+    );
+
+    Ok(())
+}
+
+#[command]
+#[checks(is_admin)]
+#[only_in(guilds)]
+#[aliases(tracker)]
+pub async fn bug_tracker(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+    let mut tracker = get_bug_tracker(ctx, server_id)
+        .await
+        .unwrap_or_else(|| BugTracker::default_for(server_id));
+
+    let prefix_arg = match args.single::<String>().ok().as_deref() {
+        None => {
+            msg.reply(
+                ctx,
+                format!(
+                    "The bugtracker is {} in this server, with bug ids like `{}-1`.",
+                    if tracker.enabled {
+                        "enabled"
+                    } else {
+                        "disabled"
+                    },
+                    tracker.prefix
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+        Some("enable") => {
+            tracker.enabled = true;
+            args.single::<String>().ok()
+        }
+        Some("disable") => {
+            tracker.enabled = false;
+            None
+        }
+        Some("prefix") => {
+            let Ok(prefix) = args.single::<String>() else {
+                failure!(ctx, msg, "You must specify the new bug id prefix.");
+                return Ok(());
+            };
+            Some(prefix)
+        }
+        Some(_) => {
+            failure!(
+                ctx,
+                msg,
+                "The first argument must be `enable`, `disable` or `prefix`."
+            );
+            return Ok(());
+        }
+    };
+
+    if let Some(prefix) = prefix_arg {
+        let Some(prefix) = parse_prefix(&prefix) else {
+            failure!(
+                ctx,
+                msg,
+                "`{}` is not a valid prefix: it must be 1 to 16 letters or digits.",
+                prefix
+            );
+            return Ok(());
+        };
+        tracker.prefix = prefix;
+    }
+
+    set_bug_tracker(ctx, &tracker).await?;
+    termite_success!(
+        ctx,
+        msg,
+        "The bugtracker is now {} in this server, with bug ids like `{}-1`.",
+        if tracker.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        },
+        tracker.prefix
+    );
+
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(issues)]
+pub async fn bug_issues(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+
+    match args.current() {
+        None => {
+            if let Some(api_url) = get_issue_sync_url(ctx, server_id).await {
+                msg.reply(
+                    ctx,
+                    format!("Mirrored bugs are synced with the issue tracker at <{api_url}>."),
+                )
+                .await?;
+            } else {
+                msg.reply(ctx, "No issue tracker is configured in this server.")
+                    .await?;
+            }
+        }
+        Some("disable") => {
+            delete_issue_sync_url(ctx, server_id).await?;
+            termite_success!(ctx, msg, "Issue tracker sync disabled.");
+        }
+        Some(api_url) if is_allowed_api_url(api_url) && api_url.len() <= 255 => {
+            set_issue_sync_url(ctx, server_id, api_url).await?;
+            termite_success!(
+                ctx,
+                msg,
+                "Mirrored bugs will be synced with the issue tracker at <{}>.",
+                api_url
+            );
+        }
+        Some(_) => {
+            failure!(
+                ctx,
+                msg,
+                "The argument must be the https api url of an allowed issue tracker, or `disable`."
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(mirror)]
+pub async fn bug_mirror(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    let Some(api_url) = get_issue_sync_url(ctx, tracker.server_id).await else {
+        failure!(
+            ctx,
+            msg,
+            "No issue tracker is configured in this server. Use `!bug issues <api url>` first."
+        );
+        return Ok(());
+    };
+    let Ok(bug_id) = args.single::<String>() else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+        return Ok(());
+    };
+    let Some(bug_id) = parse_bug_id(&bug_id) else {
+        failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        return Ok(());
+    };
+    if !is_tracked_in(ctx, &tracker, bug_id).await {
+        failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+        return Ok(());
+    }
+    if let Some((number, url)) = get_bug_issue(ctx, bug_id).await {
+        failure!(
+            ctx,
+            msg,
+            "{prefix}-{} is already mirrored to issue #{}: <{}>",
+            bug_id,
+            number,
+            url
+        );
+        return Ok(());
+    }
+
+    let bug = get_bug_from_id(ctx, bug_id).await?;
+    let issue = Issue {
+        title: bug.title.clone(),
+        open: bug.status.is_open,
+        links: bug.links.iter().map(IssueLink::from).collect(),
+    };
+    let description = match bug.message_link() {
+        Some(link) => format!("Mirrored from the Discord bug report {prefix}-{bug_id}: {link}"),
+        None => format!("Mirrored from the Discord bug report {prefix}-{bug_id}"),
+    };
+
+    let backend = backend_for(get_reqwest_client!(ctx).inner(), &api_url);
+    let created = match backend.create_issue(&issue, &description).await {
+        Ok(created) => created,
+        Err(e) => {
+            failure!(ctx, msg, "Could not create the issue: {}", e);
+            return Ok(());
+        }
+    };
+
+    // a mirrored bug always links to its issue
+    if add_link(
+        ctx,
+        bug_id,
+        &created.url,
+        &format!("Issue #{}", created.number),
+        msg.author.id,
+    )
+    .await
+    .is_none()
+    {
+        failure!(
+            ctx,
+            msg,
+            "Created issue #{} (<{}>), but could not link it to {prefix}-{}. The bug is not mirrored.",
+            created.number,
+            created.url,
+            bug_id
+        );
+        return Ok(());
+    }
+    add_bug_issue(
+        ctx,
+        bug_id,
+        created.number,
+        &created.url,
+        &SyncState {
+            title: issue.title,
+            open: issue.open,
+        },
+    )
+    .await?;
+
+    termite_success!(
+        ctx,
+        msg,
+        "{prefix}-{} is now mirrored to issue #{}: <{}>",
+        bug_id,
+        created.number,
+        created.url
+    );
+
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(workflow, statuses)]
+pub async fn bug_workflow(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let workflow = tracker_workflow(ctx, &tracker).await;
+
+    match args.single::<String>().ok().as_deref() {
+        None => {
+            let content = workflow
+                .statuses
+                .iter()
+                .enumerate()
+                .map(|(i, status)| {
+                    format!(
+                        "{}. {} `{}` ({}) colour `#{:06x}`, reaction {}",
+                        i + 1,
+                        status.marker(),
+                        status.name,
+                        if status.is_open { "open" } else { "closed" },
+                        status.colour.0,
+                        status.reaction
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            msg.reply(
+                ctx,
+                format!("Bug statuses of this server, from lowest to highest priority:\n{content}"),
+            )
+            .await?;
+        }
+        Some("set") => {
+            let Some(name) = args
+                .single::<String>()
+                .ok()
+                .as_deref()
+                .and_then(parse_status_name)
+            else {
+                failure!(
+                    ctx,
+                    msg,
+                    "You must provide a valid status name: 1 to 32 letters, digits or `-`."
+                );
+                return Ok(());
+            };
+            let Some(colour) = args
+                .single::<String>()
+                .ok()
+                .and_then(|c| u32::from_str_radix(c.trim_start_matches('#'), 16).ok())
+                .filter(|&c| c <= BIT_FILTER_24BITS)
+            else {
+                failure!(
+                    ctx,
+                    msg,
+                    "You must provide a hex colour, such as `#fd9a01`."
+                );
+                return Ok(());
+            };
+            let Some(marker) = args.single::<String>().ok().filter(|m| m.len() <= 64) else {
+                failure!(ctx, msg, "You must provide a marker emoji for the status.");
+                return Ok(());
+            };
+            let Some(reaction) = args
+                .single::<String>()
+                .ok()
+                .filter(|r| r.len() <= 64 && ReactionType::try_from(r.as_str()).is_ok())
+            else {
+                failure!(
+                    ctx,
+                    msg,
+                    "You must provide a reaction emoji for the status."
+                );
+                return Ok(());
+            };
+            let is_open = match args.single::<String>().ok().as_deref() {
+                Some("open") => true,
+                Some("closed") => false,
+                _ => {
+                    failure!(
+                        ctx,
+                        msg,
+                        "You must specify whether the status is `open` or `closed`."
+                    );
+                    return Ok(());
+                }
+            };
+            if is_open && (name == RESOLVED || name == CLOSED) {
+                failure!(ctx, msg, "The `{}` status must stay closed.", name);
+                return Ok(());
+            }
+            let position = args.single::<u32>().ok().map(|p| p.saturating_sub(1));
+
+            let status = BugStatus {
+                name,
+                colour: serenity::utils::Colour(colour),
+                marker,
+                reaction,
+                is_open,
+            };
+            set_bug_status(ctx, tracker.server_id, &status, position).await?;
+            termite_success!(
+                ctx,
+                msg,
+                "Saved the status {} `{}`.",
+                status.marker(),
+                status.name
+            );
+        }
+        Some("remove") => {
+            let Some(name) = args
+                .single::<String>()
+                .ok()
+                .as_deref()
+                .and_then(parse_status_name)
+            else {
+                failure!(ctx, msg, "You must provide the status to remove.");
+                return Ok(());
+            };
+            if name == RESOLVED || name == CLOSED {
+                failure!(ctx, msg, "The `{}` status cannot be removed.", name);
+                return Ok(());
+            }
+            if workflow.get(&name).is_none() {
+                failure!(ctx, msg, "There is no `{}` status in this server.", name);
+                return Ok(());
+            }
+            if let Err(e) = remove_bug_status(ctx, tracker.server_id, &name).await {
+                failure!(ctx, msg, "Could not remove the status `{}`: {}", name, e);
+                return Ok(());
+            }
+            termite_success!(ctx, msg, "Removed the status `{}`.", name);
+        }
+        Some(_) => {
+            failure!(
+                ctx,
+                msg,
+                "The first argument must be `set` or `remove`, or nothing to list the statuses."
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(edit)]
+pub async fn bug_edit(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    let Ok(bug_id) = args.single::<String>() else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+        return Ok(());
+    };
+    let Some(bug_id) = parse_bug_id(&bug_id) else {
+        failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        return Ok(());
+    };
+    if !is_tracked_in(ctx, &tracker, bug_id).await {
+        failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+        return Ok(());
+    }
+
+    let Ok(field) = args.single::<BugField>() else {
+        failure!(
+            ctx,
+            msg,
+            "The second argument must be one of `mod`, `forge`, `minecraft`, `steps`, `expected` or `actual`."
+        );
+        return Ok(());
+    };
+
+    let value = args.rest().trim();
+    if value.chars().count() > field.max_length() {
+        failure!(
+            ctx,
+            msg,
+            "The {} must be at most {} characters long!",
+            field,
+            field.max_length()
+        );
+        return Ok(());
+    }
+    let value = if value.is_empty() { None } else { Some(value) };
+
+    if let Err(e) = change_field(ctx, bug_id, field, value, msg.author.id).await {
+        failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+        return Err(e);
+    }
+
+    if value.is_some() {
+        termite_success!(ctx, msg, "{} set for {prefix}-{}", field, bug_id);
+    } else {
+        termite_success!(ctx, msg, "{} cleared for {prefix}-{}", field, bug_id);
+    }
+
+    Ok(())
+}
+
+#[command]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[sub_commands(bug_label_add, bug_label_remove)]
+#[aliases(label, labels)]
+pub async fn bug_label(ctx: &Context, msg: &Message) -> CommandResult {
+    failure!(
+        ctx,
+        msg,
+        "Use `!bug label add <bug id> <labels...>` or `!bug label remove <bug id> <labels...>`"
+    );
+    Ok(())
+}
+
+/// Parses the bug id and the labels of a `!bug label` subcommand
+async fn parse_label_args(
+    ctx: &Context,
+    msg: &Message,
+    tracker: &BugTracker,
+    args: &mut Args,
+) -> CommandResult<Option<(u64, Vec<String>)>> {
+    let Ok(bug_id) = args.single::<String>() else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+        return Ok(None);
+    };
+    let Some(bug_id) = parse_bug_id(&bug_id) else {
+        failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        return Ok(None);
+    };
+
+    let mut labels = Vec::new();
+    for label in args.iter::<String>().filter_map(Result::ok) {
+        if let Some(parsed) = parse_label(&label) {
+            labels.push(parsed);
+        } else {
+            failure!(
+                ctx,
+                msg,
+                "`{}` is not a valid label! Labels can only contain letters, digits, `-`, `_` and `.`, and must be at most 32 characters long.",
+                label
+            );
+            return Ok(None);
+        }
+    }
+    if labels.is_empty() {
+        failure!(ctx, msg, "You must provide at least one label!");
+        return Ok(None);
+    }
+
+    if !is_tracked_in(ctx, tracker, bug_id).await {
+        failure!(
+            ctx,
+            msg,
+            "The bug {}-{} does not exist!",
+            tracker.prefix,
+            bug_id
+        );
+        return Ok(None);
+    }
+
+    Ok(Some((bug_id, labels)))
 }
 
 #[command]
-#[checks(is_lotr_discord, is_admin)]
-#[aliases("status")]
-pub async fn bug_status(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    if let Ok(bug_id) = args.single::<String>() {
-        if let Ok(bug_id) = bug_id
-            .to_uppercase()
-            .trim_start_matches("LOTR-")
-            .parse::<u64>()
-        {
-            if let Ok(new_status) = args.single::<BugStatus>() {
-                let old_status = match change_bug_status(ctx, bug_id, new_status).await {
-                    Ok(old_status) => {
-                        termite_success!(
-                            ctx,
-                            msg,
-                            "Status changed for LOTR-{} from `{}` to `{}`!",
-                            bug_id,
-                            old_status,
-                            new_status
-                        );
-                        old_status
-                    }
-                    Err(e) => {
-                        failure!(ctx, msg, "The bug LOTR-{} does not exist!", bug_id);
-                        return Err(e);
-                    }
-                };
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(add)]
+pub async fn bug_label_add(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    let Some((bug_id, labels)) = parse_label_args(ctx, msg, &tracker, &mut args).await? else {
+        return Ok(());
+    };
 
-                if old_status != new_status {
-                    notify_users(
-                        ctx,
-                        bug_id,
-                        format!(
-                            "A bug you are subscribed to has been changed from `{old_status}` to `{new_status}`"
-                        ),
-                    )
-                    .await?;
-                }
-            } else {
-                failure!(ctx, msg, "The second argument must be a bug status.");
-            }
-        } else {
-            failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+    let mut added = Vec::with_capacity(labels.len());
+    for label in labels {
+        if add_label(ctx, bug_id, &label, msg.author.id).await? {
+            added.push(format!("`{label}`"));
         }
+    }
+
+    if added.is_empty() {
+        failure!(ctx, msg, "{prefix}-{} already has these labels", bug_id);
     } else {
-        failure!(ctx, msg, "The first argument must be a bug id.");
+        termite_success!(
+            ctx,
+            msg,
+            "Added {} to {prefix}-{}",
+            added.join(", "),
+            bug_id
+        );
     }
+
     Ok(())
 }
 
 #[command]
-#[checks(is_lotr_discord, is_admin)]
-pub async fn resolve(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    if let Ok(bug_id) = args.single::<String>() {
-        if let Ok(bug_id) = bug_id
-            .to_uppercase()
-            .trim_start_matches("LOTR-")
-            .parse::<u64>()
-        {
-            if let Err(e) = change_bug_status(ctx, bug_id, BugStatus::Resolved).await {
-                failure!(ctx, msg, "The bug LOTR-{} does not exist!", bug_id);
-                return Err(e);
-            }
-            termite_success!(ctx, msg, "LOTR-{} has been marked as resolved.", bug_id);
-            notify_users(
-                ctx,
-                bug_id,
-                "A bug you are subscribed to has been marked as resolved.",
-            )
-            .await?;
-        } else {
-            failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(remove)]
+pub async fn bug_label_remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    let Some((bug_id, labels)) = parse_label_args(ctx, msg, &tracker, &mut args).await? else {
+        return Ok(());
+    };
+
+    let mut removed = Vec::with_capacity(labels.len());
+    for label in labels {
+        if remove_label(ctx, bug_id, &label, msg.author.id).await? {
+            removed.push(format!("`{label}`"));
         }
+    }
+
+    if removed.is_empty() {
+        failure!(ctx, msg, "{prefix}-{} does not have these labels", bug_id);
     } else {
-        failure!(ctx, msg, "The first argument must be a bug id.");
+        termite_success!(
+            ctx,
+            msg,
+            "Removed {} from {prefix}-{}",
+            removed.join(", "),
+            bug_id
+        );
     }
+
     Ok(())
 }
 
 #[command]
-#[checks(is_lotr_discord, is_admin)]
-#[aliases(close)]
-pub async fn bug_close(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    if let Ok(bug_id) = args.single::<String>() {
-        if let Ok(bug_id) = bug_id
-            .to_uppercase()
-            .trim_start_matches("LOTR-")
-            .parse::<u64>()
-        {
-            if let Err(e) = change_bug_status(ctx, bug_id, BugStatus::Closed).await {
-                failure!(ctx, msg, "The bug LOTR-{} does not exist!", bug_id);
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
+#[aliases(duplicate, dup)]
+pub async fn bug_duplicate(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
+    let Ok(bug_id) = args.single::<String>() else {
+        failure!(ctx, msg, "The first argument must be a bug id.");
+        return Ok(());
+    };
+    if args.current().map(|s| s.eq_ignore_ascii_case("of")) == Some(true) {
+        args.advance();
+    }
+    let Ok(original_id) = args.single::<String>() else {
+        failure!(
+            ctx,
+            msg,
+            "You must specify the original bug: `!bug duplicate <bug id> of <bug id>`."
+        );
+        return Ok(());
+    };
+
+    let Some(bug_id) = parse_bug_id(&bug_id) else {
+        failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        return Ok(());
+    };
+    let Some(original_id) = parse_bug_id(&original_id) else {
+        failure!(ctx, msg, "`{}` is not a valid bug id!", original_id);
+        return Ok(());
+    };
+
+    if bug_id == original_id {
+        failure!(ctx, msg, "A bug cannot be a duplicate of itself!");
+        return Ok(());
+    }
+
+    let Some(bug) = get_bug_from_id(ctx, bug_id)
+        .await
+        .ok()
+        .filter(|bug| bug.guild_id == tracker.server_id)
+    else {
+        failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+        return Ok(());
+    };
+    let Some(original) = get_bug_from_id(ctx, original_id)
+        .await
+        .ok()
+        .filter(|bug| bug.guild_id == tracker.server_id)
+    else {
+        failure!(ctx, msg, "The bug {prefix}-{} does not exist!", original_id);
+        return Ok(());
+    };
+
+    if let Some(id) = bug.duplicate_of {
+        failure!(
+            ctx,
+            msg,
+            "{prefix}-{} is already a duplicate of {prefix}-{}!",
+            bug_id,
+            id
+        );
+        return Ok(());
+    }
+    if let Some(id) = original.duplicate_of {
+        failure!(
+            ctx,
+            msg,
+            "{prefix}-{} is itself a duplicate of {prefix}-{}!",
+            original_id,
+            id
+        );
+        return Ok(());
+    }
+
+    let closed = tracker_workflow(ctx, &tracker).await.closed();
+    let (old_status, subscribers) =
+        match merge_duplicate(ctx, bug_id, original_id, closed.clone(), msg.author.id).await {
+            Ok(merged) => merged,
+            Err(e) => {
+                failure!(
+                    ctx,
+                    msg,
+                    "Could not merge {prefix}-{} into {prefix}-{}",
+                    bug_id,
+                    original_id
+                );
                 return Err(e);
             }
-            termite_success!(ctx, msg, "LOTR-{} has been marked as closed.", bug_id);
-            notify_users(
-                ctx,
-                bug_id,
-                "A bug you are subscribed to has been marked as closed.",
-            )
-            .await?;
-        } else {
-            failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
+        };
+
+    let notification_result = notify_subscribers(
+        ctx,
+        bug_id,
+        subscribers,
+        format!(
+            "A bug you are subscribed to has been marked as a duplicate of {prefix}-{original_id} \
+and changed from `{old_status}` to `{}`.
+You have been subscribed to {prefix}-{original_id} instead, and the links of the duplicate have been copied over.",
+            closed
+        ),
+    )
+    .await;
+
+    // imported bugs may have no reported message to link to
+    if let Some(report_link) = bug.message_link() {
+        if add_link(
+            ctx,
+            original_id,
+            &report_link,
+            &format!("Duplicate report {prefix}-{bug_id}"),
+            msg.author.id,
+        )
+        .await
+        .is_none()
+        {
+            println!("Could not link duplicate report {prefix}-{bug_id} to {prefix}-{original_id}");
         }
-    } else {
-        failure!(ctx, msg, "The first argument must be a bug id.");
     }
-    Ok(())
+
+    termite_success!(
+        ctx,
+        msg,
+        "{prefix}-{} has been marked as a duplicate of {prefix}-{}.",
+        bug_id,
+        original_id
+    );
+
+    notification_result
 }
 
 #[command]
-#[checks(is_lotr_discord, is_admin)]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
 #[sub_commands(bug_link_remove)]
 #[aliases(link)]
 pub async fn bug_link(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
     if let Ok(bug_id) = args.single::<String>() {
-        if let Ok(bug_id) = bug_id
-            .to_uppercase()
-            .trim_start_matches("LOTR-")
-            .parse::<u64>()
-        {
+        if let Some(bug_id) = parse_bug_id(&bug_id) {
+            if !is_tracked_in(ctx, &tracker, bug_id).await {
+                failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                return Ok(());
+            }
             if let Some(message) = &msg.referenced_message {
                 let title = args.rest();
                 if title.is_empty() {
                     failure!(ctx, msg, "Specify a title for your message link!");
                     return Ok(());
                 }
-                if let Some(link_id) = add_link(ctx, bug_id, &message.link(), title).await {
-                    termite_success!(ctx, msg, "Added link #{} to LOTR-{}", link_id, bug_id);
+                if let Some(link_id) =
+                    add_link(ctx, bug_id, &message.link(), title, msg.author.id).await
+                {
+                    termite_success!(ctx, msg, "Added link #{} to {prefix}-{}", link_id, bug_id);
                     notify_users(
                         ctx,
                         bug_id,
@@ -869,7 +3332,7 @@ pub async fn bug_link(ctx: &Context, msg: &Message, mut args: Args) -> CommandRe
                     )
                     .await?;
                 } else {
-                    failure!(ctx, msg, "LOTR-{} does not exist!", bug_id);
+                    failure!(ctx, msg, "{prefix}-{} does not exist!", bug_id);
                 }
             } else if let Some(link) = args
                 .single::<String>()
@@ -881,8 +3344,8 @@ pub async fn bug_link(ctx: &Context, msg: &Message, mut args: Args) -> CommandRe
                     failure!(ctx, msg, "Specify a title for your message link!");
                     return Ok(());
                 }
-                if let Some(link_id) = add_link(ctx, bug_id, &link, title).await {
-                    termite_success!(ctx, msg, "Added link #{} to LOTR-{}", link_id, bug_id);
+                if let Some(link_id) = add_link(ctx, bug_id, &link, title, msg.author.id).await {
+                    termite_success!(ctx, msg, "Added link #{} to {prefix}-{}", link_id, bug_id);
                     notify_users(
                         ctx,
                         bug_id,
@@ -890,7 +3353,7 @@ pub async fn bug_link(ctx: &Context, msg: &Message, mut args: Args) -> CommandRe
                     )
                     .await?;
                 } else {
-                    failure!(ctx, msg, "LOTR-{} does not exist!", bug_id);
+                    failure!(ctx, msg, "{prefix}-{} does not exist!", bug_id);
                 }
             } else {
                 failure!(ctx, msg, "You need to either reference a message or specify a link to add to the bug report.");
@@ -905,23 +3368,29 @@ pub async fn bug_link(ctx: &Context, msg: &Message, mut args: Args) -> CommandRe
 }
 
 #[command]
-#[checks(is_admin, is_lotr_discord)]
+#[checks(is_admin, is_bugtracker)]
+#[only_in(guilds)]
 #[aliases(remove)]
 pub async fn bug_link_remove(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
     if let Ok(bug_id) = args.single::<String>() {
-        if let Ok(bug_id) = bug_id
-            .to_uppercase()
-            .trim_start_matches("LOTR-")
-            .parse::<u64>()
-        {
+        if let Some(bug_id) = parse_bug_id(&bug_id) {
+            if !is_tracked_in(ctx, &tracker, bug_id).await {
+                failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                return Ok(());
+            }
             let link_id = args.single::<String>();
             if let Ok(link_id) = link_id {
                 if let Ok(link_id) = link_id.trim_start_matches('#').parse::<u64>() {
-                    if remove_link(ctx, bug_id, link_id).await.is_ok() {
+                    if remove_link(ctx, bug_id, link_id, msg.author.id)
+                        .await
+                        .is_ok()
+                    {
                         termite_success!(
                             ctx,
                             msg,
-                            "Successfully removed link #{} from LOTR-{}",
+                            "Successfully removed link #{} from {prefix}-{}",
                             link_id,
                             bug_id
                         );
@@ -929,7 +3398,7 @@ pub async fn bug_link_remove(ctx: &Context, msg: &Message, mut args: Args) -> Co
                         failure!(
                             ctx,
                             msg,
-                            "Link #{} does not exist in LOTR-{}",
+                            "Link #{} does not exist in {prefix}-{}",
                             link_id,
                             bug_id
                         );
@@ -950,22 +3419,27 @@ pub async fn bug_link_remove(ctx: &Context, msg: &Message, mut args: Args) -> Co
 }
 
 #[command]
-#[checks(is_lotr_discord, is_admin)]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
 #[aliases(toggle)]
 pub async fn bug_toggle_edition(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
     if let Ok(bug_id) = args.single::<String>() {
-        if let Ok(bug_id) = bug_id
-            .to_uppercase()
-            .trim_start_matches("LOTR-")
-            .parse::<u64>()
-        {
+        if let Some(bug_id) = parse_bug_id(&bug_id) {
+            if !is_tracked_in(ctx, &tracker, bug_id).await {
+                failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                return Ok(());
+            }
             if let Ok(category) = args.single::<BugCategory>() {
-                if let Some(old_category) = change_category(ctx, bug_id, category).await {
+                if let Some(old_category) =
+                    change_category(ctx, bug_id, category, msg.author.id).await
+                {
                     if category != old_category {
                         termite_success!(
                             ctx,
                             msg,
-                            "LOTR-{} has been changed from {} to {}",
+                            "{prefix}-{} has been changed from {} to {}",
                             bug_id,
                             old_category,
                             category
@@ -980,7 +3454,7 @@ pub async fn bug_toggle_edition(ctx: &Context, msg: &Message, mut args: Args) ->
                         .await?;
                     }
                 } else {
-                    failure!(ctx, msg, "The bug LOTR-{} does not exist!", bug_id);
+                    failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
                 }
             } else {
                 failure!(
@@ -999,23 +3473,34 @@ pub async fn bug_toggle_edition(ctx: &Context, msg: &Message, mut args: Args) ->
 }
 
 #[command]
-#[checks(is_lotr_discord, is_admin)]
+#[checks(is_bugtracker, is_admin)]
+#[only_in(guilds)]
 #[aliases(rename)]
 pub async fn bug_rename(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let tracker = current_tracker(ctx, msg).await;
+    let prefix = &tracker.prefix;
     if let Ok(bug_id) = args.single::<String>() {
-        if let Ok(bug_id) = bug_id
-            .to_uppercase()
-            .trim_start_matches("LOTR-")
-            .parse::<u64>()
-        {
+        if let Some(bug_id) = parse_bug_id(&bug_id) {
+            if !is_tracked_in(ctx, &tracker, bug_id).await {
+                failure!(ctx, msg, "The bug {prefix}-{} does not exist!", bug_id);
+                return Ok(());
+            }
             let new_title = args.rest();
             if new_title.is_empty() {
-                failure!(ctx, msg, "You must specify a new title for LOTR-{}", bug_id);
-            } else if change_title(ctx, bug_id, new_title).await.is_ok() {
+                failure!(
+                    ctx,
+                    msg,
+                    "You must specify a new title for {prefix}-{}",
+                    bug_id
+                );
+            } else if change_title(ctx, bug_id, new_title, msg.author.id)
+                .await
+                .is_ok()
+            {
                 termite_success!(
                     ctx,
                     msg,
-                    "Successfully changed the title of LOTR-{}",
+                    "Successfully changed the title of {prefix}-{}",
                     bug_id
                 );
                 notify_users(
@@ -1025,7 +3510,7 @@ pub async fn bug_rename(ctx: &Context, msg: &Message, mut args: Args) -> Command
                 )
                 .await?;
             } else {
-                failure!(ctx, msg, "LOTR-{} does not exist!", bug_id);
+                failure!(ctx, msg, "{prefix}-{} does not exist!", bug_id);
             }
         } else {
             failure!(ctx, msg, "`{}` is not a valid bug id!", bug_id);
@@ -1036,66 +3521,246 @@ pub async fn bug_rename(ctx: &Context, msg: &Message, mut args: Args) -> Command
     Ok(())
 }
 
-#[command]
-#[aliases(statistics)]
-pub async fn stats(ctx: &Context, msg: &Message) -> CommandResult {
-    if let Some(counts) = get_bug_statistics(ctx).await {
-        msg.channel_id
-            .send_message(ctx, |m| {
-                m.embed(|e| {
-                    e.author(|a| {
-                        a.name("LOTR Mod Bugtracker");
-                        a.icon_url(crate::constants::TERMITE_IMAGE);
-                        a
-                    });
-                    e.colour(serenity::utils::Colour::TEAL);
-                    e.field(
-                        "Bugtracker statistics",
-                        format!(
-                            "{} resolved
-{} closed
-{} forge or vanilla
-
+pub fn format_bug_statistics(counts: &BugCounts) -> String {
+    let format_counts = |open| {
+        counts
+            .statuses
+            .iter()
+            .filter(|(status, _)| status.is_open == open)
+            .map(|(status, count)| format!("{} {}: {}\n", status.marker(), status, count))
+            .collect::<String>()
+    };
+    format!(
+        "{}
 _Open bugs: {}_
-{} with low priority
-{} with medium priority
-{} with high priority
-{} critical bugs
-
+{}
 **Total: {} tracked bugs**
 \t_including {} legacy bugs_
 ",
-                            counts.resolved,
-                            counts.closed,
-                            counts.forgevanilla,
-                            counts.total - counts.resolved - counts.closed - counts.forgevanilla,
-                            counts.low,
-                            counts.medium,
-                            counts.high,
-                            counts.critical,
-                            counts.total,
-                            counts.legacy,
-                        ),
-                        false,
-                    );
-                    e
-                })
-            })
-            .await?;
+        format_counts(false),
+        counts.open(),
+        format_counts(true),
+        counts.total,
+        counts.legacy,
+    )
+}
+
+/// Formats a duration in days and hours, or in hours and minutes under a day
+fn format_duration(duration: chrono::Duration) -> String {
+    let hours = duration.num_hours();
+    if hours < 24 {
+        format!("{}h {}m", hours, duration.num_minutes() % 60)
     } else {
-        failure!(ctx, msg, "Could not fetch bugtracker statistics");
+        format!("{}d {}h", hours / 24, hours % 24)
+    }
+}
+
+fn format_time_statistics(stats: &BugTimeStatistics) -> (String, String, String) {
+    let weeks = stats
+        .weeks
+        .iter()
+        .map(|week| {
+            format!(
+                "`{}`  {} opened, {} resolved\n",
+                week.start.format("%d/%m"),
+                week.opened,
+                week.resolved
+            )
+        })
+        .collect::<String>();
+
+    let by_category = stats
+        .by_category
+        .iter()
+        .filter_map(|(category, time)| Some((category.to_string(), time.mean()?, time.count)));
+    let by_status = stats.by_status.iter().filter_map(|(status, time)| {
+        Some((
+            format!("{} {}", status.marker(), status),
+            time.mean()?,
+            time.count,
+        ))
+    });
+    let resolution_times = by_category
+        .chain(by_status)
+        .map(|(name, mean, count)| {
+            format!(
+                "{name}: {} ({count} bug{})\n",
+                format_duration(mean),
+                if count == 1 { "" } else { "s" }
+            )
+        })
+        .collect::<String>();
+
+    let mut oldest_open = String::new();
+    for bug in &stats.oldest_open {
+        let line = format!("{} {bug}\n", bug.status.marker());
+        if oldest_open.len() + line.len() > 1024 {
+            break;
+        }
+        oldest_open.push_str(&line);
+    }
+
+    (weeks, resolution_times, oldest_open)
+}
+
+#[command]
+#[checks(is_bugtracker)]
+#[only_in(guilds)]
+#[aliases(statistics)]
+pub async fn stats(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    const MAX_WEEKS: u32 = 26;
+
+    let tracker = current_tracker(ctx, msg).await;
+    let server_id = tracker.server_id;
+    let mut weeks = 8;
+    let mut chart = false;
+    for arg in args.raw() {
+        match arg.parse::<u32>() {
+            Ok(n) if (1..=MAX_WEEKS).contains(&n) => weeks = n,
+            Ok(_) => {
+                failure!(
+                    ctx,
+                    msg,
+                    "The number of weeks must be between 1 and {}.",
+                    MAX_WEEKS
+                );
+                return Ok(());
+            }
+            Err(_) if arg == "chart" => chart = true,
+            Err(_) => (),
+        }
     }
+
+    let (Some(counts), Some(time_stats)) = (
+        get_bug_statistics(ctx, server_id).await,
+        get_time_statistics(ctx, server_id, weeks).await,
+    ) else {
+        failure!(ctx, msg, "Could not fetch bugtracker statistics");
+        return Ok(());
+    };
+    let (weekly, resolution_times, oldest_open) = format_time_statistics(&time_stats);
+    let chart = chart.then(|| {
+        let labels = time_stats
+            .weeks
+            .iter()
+            .map(|week| week.start.format("%d/%m").to_string())
+            .collect::<Vec<_>>();
+        crate::chart::grouped_bar_chart(
+            &time_stats
+                .weeks
+                .iter()
+                .zip(&labels)
+                .map(|(week, label)| (label.as_str(), week.opened, week.resolved))
+                .collect::<Vec<_>>(),
+        )
+    });
+    let name = tracker_name(ctx, server_id).await;
+
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                e.author(|a| {
+                    a.name(&name);
+                    a.icon_url(crate::constants::TERMITE_IMAGE);
+                    a
+                });
+                e.colour(serenity::utils::Colour::TEAL);
+                e.field(
+                    "Bugtracker statistics",
+                    format_bug_statistics(&counts),
+                    false,
+                );
+                if !counts.labels.is_empty() {
+                    let mut labels = String::new();
+                    for (label, count) in &counts.labels {
+                        let line = format!("`{label}`: {count}\n");
+                        if labels.len() + line.len() > 1024 {
+                            break;
+                        }
+                        labels.push_str(&line);
+                    }
+                    e.field("Open bugs by label", labels, false);
+                }
+                e.field(
+                    format!("Last {weeks} week{}", if weeks == 1 { "" } else { "s" }),
+                    weekly,
+                    false,
+                );
+                if !resolution_times.is_empty() {
+                    e.field("Mean time to resolution", resolution_times, false);
+                }
+                if !oldest_open.is_empty() {
+                    e.field("Oldest open bugs", oldest_open, false);
+                }
+                if chart.is_some() {
+                    e.image("attachment://bug_statistics.png");
+                    e.footer(|f| f.text("Red: opened bugs • Green: resolved bugs"));
+                }
+                e
+            });
+            if let Some(chart) = &chart {
+                m.add_file((chart.as_slice(), "bug_statistics.png"));
+            }
+            m
+        })
+        .await?;
+
     Ok(())
 }
 
 #[command]
-#[checks(is_admin, is_lotr_discord)]
+#[checks(is_admin, is_bugtracker)]
 #[aliases(help)]
 pub async fn bugtracker_help(ctx: &Context, msg: &Message) -> CommandResult {
     crate::commands::help::display_bugtracker_help(ctx, msg).await
 }
 
 #[command]
+#[aliases(settings)]
+pub async fn notification_settings(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let Some(mode) = args.current() else {
+        let mode = get_notification_mode(ctx, msg.author.id).await;
+        msg.reply(
+            ctx,
+            format!(
+                "Your bug notifications are set to `{mode}`.
+Use  `!bug notifications settings <immediate|daily|muted>`  to change it."
+            ),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let Ok(mode) = mode.parse::<NotificationMode>() else {
+        failure!(
+            ctx,
+            msg,
+            "The notification mode must be one of `immediate`, `daily` or `muted`."
+        );
+        return Ok(());
+    };
+
+    set_notification_mode(ctx, msg.author.id, mode).await?;
+
+    termite_success!(
+        ctx,
+        msg,
+        "{}",
+        match mode {
+            NotificationMode::Immediate =>
+                "You will now receive a DM for every notification of the bugs you are subscribed to.",
+            NotificationMode::Daily =>
+                "You will now receive a single DM a day with the notifications of the bugs you are subscribed to.",
+            NotificationMode::Muted =>
+                "You will not receive bug notifications anymore. Your subscriptions are kept.",
+        }
+    );
+
+    Ok(())
+}
+
+#[command]
+#[sub_commands(notification_settings)]
 pub async fn notifications(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let closed = args
         .single::<String>()
@@ -1103,8 +3768,12 @@ pub async fn notifications(ctx: &Context, msg: &Message, mut args: Args) -> Comm
         .unwrap_or_default();
 
     let list = get_notifications_for_user(ctx, msg.author.id, closed).await?;
+    let mut bugs = Vec::with_capacity(list.len());
+    for bug_id in list {
+        bugs.push(format!("{}-{bug_id}", get_bug_prefix(ctx, bug_id).await));
+    }
 
-    if list.is_empty() {
+    if bugs.is_empty() {
         msg.reply(
             ctx,
             if closed {
@@ -1121,18 +3790,16 @@ including those from closed or resolved bugs."
         msg.channel_id
             .send_message(ctx, |m| {
                 m.embed(|e| {
+                    // subscriptions may span the bugtrackers of several guilds
                     e.author(|a| {
-                        a.name("LOTR Mod Bugtracker")
+                        a.name("Bugtracker")
                             .icon_url(crate::constants::TERMITE_IMAGE)
                     })
                     .colour(serenity::utils::Colour::TEAL)
                     .title("Bug notifications")
                     .description(format!(
                         "_List of bugs you are subscribed to_\n\n{}",
-                        list.iter()
-                            .map(|id| format!("LOTR-{id}"))
-                            .collect::<Vec<_>>()
-                            .join(", "),
+                        bugs.join(", "),
                     ))
                     .footer(|f| {
                         f.text(if closed {
@@ -1152,10 +3819,16 @@ including those from closed or resolved bugs."
 
 #[command]
 pub async fn unsubscribe(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let Ok(bug_id) = args.single::<u64>() else {
+    let Some(bug_id) = args
+        .single::<String>()
+        .ok()
+        .as_deref()
+        .and_then(parse_bug_id)
+    else {
         failure!(ctx, msg, "The first argument must be a bug id!");
         return Ok(());
     };
+    let prefix = get_bug_prefix(ctx, bug_id).await;
 
     if is_notified_user(ctx, bug_id, msg.author.id).await != Some(true) {
         failure!(ctx, msg, "You are not subscribed to this bug!");
@@ -1165,14 +3838,14 @@ pub async fn unsubscribe(ctx: &Context, msg: &Message, mut args: Args) -> Comman
     if let Err(e) =
         crate::database::bug_reports::remove_notified_user(ctx, bug_id, msg.author.id).await
     {
-        failure!(ctx, msg, "Could not unsubscribe from LOTR-{}", bug_id);
+        failure!(ctx, msg, "Could not unsubscribe from {prefix}-{}", bug_id);
         return Err(e);
     }
 
     crate::success!(
         ctx,
         msg,
-        "Successfully unsubscribed from LOTR-{}.
+        "Successfully unsubscribed from {prefix}-{}.
 You will no longer be notified if this bug is edited, closed or resolved.",
         bug_id
     );
@@ -1182,10 +3855,16 @@ You will no longer be notified if this bug is edited, closed or resolved.",
 
 #[command]
 pub async fn subscribe(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
-    let Ok(bug_id) = args.single::<u64>() else {
+    let Some(bug_id) = args
+        .single::<String>()
+        .ok()
+        .as_deref()
+        .and_then(parse_bug_id)
+    else {
         failure!(ctx, msg, "The first argument must be a bug id!");
         return Ok(());
     };
+    let prefix = get_bug_prefix(ctx, bug_id).await;
 
     if is_notified_user(ctx, bug_id, msg.author.id).await != Some(false) {
         failure!(ctx, msg, "You are already subscribed to this bug!");
@@ -1195,14 +3874,14 @@ pub async fn subscribe(ctx: &Context, msg: &Message, mut args: Args) -> CommandR
     if let Err(e) =
         crate::database::bug_reports::add_notified_user(ctx, bug_id, msg.author.id).await
     {
-        failure!(ctx, msg, "Could not subscribe to LOTR-{}", bug_id);
+        failure!(ctx, msg, "Could not subscribe to {prefix}-{}", bug_id);
         return Err(e);
     }
 
     crate::success!(
         ctx,
         msg,
-        "Successfully subscribed to LOTR-{}.
+        "Successfully subscribed to {prefix}-{}.
 You will be notified if this bug is edited, closed or resolved.",
         bug_id
     );
@@ -1,3 +1,5 @@
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serenity::client::Context;
 use serenity::framework::standard::CommandError;
@@ -5,18 +7,22 @@ use serenity::framework::standard::{macros::command, Args, CommandResult};
 use serenity::framework::Framework;
 use serenity::futures::future::join;
 use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, RoleId};
 use serenity::prelude::Mentionable;
 
 use crate::announcement::{announce, Announcement};
-use crate::constants::{MANAGE_BOT_PERMS, OWNER_ID, RESERVED_NAMES};
+use crate::command_usage::{flush_usage, record_use};
+use crate::constants::{MANAGE_BOT_PERMS, MAX_COMMAND_BUNDLE_SIZE, OWNER_ID, RESERVED_NAMES};
 use crate::database::{
     blacklist::check_blacklist,
+    command_usage::{get_last_use, get_usage, summarize_usage, unused_commands, weekly_usage},
     custom_commands::{
-        add_custom_command, check_command_exists, get_command_data, get_custom_commands_list,
-        remove_custom_command,
+        add_custom_command, check_command_exists, get_command_data, get_command_history,
+        get_command_revision, get_custom_commands_list, remove_custom_command, CommandRevision,
     },
 };
-use crate::utils::{get_json_from_message, has_permission, to_json_safe_string, NotInGuild};
+use crate::template::{self, Placeholders};
+use crate::utils::{get_json_from_message, has_permission, line_diff, NotInGuild};
 use crate::{check::*, FrameworkKey};
 use crate::{failure, handle_json_error, is_admin, success};
 
@@ -79,7 +85,17 @@ pub async fn manual_dispatch(
 
 #[command]
 #[aliases("command")]
-#[sub_commands(define, custom_command_remove, custom_command_display)]
+#[sub_commands(
+    define,
+    custom_command_remove,
+    custom_command_display,
+    custom_command_history,
+    custom_command_diff,
+    custom_command_rollback,
+    custom_command_export,
+    custom_command_import,
+    custom_command_stats
+)]
 pub async fn custom_command(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let Some(server_id) = msg.guild_id else {
         // No custom commands for DMs!
@@ -92,8 +108,7 @@ pub async fn custom_command(ctx: &Context, msg: &Message, mut args: Args) -> Com
     if let Some(command_data) = get_command_data(ctx, server_id, &name, false).await {
         println!("Custom command execution: {}", msg.content);
 
-        let mut message: Announcement =
-            serde_json::from_str(&command_data.body.replace("\\$", "$"))?;
+        let mut message: Announcement = serde_json::from_str(&command_data.body)?;
         let mut delete = message.extra["self_delete"].as_bool().unwrap_or_default();
 
         let default_command_type = message.extra["type"].as_str();
@@ -162,88 +177,54 @@ Channel: {:?}\nMessage: {}\n=== END ===",
             }
         }
 
-        let mut command_body = command_data.body;
         if let Some(subcommand) = subcommand {
+            let subcommand = subcommands_object[subcommand]
+                .as_str()
+                .unwrap_or(subcommand);
             if subcommands_object[subcommand].is_object() {
-                command_body = serde_json::to_string(&subcommands_object[subcommand])?;
-                message = serde_json::from_str(&command_body)?;
+                message = serde_json::from_value(subcommands_object[subcommand].clone())?;
                 args.advance();
-            } else if let Some(subcommand_alias) = subcommands_object[subcommand].as_str() {
-                if subcommands_object[subcommand_alias].is_object() {
-                    command_body = serde_json::to_string(&subcommands_object[subcommand_alias])?;
-                    message = serde_json::from_str(&command_body)?;
-                    args.advance();
-                }
             }
         }
 
-        if command_body.contains('$') {
-            let mut changed = false;
-
-            let mut b = command_body
-                .replace('$', "\u{200B}$")
-                .replace("\\\u{200B}$", "\\$");
-
-            if b.contains("\u{200B}$me")
-                || b.contains("\u{200B}$ping")
-                || b.contains("\u{200B}$channel")
-            {
-                changed = true;
-                b = b
-                    .replace("\u{200B}$me", &to_json_safe_string(&msg.author.name))
-                    .replace("\u{200B}$ping", &msg.author.mention().to_string())
-                    .replace("\u{200B}$channel", &msg.channel_id.mention().to_string());
-            }
-
-            if b.contains("\u{200B}$as_url") {
-                changed = true;
-                b = b.replace("\u{200B}$as_url", &urlencoding::encode(args.rest()));
-            }
+        let block_context = BlockContext {
+            argc: args.remaining(),
+            channel_id: msg.channel_id,
+            roles: msg.member.as_ref().map_or(&[][..], |m| m.roles.as_slice()),
+        };
+        for _ in 0..MAX_BLOCK_DEPTH {
+            let Some(block) = block_context.select(&message.extra) else {
+                break;
+            };
+            message = serde_json::from_value(block.clone())?;
+        }
 
-            if b.contains("\u{200B}$args") {
-                changed = true;
-                b = b.replace("\u{200B}$args", &to_json_safe_string(args.rest()));
-            } else {
-                args.iter::<String>()
+        if command_data.body.contains('$') {
+            let rest = args.rest().to_string();
+            let placeholders = Placeholders {
+                me: msg.author.name.clone(),
+                ping: msg.author.mention().to_string(),
+                channel: msg.channel_id.mention().to_string(),
+                args: rest.replace('@', "@\u{200B}"),
+                positional: args
+                    .iter::<String>()
                     .filter_map(Result::ok)
-                    .enumerate()
-                    .for_each(|(i, arg)| {
-                        let key = format!("\u{200B}${i}");
-                        if b.contains(&key) {
-                            changed = true;
-                            b = b.replace(
-                                key.as_str(),
-                                &to_json_safe_string(
-                                    arg.replace('$', "\\$")
-                                        .replace('@', "@\u{200B}")
-                                        .trim_matches('"'),
-                                ),
-                            );
-                        }
-                    });
-            }
+                    .map(|arg| arg.trim_matches('"').replace('@', "@\u{200B}"))
+                    .collect(),
+                default_args: message.extra["default_args"]
+                    .as_array()
+                    .map(|a| {
+                        a.iter()
+                            .map(|arg| arg.as_str().unwrap_or_default().to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                as_url: urlencoding::encode(&rest).into_owned(),
+            };
 
-            let argc = args.len() - 1;
-            if changed {
-                message = serde_json::from_str(&b.replace("\\$", "$"))?;
-            }
-            changed = false;
-            if let Value::Array(a) = &message.extra["default_args"] {
-                for (i, arg) in a[argc.min(a.len())..]
-                    .iter()
-                    .filter_map(Value::as_str)
-                    .enumerate()
-                {
-                    changed = true;
-                    println!("Default argument '{arg}'");
-                    b = b.replace(
-                        format!("\u{200B}${}", i + argc).as_str(),
-                        &arg.replace('$', "\\$"),
-                    );
-                }
-            }
-            if changed {
-                message = serde_json::from_str(&b.replace("\\$", "$"))?;
+            message.map_text(|text| *text = placeholders.render(text));
+            if let Some(Value::String(command)) = message.extra.get_mut("command") {
+                *command = placeholders.render(command);
             }
         }
 
@@ -255,10 +236,12 @@ Channel: {:?}\nMessage: {}\n=== END ===",
         if is_alias {
             if let Some(command) = message.extra["command"].as_str() {
                 manual_dispatch(ctx.clone(), msg, command).await?;
+                record_use(ctx, server_id, &name).await;
                 return Ok(());
             }
         }
         announce(ctx, msg.channel_id, &message).await?;
+        record_use(ctx, server_id, &name).await;
         if delete {
             msg.delete(ctx).await?;
         }
@@ -273,6 +256,255 @@ Channel: {:?}\nMessage: {}\n=== END ===",
     Ok(())
 }
 
+/// Maximum nesting of `"if"` and `"random"` blocks in a custom command
+const MAX_BLOCK_DEPTH: usize = 8;
+
+/// What the `"if"` blocks of a custom command are evaluated against
+struct BlockContext<'a> {
+    argc: usize,
+    channel_id: ChannelId,
+    roles: &'a [RoleId],
+}
+
+/// Parses an id, a mention or an array of them
+fn block_ids(value: &Value) -> Vec<u64> {
+    match value {
+        Value::Array(a) => a.iter().flat_map(block_ids).collect(),
+        Value::Number(n) => n.as_u64().into_iter().collect(),
+        Value::String(s) => s
+            .trim()
+            .trim_matches(|c| "<>#@&".contains(c))
+            .parse()
+            .ok()
+            .into_iter()
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn block_weight(choice: &Value) -> f64 {
+    choice["weight"].as_f64().unwrap_or(1.0).max(0.0)
+}
+
+impl BlockContext<'_> {
+    /// Checks that all the conditions of an `"if"` branch hold
+    fn matches(&self, branch: &Value) -> bool {
+        let argc = self.argc as u64;
+        let check_args = |key: &str, check: fn(u64, u64) -> bool| {
+            branch[key].as_u64().map_or(true, |n| check(argc, n))
+        };
+
+        check_args("args", |argc, n| argc == n)
+            && check_args("min_args", |argc, n| argc >= n)
+            && check_args("max_args", |argc, n| argc <= n)
+            && (branch["channel"].is_null()
+                || block_ids(&branch["channel"]).contains(&self.channel_id.0))
+            && (branch["role"].is_null()
+                || block_ids(&branch["role"])
+                    .into_iter()
+                    .any(|id| self.roles.contains(&RoleId(id))))
+    }
+
+    /// Picks the message to display instead of a command body: the first matching
+    /// `"if"` branch, or the `"else"` message, or a weighted `"random"` choice.
+    fn select<'a>(&self, extra: &'a Value) -> Option<&'a Value> {
+        if let Some(branches) = extra["if"].as_array() {
+            if let Some(branch) = branches.iter().find(|branch| self.matches(branch)) {
+                return Some(&branch["then"]);
+            }
+            if extra["else"].is_object() {
+                return Some(&extra["else"]);
+            }
+        }
+
+        let choices = extra["random"].as_array()?;
+        let mut target = alea::f64() * choices.iter().map(block_weight).sum::<f64>();
+        choices
+            .iter()
+            .find(|choice| {
+                target -= block_weight(choice);
+                target < 0.0
+            })
+            .or_else(|| choices.last())
+    }
+}
+
+/// Checks the `"if"` and `"random"` blocks of a custom command body
+fn validate_blocks(body: &Value, depth: usize) -> Result<(), String> {
+    if depth >= MAX_BLOCK_DEPTH {
+        return Err(format!(
+            "`\"if\"` and `\"random\"` blocks cannot be nested more than {MAX_BLOCK_DEPTH} times"
+        ));
+    }
+    // the type is checked before the blocks are evaluated
+    if depth > 0 && !body["type"].is_null() {
+        return Err(
+            "`\"type\"` can only be set on a command or a subcommand, not inside a block".into(),
+        );
+    }
+
+    if !body["if"].is_null() {
+        let Some(branches) = body["if"].as_array() else {
+            return Err("`\"if\"` must be an array of branches".into());
+        };
+        for (i, branch) in branches.iter().enumerate() {
+            let i = i + 1;
+            if !branch["then"].is_object() {
+                return Err(format!("`\"if\"` branch {i} requires a `\"then\"` message"));
+            }
+            if let Some(key) = ["args", "min_args", "max_args"]
+                .into_iter()
+                .find(|&key| !branch[key].is_null() && !branch[key].is_u64())
+            {
+                return Err(format!(
+                    "`\"{key}\"` in `\"if\"` branch {i} must be a positive integer"
+                ));
+            }
+            if let Some(key) = ["channel", "role"]
+                .into_iter()
+                .find(|&key| !branch[key].is_null() && block_ids(&branch[key]).is_empty())
+            {
+                return Err(format!(
+                    "`\"{key}\"` in `\"if\"` branch {i} must be an id or an array of ids"
+                ));
+            }
+            validate_blocks(&branch["then"], depth + 1)?;
+        }
+        if !body["else"].is_null() {
+            if !body["else"].is_object() {
+                return Err("`\"else\"` must be a message".into());
+            }
+            validate_blocks(&body["else"], depth + 1)?;
+        }
+    }
+
+    if !body["random"].is_null() {
+        let Some(choices) = body["random"].as_array().filter(|c| !c.is_empty()) else {
+            return Err("`\"random\"` must be a non-empty array of messages".into());
+        };
+        for (i, choice) in choices.iter().enumerate() {
+            let i = i + 1;
+            if !choice.is_object() {
+                return Err(format!("`\"random\"` choice {i} must be a message"));
+            }
+            if !choice["weight"].is_null() && !choice["weight"].as_f64().map_or(false, |w| w >= 0.0)
+            {
+                return Err(format!(
+                    "the weight of `\"random\"` choice {i} must be a positive number"
+                ));
+            }
+            validate_blocks(choice, depth + 1)?;
+        }
+        if choices.iter().map(block_weight).sum::<f64>() <= 0.0 {
+            return Err("the `\"random\"` weights cannot all be zero".into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the placeholders of the texts of a custom command body that are
+/// rendered: those of its message, its `"command"`, and those of its blocks
+fn validate_templates(body: &Value) -> Result<(), String> {
+    let mut message: Announcement =
+        serde_json::from_value(body.clone()).map_err(|e| format!("Invalid command: {e}"))?;
+    message
+        .try_map_text(|text| template::validate(text))
+        .and_then(|()| body["command"].as_str().map_or(Ok(()), template::validate))
+        .map_err(|e| format!("Invalid command template: {e}"))?;
+
+    body["if"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|branch| &branch["then"])
+        .chain(std::iter::once(&body["else"]))
+        .chain(body["random"].as_array().into_iter().flatten())
+        .filter(|block| block.is_object())
+        .try_for_each(validate_templates)
+}
+
+/// A custom command that passed the checks of [`prepare_custom_command`]
+struct PreparedCommand {
+    body: String,
+    documentation: Option<String>,
+}
+
+/// Runs the checks of `!define` on a custom command, and builds its documentation
+fn prepare_custom_command(name: &str, mut message: Value) -> Result<PreparedCommand, String> {
+    if RESERVED_NAMES.contains(&name) {
+        return Err(format!(
+            "You cannot add a command with the reserved name `{name}`"
+        ));
+    }
+
+    if message["type"].as_str() == Some("alias") && !message["command"].is_string() {
+        return Err(
+            "Custom commands with the `\"alias\"` type require a `\"command\"` string field."
+                .into(),
+        );
+    }
+
+    let mut documentation = message
+        .as_object_mut()
+        .map(|map| map.remove("documentation").unwrap_or_default())
+        .unwrap_or_default();
+    if let Some(map) = message["subcommands"].as_object() {
+        // validate that all subcommands are well defined
+        if let Some((key, val)) = map.iter().find_map(|(key, val)| {
+            val.as_str().and_then(|v| {
+                if !(map.contains_key(v) && map[v].is_object()) || v == key {
+                    Some((key, v))
+                } else {
+                    None
+                }
+            })
+        }) {
+            return Err(format!("The alias `{key:?}: {val:?}` is not defined!"));
+        }
+        // validate that all aliases subcommands have a "command" field
+        if let Some((key, _val)) = map.iter().find(|(_key, val)| {
+            val["type"].as_str() == Some("alias") && !val["command"].is_string()
+        }) {
+            return Err(format!(
+                "The subcommand `{key:?}` with the `\"alias\"` type requires a `\"command\"` string field."
+            ));
+        }
+
+        let s = map
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<&str>>()
+            .join("`, `");
+        // exported commands already have the list of subcommands
+        let documentation_text = documentation.as_str().unwrap_or_default();
+        documentation = Value::String(format!(
+            "{}\n_Subcommands:_  `{}`",
+            documentation_text
+                .split_once("\n_Subcommands:_")
+                .map_or(documentation_text, |(text, _)| text),
+            s
+        ));
+    }
+    // subcommand aliases are plain strings
+    let bodies = std::iter::once(&message).chain(
+        message["subcommands"]
+            .as_object()
+            .into_iter()
+            .flat_map(|map| map.values())
+            .filter(|body| body.is_object()),
+    );
+    for body in bodies {
+        validate_blocks(body, 0).map_err(|e| format!("Invalid command: {e}"))?;
+        validate_templates(body)?;
+    }
+
+    Ok(PreparedCommand {
+        body: serde_json::to_string_pretty(&message).map_err(|e| e.to_string())?,
+        documentation: documentation.as_str().map(String::from),
+    })
+}
+
 #[command]
 #[checks(is_admin)]
 #[only_in(guilds)]
@@ -281,73 +513,29 @@ pub async fn define(ctx: &Context, msg: &Message, mut args: Args) -> CommandResu
 
     let name: String = args.single::<String>()?.to_lowercase();
 
-    if RESERVED_NAMES.contains(&name.as_str()) {
-        failure!(
-            ctx,
-            msg,
-            "You cannot add a command with the reserved name `{}`",
-            name
-        );
-        return Ok(());
-    }
-
     match get_json_from_message::<Value>(msg).await {
-        Ok(mut message) => {
-            if message["type"].as_str() == Some("alias") && !message["command"].is_string() {
-                failure!(
-                    ctx,
-                    msg,
-                    "Custom commands with the `\"alias\"` type require a `\"command\"` string field."
-                );
-                return Ok(());
-            }
-
-            let mut documentation = message
-                .as_object_mut()
-                .map(|map| map.remove("documentation").unwrap_or_default())
-                .unwrap_or_default();
-            if let Some(map) = message["subcommands"].as_object() {
-                // validate that all subcommands are well defined
-                if let Some((key, val)) = map.iter().find_map(|(key, val)| {
-                    val.as_str().and_then(|v| {
-                        if !(map.contains_key(v) && map[v].is_object()) || v == key {
-                            Some((key, v))
-                        } else {
-                            None
-                        }
-                    })
-                }) {
-                    failure!(ctx, msg, "The alias `{:?}: {:?}` is not defined!", key, val);
-                    return Ok(());
-                }
-                // validate that all aliases subcommands have a "command" field
-                if let Some((key, _val)) = map.iter().find(|(_key, val)| {
-                    val["type"].as_str() == Some("alias") && !val["command"].is_string()
-                }) {
-                    failure!(
-                        ctx,
-                        msg,
-                        "The subcommand `{:?}` with the `\"alias\"` type requires a `\"command\"` string field.",
-                        key
-                    );
+        Ok(message) => {
+            let PreparedCommand {
+                body,
+                documentation,
+            } = match prepare_custom_command(&name, message) {
+                Ok(command) => command,
+                Err(e) => {
+                    failure!(ctx, msg, e);
                     return Ok(());
                 }
+            };
 
-                let s = map
-                    .keys()
-                    .map(String::as_str)
-                    .collect::<Vec<&str>>()
-                    .join("`, `");
-                documentation = Value::String(format!(
-                    "{}\n_Subcommands:_  `{}`",
-                    documentation.as_str().unwrap_or_default(),
-                    s
-                ));
-            }
-            let body = serde_json::to_string_pretty(&message)?;
             println!("adding custom command \"{name}\": {body}\n({documentation:?})");
-            let db_res =
-                add_custom_command(ctx, server_id, &name, &body, documentation.as_str()).await;
+            let db_res = add_custom_command(
+                ctx,
+                server_id,
+                &name,
+                &body,
+                documentation.as_deref(),
+                msg.author.id,
+            )
+            .await;
             if db_res.is_ok()
                 && check_command_exists(ctx, server_id, &name)
                     .await
@@ -468,3 +656,632 @@ async fn custom_command_display(ctx: &Context, msg: &Message, args: Args) -> Com
     }
     Ok(())
 }
+
+/// Number of revisions listed by `!command history`
+const HISTORY_LENGTH: u32 = 20;
+
+fn parse_revision(s: &str) -> Option<u32> {
+    s.trim_start_matches(['r', 'R']).parse().ok()
+}
+
+#[command]
+#[aliases("history", "revisions")]
+#[checks(is_admin)]
+#[only_in(guilds)]
+async fn custom_command_history(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+
+    let name = args.single::<String>()?.to_lowercase();
+
+    let history = get_command_history(ctx, server_id, &name, HISTORY_LENGTH)
+        .await
+        .unwrap_or_default();
+    if history.is_empty() {
+        failure!(
+            ctx,
+            msg,
+            "The custom command `{}` has no saved revision!",
+            name
+        );
+        return Ok(());
+    }
+    let current = get_command_data(ctx, server_id, &name, false)
+        .await
+        .map(|command| command.body);
+
+    let mut current_marked = false;
+    let description = history
+        .iter()
+        .map(|revision| {
+            let is_current = !current_marked && current.as_deref() == Some(&revision.body);
+            current_marked |= is_current;
+            format!(
+                "`r{}` {} by {}{}",
+                revision.revision,
+                revision.timestamp.format("<t:%s:R>"),
+                format_author(revision),
+                if is_current { " (current)" } else { "" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.embed(|e| {
+                e.title(format!("History of the custom command: {name}"));
+                e.description(description);
+                if current.is_none() {
+                    e.footer(|f| {
+                        f.text("This command was deleted, it can be restored with a rollback.")
+                    });
+                }
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+fn format_author(revision: &CommandRevision) -> String {
+    revision
+        .author_id
+        .map_or_else(|| "an unknown user".into(), |id| id.mention().to_string())
+}
+
+fn revision_text(revision: &CommandRevision) -> String {
+    format!(
+        "// documentation: {}\n{}",
+        revision.description.replace('\n', "\n// "),
+        revision.body
+    )
+}
+
+#[command]
+#[aliases("diff")]
+#[checks(is_admin)]
+#[only_in(guilds)]
+async fn custom_command_diff(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+
+    let name = args.single::<String>()?.to_lowercase();
+    let revisions = args
+        .iter::<String>()
+        .filter_map(|arg| parse_revision(&arg.ok()?))
+        .take(2)
+        .collect::<Vec<_>>();
+    // with a single revision, show the changes it made to the previous one
+    let (from, to) = match revisions[..] {
+        [to] => (to.checked_sub(1).filter(|&r| r != 0), to),
+        [from, to] => (Some(from), to),
+        _ => {
+            failure!(
+                ctx,
+                msg,
+                "Usage: `command diff <name> <revision> [other revision]`"
+            );
+            return Ok(());
+        }
+    };
+
+    let Some(new) = get_command_revision(ctx, server_id, &name, to).await else {
+        failure!(
+            ctx,
+            msg,
+            "The custom command `{}` has no revision r{}",
+            name,
+            to
+        );
+        return Ok(());
+    };
+    let old = match from {
+        Some(from) => {
+            let Some(old) = get_command_revision(ctx, server_id, &name, from).await else {
+                failure!(
+                    ctx,
+                    msg,
+                    "The custom command `{}` has no revision r{}",
+                    name,
+                    from
+                );
+                return Ok(());
+            };
+            Some(old)
+        }
+        None => None,
+    };
+
+    let diff = line_diff(
+        &old.as_ref().map(revision_text).unwrap_or_default(),
+        &revision_text(&new),
+        3,
+    );
+    let title = match &old {
+        Some(old) => format!(
+            "Changes of `{name}` from r{} to r{} (by {})",
+            old.revision,
+            new.revision,
+            format_author(&new)
+        ),
+        None => format!("First revision of `{name}` (by {})", format_author(&new)),
+    };
+
+    msg.channel_id
+        .send_message(ctx, |m| {
+            if title.len() + diff.len() < 1980 {
+                m.content(format!(
+                    "{title}\n```diff\n{}```",
+                    diff.replace("```", "`\u{200B}``")
+                ));
+            } else {
+                m.content(title);
+                m.add_file((
+                    diff.as_bytes(),
+                    format!("{name}_r{}.diff", new.revision).as_str(),
+                ));
+            }
+            m.allowed_mentions(|a| a.empty_parse())
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("rollback", "revert")]
+#[checks(is_admin)]
+#[only_in(guilds)]
+async fn custom_command_rollback(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+
+    let name = args.single::<String>()?.to_lowercase();
+    let Some(revision) = args
+        .single::<String>()
+        .ok()
+        .as_deref()
+        .and_then(parse_revision)
+    else {
+        failure!(ctx, msg, "Usage: `command rollback <name> <revision>`");
+        return Ok(());
+    };
+
+    let Some(saved) = get_command_revision(ctx, server_id, &name, revision).await else {
+        failure!(
+            ctx,
+            msg,
+            "The custom command `{}` has no revision r{}",
+            name,
+            revision
+        );
+        return Ok(());
+    };
+
+    println!("Rolling back custom command \"{name}\" to revision {revision}");
+    // the rollback is saved as a new revision, so that it can be undone
+    add_custom_command(
+        ctx,
+        server_id,
+        &name,
+        &saved.body,
+        Some(&saved.description),
+        msg.author.id,
+    )
+    .await?;
+
+    success!(
+        ctx,
+        msg,
+        "The custom command `{}` was rolled back to revision r{}",
+        name,
+        revision
+    );
+
+    Ok(())
+}
+
+/// A custom command in an export bundle
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedCommand {
+    name: String,
+    #[serde(default)]
+    documentation: String,
+    body: Value,
+}
+
+/// All the custom commands of a guild, made by `!command export`
+#[derive(Debug, Serialize, Deserialize)]
+struct CommandBundle {
+    commands: Vec<ExportedCommand>,
+}
+
+#[command]
+#[aliases("export")]
+#[checks(is_admin)]
+#[only_in(guilds)]
+async fn custom_command_export(ctx: &Context, msg: &Message) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+
+    let list = get_custom_commands_list(ctx, server_id)
+        .await
+        .unwrap_or_default();
+    if list.is_empty() {
+        failure!(ctx, msg, "There are no custom commands on this server!");
+        return Ok(());
+    }
+
+    let mut commands = Vec::with_capacity(list.len());
+    for (name, documentation) in list {
+        if let Some(command) = get_command_data(ctx, server_id, &name, false).await {
+            commands.push(ExportedCommand {
+                name,
+                documentation,
+                body: serde_json::from_str(&command.body)?,
+            });
+        }
+    }
+
+    let count = commands.len();
+    let bundle = serde_json::to_vec_pretty(&CommandBundle { commands })?;
+    println!("Exporting {count} custom commands from {server_id:?}");
+    msg.channel_id
+        .send_message(ctx, |m| {
+            m.content(format!(
+                "Exported {count} custom commands. Use `command import` with this file \
+to add them to another server."
+            ));
+            m.add_file((bundle.as_slice(), "custom_commands.json"))
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("import")]
+#[checks(is_admin)]
+#[only_in(guilds)]
+async fn custom_command_import(ctx: &Context, msg: &Message) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+
+    let Some(attachment) = msg.attachments.first() else {
+        failure!(
+            ctx,
+            msg,
+            "Attach a bundle of custom commands made with `command export`!"
+        );
+        return Ok(());
+    };
+    if attachment.size > MAX_COMMAND_BUNDLE_SIZE {
+        failure!(
+            ctx,
+            msg,
+            "Attachment is too big! Filesize must be under {}. Attached file size: {}",
+            bytesize::ByteSize(MAX_COMMAND_BUNDLE_SIZE),
+            bytesize::ByteSize(attachment.size)
+        );
+        return Ok(());
+    }
+    let bundle: CommandBundle = match serde_json::from_slice(&attachment.download().await?) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            failure!(ctx, msg, "Invalid bundle of custom commands: {}", e);
+            return Ok(());
+        }
+    };
+
+    // check every command before saving any of them
+    let mut prepared = Vec::with_capacity(bundle.commands.len());
+    for ExportedCommand {
+        name,
+        documentation,
+        mut body,
+    } in bundle.commands
+    {
+        let name = name.to_lowercase();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            failure!(ctx, msg, "Invalid custom command name: `{:?}`", name);
+            return Ok(());
+        }
+        if let Some(map) = body.as_object_mut() {
+            map.insert("documentation".into(), Value::String(documentation));
+        }
+        match prepare_custom_command(&name, body) {
+            Ok(command) => prepared.push((name, command)),
+            Err(e) => {
+                failure!(ctx, msg, "Could not import `{}`: {}", name, e);
+                return Ok(());
+            }
+        }
+    }
+
+    let (mut added, mut updated, mut unchanged) = (0, 0, 0);
+    for (name, command) in &prepared {
+        match get_command_data(ctx, server_id, name, true).await {
+            Some(existing)
+                if existing.body == command.body
+                    && existing.description == command.documentation =>
+            {
+                unchanged += 1;
+                continue;
+            }
+            Some(_) => updated += 1,
+            None => added += 1,
+        }
+        add_custom_command(
+            ctx,
+            server_id,
+            name,
+            &command.body,
+            command.documentation.as_deref(),
+            msg.author.id,
+        )
+        .await?;
+    }
+
+    println!(
+        "Imported {} custom commands in {server_id:?}",
+        prepared.len()
+    );
+    success!(
+        ctx,
+        msg,
+        "Imported {} custom commands: {} added, {} updated, {} unchanged",
+        prepared.len(),
+        added,
+        updated,
+        unchanged
+    );
+
+    Ok(())
+}
+
+/// Number of days over which the custom command statistics are computed
+const USAGE_PERIOD_DAYS: i64 = 30;
+/// Number of most used commands listed by `!command stats`
+const TOP_COMMANDS: usize = 10;
+/// Number of weeks listed by `!command stats <name>`
+const USAGE_WEEKS: i64 = 8;
+
+fn format_trend(recent: u32, previous: u32) -> String {
+    if previous == 0 {
+        if recent == 0 {
+            "+0%".into()
+        } else {
+            "new".into()
+        }
+    } else {
+        let change = (recent as f64 - previous as f64) / previous as f64 * 100.0;
+        format!("{change:+.0}%")
+    }
+}
+
+/// Joins names in backticks, cut to fit in an embed field
+fn format_name_list<'a>(names: impl ExactSizeIterator<Item = &'a str>) -> String {
+    let count = names.len();
+    let mut res = String::new();
+    for (i, name) in names.enumerate() {
+        let item = format!("`{name}`");
+        if res.len() + item.len() + 2 > 990 {
+            res.push_str(&format!("_…and {} more_", count - i));
+            break;
+        }
+        if !res.is_empty() {
+            res.push_str(", ");
+        }
+        res.push_str(&item);
+    }
+    if res.is_empty() {
+        res.push_str("_None_");
+    }
+    res
+}
+
+#[command]
+#[aliases("stats", "statistics", "usage")]
+#[checks(is_admin)]
+#[only_in(guilds)]
+async fn custom_command_stats(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let server_id = msg.guild_id.ok_or(NotInGuild)?;
+    let name = args.current().map(str::to_lowercase);
+
+    // include the uses that have not been written to the database yet
+    flush_usage(&ctx.data).await;
+
+    let today = Utc::now().date_naive();
+    let since = today - Duration::days(2 * USAGE_PERIOD_DAYS - 1);
+    let Some(rows) = get_usage(ctx, server_id, since, name.as_deref()).await else {
+        failure!(ctx, msg, "Could not get the custom command statistics!");
+        return Ok(());
+    };
+    let summaries = summarize_usage(&rows, today, USAGE_PERIOD_DAYS);
+
+    if let Some(name) = name {
+        if !check_command_exists(ctx, server_id, &name)
+            .await
+            .unwrap_or_default()
+        {
+            failure!(ctx, msg, "The custom command does not exist!");
+            return Ok(());
+        }
+        let (recent, previous) = summaries
+            .first()
+            .map_or((0, 0), |summary| (summary.recent, summary.previous));
+        let last_use = get_last_use(ctx, server_id, &name).await;
+
+        let weeks = weekly_usage(&rows, today, USAGE_WEEKS)
+            .into_iter()
+            .map(|(start, uses)| format!("`{}`  **{uses}**", start.format("%b %e")))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        msg.channel_id
+            .send_message(ctx, |m| {
+                m.embed(|e| {
+                    e.title(format!("Usage of the custom command: {name}"));
+                    e.field(
+                        format!("Last {USAGE_PERIOD_DAYS} days"),
+                        format!(
+                            "**{recent}** uses ({} from the previous {USAGE_PERIOD_DAYS} days)",
+                            format_trend(recent, previous)
+                        ),
+                        true,
+                    );
+                    e.field(
+                        "Last used",
+                        last_use.map_or_else(
+                            || "Never".to_string(),
+                            |day| day.format("%e %B %Y").to_string(),
+                        ),
+                        true,
+                    );
+                    e.field("Uses per week", weeks, false);
+                    e
+                })
+            })
+            .await?;
+    } else {
+        let commands = get_custom_commands_list(ctx, server_id)
+            .await
+            .unwrap_or_default();
+        let exists = |name: &str| commands.iter().any(|(command, _)| command == name);
+
+        let used = summaries
+            .iter()
+            .filter(|summary| summary.recent > 0 && exists(&summary.name))
+            .collect::<Vec<_>>();
+        let top = used
+            .iter()
+            .take(TOP_COMMANDS)
+            .map(|summary| {
+                format!(
+                    "`{}`  **{}** uses ({})",
+                    summary.name,
+                    summary.recent,
+                    format_trend(summary.recent, summary.previous)
+                )
+            })
+            .collect::<Vec<_>>();
+        let unused = unused_commands(commands.iter().map(|(name, _)| name.as_str()), &summaries);
+        let declining = summaries
+            .iter()
+            .filter(|summary| summary.recent < summary.previous && exists(&summary.name))
+            .map(|summary| summary.name.as_str())
+            .collect::<Vec<_>>();
+        let (recent, previous) = summaries.iter().fold((0, 0), |(recent, previous), s| {
+            (recent + s.recent, previous + s.previous)
+        });
+
+        msg.channel_id
+            .send_message(ctx, |m| {
+                m.embed(|e| {
+                    e.title("Custom command usage");
+                    e.description(format!(
+                        "**{recent}** uses of **{}** custom commands in the last \
+{USAGE_PERIOD_DAYS} days ({} from the previous {USAGE_PERIOD_DAYS} days)",
+                        commands.len(),
+                        format_trend(recent, previous)
+                    ));
+                    e.field(
+                        "Most used",
+                        if top.is_empty() {
+                            "_None_".into()
+                        } else {
+                            top.join("\n")
+                        },
+                        false,
+                    );
+                    e.field(
+                        "Used less than before",
+                        format_name_list(declining.into_iter()),
+                        false,
+                    );
+                    e.field(
+                        format!("Unused in the last {USAGE_PERIOD_DAYS} days"),
+                        format_name_list(unused.into_iter()),
+                        false,
+                    );
+                    e
+                })
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const CONTEXT: BlockContext = BlockContext {
+        argc: 2,
+        channel_id: ChannelId(10),
+        roles: &[RoleId(20), RoleId(21)],
+    };
+
+    #[test]
+    fn test_select() {
+        let body = json!({
+            "if": [
+                {"args": 1, "then": {"content": "one"}},
+                {"min_args": 2, "channel": ["<#11>", 12], "then": {"content": "elsewhere"}},
+                {"max_args": 2, "role": "<@&21>", "then": {"content": "role"}},
+            ],
+            "else": {"content": "else"},
+        });
+        assert_eq!(CONTEXT.select(&body), Some(&json!({"content": "role"})));
+
+        let body = json!({
+            "if": [{"args": 3, "then": {"content": "three"}}],
+            "else": {"content": "else"},
+        });
+        assert_eq!(CONTEXT.select(&body), Some(&json!({"content": "else"})));
+
+        // without "else", a random choice is picked, and never one with no weight
+        let body = json!({
+            "if": [{"args": 3, "then": {"content": "three"}}],
+            "random": [{"content": "never", "weight": 0}, {"content": "always"}],
+        });
+        for _ in 0..20 {
+            assert_eq!(CONTEXT.select(&body).unwrap()["content"], "always");
+        }
+
+        assert_eq!(CONTEXT.select(&json!({"content": "plain"})), None);
+    }
+
+    #[test]
+    fn test_validate_blocks() {
+        let valid = json!({
+            "if": [{"min_args": 1, "role": [1, "<@&2>"], "then": {
+                "random": [{"content": "a", "weight": 2.5}, {"content": "b"}],
+            }}],
+            "else": {"content": "none"},
+        });
+        assert_eq!(validate_blocks(&valid, 0), Ok(()));
+        assert_eq!(validate_blocks(&json!({"type": "admin"}), 0), Ok(()));
+
+        for invalid in [
+            json!({"if": {"then": {}}}),
+            json!({"if": [{"args": 1}]}),
+            json!({"if": [{"args": -1, "then": {}}]}),
+            json!({"if": [{"channel": "general", "then": {}}]}),
+            json!({"if": [], "else": "text"}),
+            json!({"random": []}),
+            json!({"random": ["text"]}),
+            json!({"random": [{"weight": -1}]}),
+            json!({"random": [{"weight": 0}, {"weight": 0}]}),
+            json!({"if": [{"then": {"type": "alias", "command": "ban"}}]}),
+            json!({"random": [{"type": "meme"}]}),
+        ] {
+            assert!(validate_blocks(&invalid, 0).is_err(), "{invalid}");
+        }
+
+        let mut nested = json!({"content": "deep"});
+        for _ in 0..MAX_BLOCK_DEPTH {
+            nested = json!({"random": [nested]});
+        }
+        assert!(validate_blocks(&nested, 0).is_err());
+    }
+}
@@ -250,6 +250,13 @@ async fn custom_commands(ctx: &Context, msg: &Message) -> CommandResult {
 		// if $0, $1 are left in the json because there are not enough arguments
 		// to fill them, these values will be used.
 	"self_delete": true // or false: wether the command message is deleted after execution.
+	"if": [{"min_args": 1, "then": {"content": "some content", ...}}, ...],
+	"else": {...}
+		// the first branch whose conditions all hold replaces the message, else
+		// the "else" message does. Conditions: "args", "min_args", "max_args",
+		// "channel" and "role" (ids, or arrays of ids).
+	"random": [{"weight": 2, "content": "..."}, {...}]
+		// picks one of the messages, optionally weighted (1 by default).
 	"subcommands" : {
 		"subcommand_name": {"content": "some content", ...},
 		"other_subcommand_name": {...}, // define subcommands. 
@@ -262,6 +269,10 @@ async fn custom_commands(ctx: &Context, msg: &Message) -> CommandResult {
 	}
 }
 ```
+**Placeholders**, replaced in every text of the command:
+`$me`, `$ping`: the name and a mention of the user; `$channel`: a mention of the channel;
+`$args`: all the arguments, `$as_url` url-encoded; `$0`, `$1`...: a single argument.
+`${1:default}` uses `default` if the argument is missing. `\$` is a literal `$`.
 "#,
             )
         })
@@ -293,12 +304,14 @@ pub async fn display_bugtracker_help(ctx: &Context, msg: &Message) -> CommandRes
                     "**Creating a bug report**",
                     format!(
 "`{prefix}track [status] <bug title>`  Creates a new bug report with the optional specified \
-`status`: one of `low`, `medium`, `high`, `critical`, and `forge` or `vanilla`. \
+`status` (`medium` by default). \
 The command returns a unique bug id.
  \t**Must be used with an inline reply to a message that will constitute the \
  initial bug report content.**
 \tYou can optionnally use  `{prefix}track legacy [status] <bug title>`  \
 to create a legacy bug report.
+\tUse  `{prefix}track thread [legacy] [status] <bug title>`  to also open a discussion thread \
+off the message, where status changes will be posted.
 `{prefix}bug link <bug id> [link url] [link title]`  Adds additional information to the bug \
 report referenced by its `bug id`. Can also be used with an inline reply to a message, \
 in which case you don't need to specify a url.
@@ -308,24 +321,72 @@ in which case you don't need to specify a url.
                     ),
                     false,
                 );
+                e.field(
+                    "**Proposing a bug report**",
+                    format!(
+"`{prefix}bug submit [legacy] <bug title>`  Proposes a bug report, described by the message you \
+reply to or by the command itself. Admins accept or reject it with the buttons of the proposal, \
+and you are subscribed to the bug once it is accepted.
+`{prefix}bug queue`  Lists the proposed bug reports waiting for review.
+"
+                    ),
+                    false,
+                );
                 e.field(
                     "**Displaying and editing bug reports**",
                     format!(
-"`{prefix}bugs [latest|oldest|highest|lowest] [status] [page] [limit n]`  Displays a list of \
-bugs. By default, it will display all bugs that are not `resolved`, `forge` or `closed`, in \
+"`{prefix}bugs [latest|oldest|highest|lowest|votes] [status] [page] [limit n]`  Displays a list of \
+bugs. By default, it will display all bugs with an open status, in \
 chronological order starting from the latest one, and with a default limit of 10 bugs.
  \tThe `limit` keyword is necessary to specify a custom limit. `highest` and `lowest` will \
- sort the bugs by priority.
- \tAvailable statuses are `low`, `medium`, `high`, `critical`, `resolved`, `forge` \
- (or `vanilla`) and `closed`.
+ sort the bugs by priority, `votes` by number of players affected.
+ \tUse  `{prefix}bug workflow`  to list the available statuses.
  \tYou can optionnally use  `{prefix}bugs [legacy|renewed] [latest|oldest] [status] [limit]`  \
  to display legacy only or renewed only bugs.
 `{prefix}bug <bug id>`  Displays a single bug.
+`{prefix}bug search [legacy|renewed] [status] <terms>`  Searches bug reports for the given terms, \
+in their titles, reported messages and link titles. Useful to find duplicates before tracking a bug.
 `{prefix}bug rename <bug id> <new title>`  Change a bug's title.
 `{prefix}bug status <bug id> <new status>`  Change a bug's status.
 `{prefix}bug toggle <bug id>`  Switch a bug's edition between renewed and legacy.
-
-`{prefix}bug statistics` Show bugtracker statistics.
+"
+                    ),
+                    false,
+                );
+                e.field(
+                    "**Triaging bug reports**",
+                    format!(
+"`{prefix}bug duplicate <bug id> of <original bug id>`  Close a bug as a duplicate of another. \
+Its subscribers, votes and links are moved to the original bug.
+`{prefix}bug assign <bug id> <user mentions>`  Assigns users to a bug and subscribes them to \
+its notifications. Use  `{prefix}bug assign remove <bug id> <user mentions>`  to unassign them.
+`{prefix}bugs mine`  or  `{prefix}bugs assigned <user mention>`  Displays the bugs assigned to \
+you or to a user. Accepts the same arguments as  `{prefix}bugs`.
+`{prefix}bug history <bug id> [page]`  Displays who changed a bug's status, title, edition \
+or links, and when.
+`{prefix}bug digest <channel mention> [weekday] [hour]`  Posts a weekly digest of new, \
+resolved and re-opened bugs in that channel (hour in UTC). Use  `{prefix}bug digest disable`  \
+to stop it.
+`{prefix}bug statistics [weeks] [chart]`  Show statistics: weekly activity (8 weeks by \
+default, optional chart), resolution times and oldest open bugs.
+"
+                    ),
+                    false,
+                );
+                e.field(
+                    "**Labels and details**",
+                    format!(
+"`{prefix}bug label add <bug id> <labels...>`  Adds free-form labels to a bug, such as \
+`crash` or `worldgen`. Labels can only contain letters, digits, `-`, `_` and `.`.
+`{prefix}bug label remove <bug id> <labels...>`  Removes labels from a bug.
+`{prefix}bug edit <bug id> <mod|forge|minecraft|steps|expected|actual> [value]`  Sets the mod \
+version, Forge version, Minecraft edition, reproduction steps, expected or actual behaviour of \
+a bug. Leave the value empty to clear the field.
+`{prefix}bugs [status] [order] label <label> [page]`  Only displays bugs with that label. \
+The `label` keyword can be repeated to filter by several labels.
+`{prefix}bugs export [status] [legacy|renewed] [json|csv]`  Exports every matching bug report, \
+resolved and closed ones included unless a status is given, with its links, labels and subscriber \
+count, as a file attachment.
 "
                     ),
                     false,
@@ -337,10 +398,41 @@ chronological order starting from the latest one, and with a default limit of 10
 Equivalent to  `{prefix}bug status <bug id> resolved`.
 `{prefix}bug close <bug id>`  Marks a bug as closed. \
 Equivalent to  `{prefix}bug status <bug id> closed`.
+`{prefix}bugs stale [days] [remind]`  Displays open bugs without status change, new link or \
+new subscriber for 90 days (or the given number of days). `remind` DMs their assignees instead.
+`{prefix}bug status <new status> <bug ids...>`  Changes the status of several bugs at once. \
+Accepts ranges such as `LOTR-40..LOTR-45`. Subscribers receive a single notification.
 ",
                     ),
                     false,
                 );
+                e.field(
+                    "**Notifications**",
+                    format!(
+"`{prefix}bug subscribe <bug id>`  and  `{prefix}bug unsubscribe <bug id>`  Toggle the DM \
+notifications of a bug. `{prefix}bug notifications`  lists your subscriptions.
+`{prefix}bug notifications settings [immediate|daily|muted]`  Shows or changes how you receive \
+notifications: right away, batched in a single daily DM, or not at all.
+Press the  `Affects me`  button of a bug if you hit it too, to help prioritise it.
+"
+                    ),
+                    false,
+                );
+                e.field(
+                    "**Server setup**",
+                    format!(
+"`{prefix}bug tracker`  Shows whether the bugtracker is enabled in this server. \
+`{prefix}bug tracker enable [prefix]`  and  `{prefix}bug tracker disable`  toggle it, and \
+`{prefix}bug tracker prefix <prefix>`  changes the prefix of the bug ids (`LOTR` by default).
+`{prefix}bug workflow set <status> <#colour> <marker> <reaction> <open|closed> [position]`  \
+Adds or edits a status of this server. Positions go from lowest to highest priority. \
+Use  `{prefix}bug workflow remove <status>`  to remove an unused status.
+`{prefix}bug issues <api url>`  Syncs mirrored bugs with an issue tracker \
+allowed by the bot owner (`{prefix}bug issues disable`  to stop). `{prefix}bug mirror <bug id>`  creates the issue of a bug.
+"
+                    ),
+                    false,
+                );
                 e
             })
         })
@@ -415,9 +507,15 @@ message to the mentioned channel.  For the JSON argument documentation, type `{p
 
 `{prefix}define <command name> <json command content>`  Define or update a custom command. \
 For the JSON argument documentation, type  `{prefix}help custom`
-`{prefix}command display [command name]`  Provide an argument to get info on a specific command, \
-or leave empty to get a list of commands
+`{prefix}command display [command name]`  Show a custom command, or list them all
 `{prefix}command remove <command name>`  Remove a custom command
+`{prefix}command history <name>`  List the revisions of a command
+`{prefix}command diff <name> <rev> [other rev]`  Show the changes made by a revision, \
+or between two revisions
+`{prefix}command rollback <name> <rev>`  Restore a revision, even of a removed command
+`{prefix}command export`  Save all custom commands in a JSON file
+`{prefix}command import`  Add or update the commands of an attached export
+`{prefix}command stats [name]`  Show the most used, declining and unused commands
 
 *Only bot admins can use these commands*
 *For bugtracker help, use  `{prefix}help bugtracker`*"
@@ -12,6 +12,20 @@ pub const LOTR_DISCORD: GuildId = GuildId(405091134327619587);
 /// Maximum size, in bytes, of a JSON file for [announcements][crate::announcement]
 /// and [custom commands][crate::commands::custom_commands]
 pub const MAX_JSON_FILE_SIZE: u64 = 10240;
+/// Maximum size, in bytes, of an imported bundle of [custom commands][crate::commands::custom_commands]
+pub const MAX_COMMAND_BUNDLE_SIZE: u64 = 1024 * 1024;
+
+/// Maximum size, in bytes, of an attachment archived with a
+/// [bug report][crate::database::bug_reports] snapshot
+pub const MAX_ARCHIVED_ATTACHMENT_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Bug id prefix of the [LOTR Mod Community Discord](LOTR_DISCORD) bugtracker,
+/// which is enabled even without [bugtracker settings][crate::database::bug_trackers]
+pub const DEFAULT_BUG_PREFIX: &str = "LOTR";
+
+/// Number of consecutive failed bug notification DMs after which a user
+/// is unsubscribed from all their bugs
+pub const MAX_DELIVERY_FAILURES: u32 = 3;
 
 /// Bit filter for colours
 pub const BIT_FILTER_24BITS: u32 = (1 << 24) - 1;
@@ -113,12 +127,44 @@ pub const TABLE_CHANNEL_BLACKLIST: &str = "channel_blacklist";
 pub const TABLE_MC_SERVER_IP: &str = "mc_server_ip";
 /// SQL table name for [custom commands][crate::database::custom_commands]
 pub const TABLE_CUSTOM_COMMANDS: &str = "custom_commands";
+/// SQL table name for [custom command revisions][crate::database::custom_commands]
+pub const TABLE_CUSTOM_COMMANDS_HISTORY: &str = "custom_commands__history";
+/// SQL table name for [custom command usage][crate::database::command_usage]
+pub const TABLE_CUSTOM_COMMANDS_USAGE: &str = "custom_commands__usage";
 /// SQL table name for [bug reports][crate::database::bug_reports]
 pub const TABLE_BUG_REPORTS: &str = "bug_reports";
 /// SQL table name for [bug report links][crate::database::bug_reports]
 pub const TABLE_BUG_REPORTS_LINKS: &str = "bug_reports__links";
 /// SQL table name for [bug report notifications][crate::database::bug_reports]
 pub const TABLE_BUG_REPORTS_NOTIFICATIONS: &str = "bug_reports__notifications";
+/// SQL table name for [bug notification settings][crate::database::bug_reports]
+pub const TABLE_BUG_REPORTS_NOTIFICATION_SETTINGS: &str = "bug_reports__notification_settings";
+/// SQL table name for [pending bug notifications][crate::database::bug_reports]
+pub const TABLE_BUG_REPORTS_PENDING_NOTIFICATIONS: &str = "bug_reports__pending_notifications";
+/// SQL table name for [bug notification delivery failures][crate::database::bug_reports]
+pub const TABLE_BUG_REPORTS_DELIVERY_FAILURES: &str = "bug_reports__delivery_failures";
+/// SQL table name for [bug report history][crate::database::bug_reports]
+pub const TABLE_BUG_REPORTS_HISTORY: &str = "bug_reports__history";
+/// SQL table name for [bug report assignees][crate::database::bug_reports]
+pub const TABLE_BUG_REPORTS_ASSIGNEES: &str = "bug_reports__assignees";
+/// SQL table name for [bug report votes][crate::database::bug_reports]
+pub const TABLE_BUG_REPORTS_VOTES: &str = "bug_reports__votes";
+/// SQL table name for [bug report labels][crate::database::bug_reports]
+pub const TABLE_BUG_REPORTS_LABELS: &str = "bug_reports__labels";
+/// SQL table name for [bug report attachments][crate::database::bug_reports]
+pub const TABLE_BUG_REPORTS_ATTACHMENTS: &str = "bug_reports__attachments";
+/// SQL table name for [bugtracker status workflows][crate::database::bug_statuses]
+pub const TABLE_BUG_REPORTS_STATUSES: &str = "bug_reports__statuses";
+/// SQL table name for [proposed bug reports][crate::database::bug_submissions]
+pub const TABLE_BUG_REPORTS_SUBMISSIONS: &str = "bug_reports__submissions";
+/// SQL table name for [bugtracker settings][crate::database::bug_trackers]
+pub const TABLE_BUG_TRACKERS: &str = "bug_trackers";
+/// SQL table name for [issue tracker settings][crate::database::issue_sync]
+pub const TABLE_BUG_ISSUE_SYNC: &str = "bug_issue_sync";
+/// SQL table name for [bugs mirrored to issues][crate::database::issue_sync]
+pub const TABLE_BUG_REPORTS_ISSUES: &str = "bug_reports__issues";
+/// SQL table name for [bug digest settings][crate::database::bug_digest]
+pub const TABLE_BUG_DIGESTS: &str = "bug_digests";
 /// SQL table name for [role handling][crate::database::roles]
 pub const TABLE_ROLES: &str = "roles";
 /// SQL table name for [role aliases handling][crate::database::roles]
@@ -0,0 +1,272 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, TimeZone, Utc, Weekday};
+use const_format::formatcp;
+use mysql_async::prelude::*;
+use mysql_async::Conn;
+use serenity::client::Context;
+use serenity::framework::standard::CommandResult;
+use serenity::model::prelude::*;
+
+use crate::constants::{
+    TABLE_BUG_DIGESTS, TABLE_BUG_REPORTS, TABLE_BUG_REPORTS_HISTORY, TABLE_BUG_TRACKERS,
+};
+use crate::database::bug_reports::{count_bugs, BugCounts, PartialBugReport};
+use crate::database::bug_statuses::{load_status_workflow, StatusWorkflow, RESOLVED};
+use crate::database::bug_trackers::PREFIX_SQL;
+use crate::get_database_conn;
+
+#[derive(Debug, Clone)]
+pub struct DigestConfig {
+    pub server_id: GuildId,
+    pub channel_id: ChannelId,
+    pub weekday: Weekday,
+    /// Hour of the day, in UTC
+    pub hour: u32,
+    pub last_digest: Option<DateTime<Utc>>,
+}
+
+impl DigestConfig {
+    /// The latest scheduled digest time before `now`
+    pub fn last_scheduled(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let days_since =
+            (now.weekday().num_days_from_monday() + 7 - self.weekday.num_days_from_monday()) % 7;
+        let date = now.date_naive() - Duration::days(days_since as i64);
+        let scheduled = Utc.from_utc_datetime(
+            &date
+                .and_hms_opt(self.hour, 0, 0)
+                .expect("Expected a valid digest hour"),
+        );
+        if scheduled > now {
+            scheduled - Duration::days(7)
+        } else {
+            scheduled
+        }
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.last_digest
+            .map_or(true, |last| last < self.last_scheduled(now))
+    }
+}
+
+type DigestRow = (u64, u64, u8, u32, Option<NaiveDateTime>);
+
+fn config_from_row((server_id, channel_id, weekday, hour, last_digest): DigestRow) -> DigestConfig {
+    DigestConfig {
+        server_id: GuildId(server_id),
+        channel_id: ChannelId(channel_id),
+        weekday: (0..weekday % 7).fold(Weekday::Mon, |day, _| day.succ()),
+        hour: hour.min(23),
+        last_digest: last_digest.map(|t| Utc.from_utc_datetime(&t)),
+    }
+}
+
+pub async fn get_digest_config(ctx: &Context, server_id: GuildId) -> Option<DigestConfig> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_first(
+        formatcp!(
+            "SELECT server_id, channel_id, weekday, hour, last_digest FROM {} \
+WHERE server_id = :server_id",
+            TABLE_BUG_DIGESTS
+        ),
+        params! {
+            "server_id" => server_id.0
+        },
+    )
+    .await
+    .ok()?
+    .map(config_from_row)
+}
+
+/// Sets the digest channel and schedule of a guild.
+///
+/// The first digest will cover the bugs changed since this call.
+pub async fn set_digest_config(
+    ctx: &Context,
+    server_id: GuildId,
+    channel_id: ChannelId,
+    weekday: Weekday,
+    hour: u32,
+) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "REPLACE INTO {} (server_id, channel_id, weekday, hour, last_digest) \
+VALUES (:server_id, :channel_id, :weekday, :hour, UTC_TIMESTAMP())",
+            TABLE_BUG_DIGESTS
+        ),
+        params! {
+            "server_id" => server_id.0,
+            "channel_id" => channel_id.0,
+            "weekday" => weekday.num_days_from_monday(),
+            "hour" => hour,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_digest_config(ctx: &Context, server_id: GuildId) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE server_id = :server_id LIMIT 1",
+            TABLE_BUG_DIGESTS
+        ),
+        params! {
+            "server_id" => server_id.0
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_digest_configs(conn: &mut Conn) -> Result<Vec<DigestConfig>, mysql_async::Error> {
+    conn.query_map(
+        formatcp!(
+            "SELECT server_id, channel_id, weekday, hour, last_digest FROM {}",
+            TABLE_BUG_DIGESTS
+        ),
+        config_from_row,
+    )
+    .await
+}
+
+pub async fn set_last_digest(
+    conn: &mut Conn,
+    server_id: GuildId,
+    time: DateTime<Utc>,
+) -> Result<(), mysql_async::Error> {
+    conn.exec_drop(
+        formatcp!(
+            "UPDATE {} SET last_digest = :last_digest WHERE server_id = :server_id",
+            TABLE_BUG_DIGESTS
+        ),
+        params! {
+            "last_digest" => time.naive_utc(),
+            "server_id" => server_id.0
+        },
+    )
+    .await
+}
+
+/// Bugtracker activity over a period of time
+#[derive(Debug, Clone)]
+pub struct BugDigest {
+    pub since: DateTime<Utc>,
+    pub counts: BugCounts,
+    pub new: Vec<PartialBugReport>,
+    pub resolved: Vec<PartialBugReport>,
+    pub reopened: Vec<PartialBugReport>,
+}
+
+async fn get_bugs_with_status_change(
+    conn: &mut Conn,
+    server_id: GuildId,
+    workflow: &StatusWorkflow,
+    since: DateTime<Utc>,
+    old_statuses: &str,
+    new_statuses: &str,
+) -> Result<Vec<PartialBugReport>, mysql_async::Error> {
+    conn.exec_map(
+        format!(
+            "SELECT bug_id, {PREFIX_SQL}, title, status, timestamp, category FROM {TABLE_BUG_REPORTS} \
+LEFT JOIN {TABLE_BUG_TRACKERS} ON guild_id = server_id \
+WHERE guild_id = :guild_id AND bug_id IN (SELECT bug_id FROM {TABLE_BUG_REPORTS_HISTORY} \
+WHERE change_type = 'status' AND timestamp > :since \
+AND old_value IN ({old_statuses}) AND new_value IN ({new_statuses})) \
+ORDER BY bug_id ASC"
+        ),
+        params! {
+            "guild_id" => server_id.0,
+            "since" => since.naive_utc()
+        },
+        |row| partial_from_row(workflow, row),
+    )
+    .await
+    .map(|v| v.into_iter().flatten().collect())
+}
+
+type PartialRow = (u64, String, String, String, NaiveDateTime, String);
+
+fn partial_from_row(
+    workflow: &StatusWorkflow,
+    (bug_id, prefix, title, status, timestamp, category): PartialRow,
+) -> Option<PartialBugReport> {
+    PartialBugReport::new(
+        bug_id,
+        prefix,
+        title,
+        workflow.resolve(&status),
+        timestamp,
+        category
+            .parse()
+            .expect("Expected a valid category from the database"),
+    )
+}
+
+/// Gets the bugtracker activity of a guild since the given time
+pub async fn get_bug_digest(
+    conn: &mut Conn,
+    server_id: GuildId,
+    since: DateTime<Utc>,
+) -> Result<Option<BugDigest>, mysql_async::Error> {
+    let Some(counts) = count_bugs(conn, server_id).await else {
+        return Ok(None);
+    };
+    let workflow = load_status_workflow(conn, server_id).await?;
+
+    let new = conn
+        .exec_map(
+            formatcp!(
+                "SELECT bug_id, {}, title, status, timestamp, category FROM {} \
+LEFT JOIN {} ON guild_id = server_id \
+WHERE guild_id = :guild_id AND timestamp > :since ORDER BY bug_id ASC",
+                PREFIX_SQL,
+                TABLE_BUG_REPORTS,
+                TABLE_BUG_TRACKERS
+            ),
+            params! {
+                "guild_id" => server_id.0,
+                "since" => since.naive_utc()
+            },
+            |row| partial_from_row(&workflow, row),
+        )
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let open_statuses = workflow.sql_names(true);
+    let closed_statuses = workflow.sql_names(false);
+    let resolved = get_bugs_with_status_change(
+        conn,
+        server_id,
+        &workflow,
+        since,
+        &open_statuses,
+        &format!("'{RESOLVED}'"),
+    )
+    .await?;
+    let reopened = get_bugs_with_status_change(
+        conn,
+        server_id,
+        &workflow,
+        since,
+        &closed_statuses,
+        &open_statuses,
+    )
+    .await?;
+
+    Ok(Some(BugDigest {
+        since,
+        counts,
+        new,
+        resolved,
+        reopened,
+    }))
+}
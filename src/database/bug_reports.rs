@@ -1,118 +1,37 @@
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use const_format::formatcp;
 use mysql_async::prelude::*;
+use mysql_async::{Conn, Row, TxOpts, Value};
+use serde::{Deserialize, Serialize};
 use serenity::client::Context;
 use serenity::framework::standard::{CommandError, CommandResult};
+use serenity::http::error::ErrorResponse;
+use serenity::http::Http;
 use serenity::model::prelude::*;
-use serenity::utils::Colour;
+use serenity::prelude::{HttpError, SerenityError};
+use std::collections::HashMap;
 
 use crate::constants::{
-    TABLE_BUG_REPORTS, TABLE_BUG_REPORTS_LINKS, TABLE_BUG_REPORTS_NOTIFICATIONS,
+    MAX_ARCHIVED_ATTACHMENT_SIZE, MAX_DELIVERY_FAILURES, TABLE_BUG_REPORTS,
+    TABLE_BUG_REPORTS_ASSIGNEES, TABLE_BUG_REPORTS_ATTACHMENTS,
+    TABLE_BUG_REPORTS_DELIVERY_FAILURES, TABLE_BUG_REPORTS_HISTORY, TABLE_BUG_REPORTS_LABELS,
+    TABLE_BUG_REPORTS_LINKS, TABLE_BUG_REPORTS_NOTIFICATIONS,
+    TABLE_BUG_REPORTS_NOTIFICATION_SETTINGS, TABLE_BUG_REPORTS_PENDING_NOTIFICATIONS,
+    TABLE_BUG_REPORTS_VOTES, TABLE_BUG_TRACKERS,
 };
+use crate::database::bug_statuses::{load_status_workflow, BugStatus, StatusWorkflow};
+use crate::database::bug_trackers::{get_bug_prefix, BugTracker, PREFIX_SQL};
 use crate::get_database_conn;
 
 #[derive(Debug, Clone, Copy)]
 pub enum BugOrder {
     Chronological(bool),
     Priority(bool),
+    /// Most "affects me" votes first
+    Votes,
     None,
 }
 
-use BugStatus::*;
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
-pub enum BugStatus {
-    Closed,
-    ForgeVanilla,
-    Resolved,
-    Low,
-    #[default]
-    Medium,
-    High,
-    Critical,
-}
-
-impl std::fmt::Display for BugStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            ForgeVanilla => write!(f, "Forge or Vanilla"),
-            _ => write!(f, "{self:?}"),
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct ParseStatusError;
-
-impl std::str::FromStr for BugStatus {
-    type Err = ParseStatusError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s.to_lowercase().as_str() {
-            "resolved" => Resolved,
-            "low" => Low,
-            "medium" => Medium,
-            "high" => High,
-            "critical" => Critical,
-            "closed" => Closed,
-            "forgevanilla" | "forge" | "vanilla" => ForgeVanilla,
-            _ => return Err(Self::Err {}),
-        })
-    }
-}
-
-impl BugStatus {
-    #[inline]
-    pub const fn as_str(self) -> &'static str {
-        match self {
-            Resolved => "resolved",
-            Low => "low",
-            Medium => "medium",
-            High => "high",
-            Critical => "critical",
-            Closed => "closed",
-            ForgeVanilla => "forgevanilla",
-        }
-    }
-
-    #[inline]
-    pub const fn colour(self) -> Colour {
-        match self {
-            Resolved => Colour(0x2fd524),
-            Low => Colour(0xfef001),
-            Medium => Colour(0xfd9a01),
-            High => Colour(0xfd6104),
-            Critical => Colour(0xff0000),
-            Closed => Colour(0x7694cb),
-            ForgeVanilla => Colour(0x9f00c5),
-        }
-    }
-
-    pub const fn marker(self) -> &'static str {
-        match self {
-            Resolved => ":green_circle:",
-            Low => ":yellow_circle:",
-            Medium => ":orange_circle:",
-            High => ":red_circle:",
-            Critical => ":bangbang:",
-            Closed => ":blue_circle:",
-            ForgeVanilla => ":regional_indicator_v:",
-        }
-    }
-
-    pub fn reaction(self) -> ReactionType {
-        ReactionType::Unicode(
-            match self {
-                Resolved => "✅",
-                Low | Medium | High | Critical => "⚠️",
-                Closed => "❌",
-                ForgeVanilla => "🇻", // not a V: the [V] emoji
-            }
-            .to_string(),
-        )
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct BugLink {
     pub id: u64,
@@ -166,9 +85,152 @@ impl BugCategory {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BugField {
+    ModVersion,
+    ForgeVersion,
+    MinecraftEdition,
+    Reproduction,
+    Expected,
+    Actual,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseFieldError;
+
+impl std::str::FromStr for BugField {
+    type Err = ParseFieldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use BugField::*;
+
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "mod" | "version" | "modversion" => ModVersion,
+            "forge" | "forgeversion" => ForgeVersion,
+            "minecraft" | "mc" => MinecraftEdition,
+            "steps" | "repro" | "reproduction" => Reproduction,
+            "expected" => Expected,
+            "actual" => Actual,
+            _ => return Err(ParseFieldError),
+        })
+    }
+}
+
+impl std::fmt::Display for BugField {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use BugField::*;
+
+        write!(
+            f,
+            "{}",
+            match self {
+                ModVersion => "Mod version",
+                ForgeVersion => "Forge version",
+                MinecraftEdition => "Minecraft edition",
+                Reproduction => "Steps to reproduce",
+                Expected => "Expected behaviour",
+                Actual => "Actual behaviour",
+            }
+        )
+    }
+}
+
+impl BugField {
+    pub const ALL: [Self; 6] = [
+        Self::ModVersion,
+        Self::ForgeVersion,
+        Self::MinecraftEdition,
+        Self::Reproduction,
+        Self::Expected,
+        Self::Actual,
+    ];
+
+    const fn column(self) -> &'static str {
+        use BugField::*;
+        match self {
+            ModVersion => "mod_version",
+            ForgeVersion => "forge_version",
+            MinecraftEdition => "minecraft_edition",
+            Reproduction => "reproduction",
+            Expected => "expected",
+            Actual => "actual",
+        }
+    }
+
+    /// Short fields are displayed inline in the bug embed
+    pub const fn is_short(self) -> bool {
+        matches!(
+            self,
+            Self::ModVersion | Self::ForgeVersion | Self::MinecraftEdition
+        )
+    }
+
+    pub const fn max_length(self) -> usize {
+        if self.is_short() {
+            64
+        } else {
+            1024
+        }
+    }
+}
+
+/// Optional structured information about a bug report
+#[derive(Debug, Clone, Default)]
+pub struct BugDetails {
+    pub mod_version: Option<String>,
+    pub forge_version: Option<String>,
+    pub minecraft_edition: Option<String>,
+    pub reproduction: Option<String>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl BugDetails {
+    pub fn get(&self, field: BugField) -> Option<&str> {
+        use BugField::*;
+        match field {
+            ModVersion => &self.mod_version,
+            ForgeVersion => &self.forge_version,
+            MinecraftEdition => &self.minecraft_edition,
+            Reproduction => &self.reproduction,
+            Expected => &self.expected,
+            Actual => &self.actual,
+        }
+        .as_deref()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotAttachment {
+    pub url: String,
+    pub filename: String,
+    pub content_type: Option<String>,
+}
+
+impl SnapshotAttachment {
+    pub fn is_image(&self) -> bool {
+        self.content_type
+            .as_deref()
+            .map_or(false, |t| t.starts_with("image/"))
+    }
+}
+
+/// Copy of the reported message, taken when the bug report is created
+#[derive(Debug, Clone)]
+pub struct MessageSnapshot {
+    pub content: String,
+    pub author_id: UserId,
+    pub author_name: String,
+    pub attachments: Vec<SnapshotAttachment>,
+}
+
 #[derive(Debug, Clone)]
 pub struct BugReport {
     pub bug_id: u64,
+    /// Guild whose bugtracker the bug was tracked in
+    pub guild_id: GuildId,
+    /// Id prefix of that bugtracker
+    pub prefix: String,
     pub channel_id: ChannelId,
     pub message_id: MessageId,
     pub title: String,
@@ -176,11 +238,30 @@ pub struct BugReport {
     pub timestamp: DateTime<Utc>,
     pub category: BugCategory,
     pub links: Vec<BugLink>,
+    pub duplicate_of: Option<u64>,
+    /// Discussion thread created off the bug report message
+    pub thread_id: Option<ChannelId>,
+    pub assignees: Vec<UserId>,
+    pub labels: Vec<String>,
+    /// Number of users who pressed "Affects me"
+    pub votes: u32,
+    pub details: BugDetails,
+    /// `None` for bugs tracked before snapshots existed, or imported from another tracker
+    pub snapshot: Option<MessageSnapshot>,
+}
+
+impl BugReport {
+    /// Link to the reported message. `None` for imported bugs without a message,
+    /// which are stored with a zero message id.
+    pub fn message_link(&self) -> Option<String> {
+        (self.message_id.0 != 0).then(|| self.message_id.link(self.channel_id, Some(self.guild_id)))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct PartialBugReport {
     pub bug_id: u64,
+    pub prefix: String,
     pub title: String,
     pub status: BugStatus,
     pub timestamp: DateTime<Utc>,
@@ -196,7 +277,8 @@ impl std::fmt::Display for PartialBugReport {
         };
         write!(
             f,
-            "LOTR-{} — {}  ({})",
+            "{}-{} — {}  ({})",
+            self.prefix,
             self.bug_id,
             self.title,
             self.timestamp.format(format_str)
@@ -207,6 +289,7 @@ impl std::fmt::Display for PartialBugReport {
 impl PartialBugReport {
     pub fn new(
         bug_id: u64,
+        prefix: String,
         title: String,
         status: BugStatus,
         timestamp: NaiveDateTime,
@@ -214,6 +297,7 @@ impl PartialBugReport {
     ) -> Option<Self> {
         Some(Self {
             bug_id,
+            prefix,
             title,
             status,
             timestamp: Utc.from_utc_datetime(&timestamp), // DateTime::from_utc(timestamp, Utc),
@@ -222,22 +306,26 @@ impl PartialBugReport {
     }
 }
 
+/// Takes a column of a row, failing if it is missing or cannot be converted
+fn take_column<T: FromValue>(row: &mut Row, column: &str) -> Result<T, CommandError> {
+    row.take_opt(column)
+        .ok_or_else(|| CommandError::from(format!("Missing column `{column}` in bug report")))?
+        .map_err(|e| CommandError::from(format!("Invalid column `{column}` in bug report: {e}")))
+}
+
 pub async fn get_bug_from_id(ctx: &Context, bug_id: u64) -> Result<BugReport, CommandError> {
     let mut conn = get_database_conn!(ctx);
 
-    let (channel_id, message_id, title, status, timestamp, category): (
-        u64,
-        u64,
-        String,
-        String,
-        NaiveDateTime,
-        String,
-    ) = conn
+    let mut row: Row = conn
         .exec_first(
             formatcp!(
-                "SELECT channel_id, message_id, title, status, timestamp, category \
-FROM {} WHERE bug_id = :bug_id",
-                TABLE_BUG_REPORTS
+                "SELECT guild_id, {} AS prefix, channel_id, message_id, title, status, timestamp, \
+category, duplicate_of, thread_id, mod_version, forge_version, minecraft_edition, reproduction, \
+expected, actual, snapshot_content, snapshot_author_id, snapshot_author_name \
+FROM {} LEFT JOIN {} ON guild_id = server_id WHERE bug_id = :bug_id",
+                PREFIX_SQL,
+                TABLE_BUG_REPORTS,
+                TABLE_BUG_TRACKERS
             ),
             params! {
                 "bug_id" => bug_id
@@ -246,6 +334,9 @@ FROM {} WHERE bug_id = :bug_id",
         .await?
         .ok_or_else(|| CommandError::from("Bug report does not exist!"))?;
 
+    let guild_id: u64 = take_column(&mut row, "guild_id")?;
+    let workflow = load_status_workflow(&mut conn, GuildId(guild_id)).await?;
+
     let links: Vec<BugLink> = conn
         .exec_map(
             formatcp!(
@@ -259,123 +350,386 @@ FROM {} WHERE bug_id = :bug_id",
         )
         .await?;
 
+    let assignees: Vec<UserId> = conn
+        .exec_map(
+            formatcp!(
+                "SELECT user_id FROM {} WHERE bug_id = :bug_id",
+                TABLE_BUG_REPORTS_ASSIGNEES
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+            UserId,
+        )
+        .await?;
+
+    let snapshot = match (
+        take_column::<Option<String>>(&mut row, "snapshot_content")?,
+        take_column::<Option<u64>>(&mut row, "snapshot_author_id")?,
+        take_column::<Option<String>>(&mut row, "snapshot_author_name")?,
+    ) {
+        (Some(content), Some(author_id), Some(author_name)) => {
+            let attachments = conn
+                .exec_map(
+                    formatcp!(
+                        "SELECT url, filename, content_type FROM {} \
+WHERE bug_id = :bug_id ORDER BY attachment_id ASC",
+                        TABLE_BUG_REPORTS_ATTACHMENTS
+                    ),
+                    params! {
+                        "bug_id" => bug_id
+                    },
+                    |(url, filename, content_type)| SnapshotAttachment {
+                        url,
+                        filename,
+                        content_type,
+                    },
+                )
+                .await?;
+            Some(MessageSnapshot {
+                content,
+                author_id: UserId(author_id),
+                author_name,
+                attachments,
+            })
+        }
+        _ => None,
+    };
+
+    let labels: Vec<String> = conn
+        .exec(
+            formatcp!(
+                "SELECT label FROM {} WHERE bug_id = :bug_id ORDER BY label ASC",
+                TABLE_BUG_REPORTS_LABELS
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+        )
+        .await?;
+
+    let votes = count_votes(&mut conn, bug_id).await?;
+
     Ok(BugReport {
         bug_id,
-        channel_id: ChannelId(channel_id),
-        message_id: MessageId(message_id),
-        title,
-        status: status
-            .parse()
-            .expect("Expected a valid bug status from the database"),
-        timestamp: Utc.from_utc_datetime(&timestamp),
-        category: category
+        guild_id: GuildId(guild_id),
+        prefix: take_column(&mut row, "prefix")?,
+        channel_id: ChannelId(take_column(&mut row, "channel_id")?),
+        message_id: MessageId(take_column(&mut row, "message_id")?),
+        title: take_column(&mut row, "title")?,
+        status: workflow.resolve(&take_column::<String>(&mut row, "status")?),
+        timestamp: Utc.from_utc_datetime(&take_column(&mut row, "timestamp")?),
+        category: take_column::<String>(&mut row, "category")?
             .parse()
             .expect("Expected a valid category from the database"),
         links,
+        duplicate_of: take_column(&mut row, "duplicate_of")?,
+        thread_id: take_column::<Option<u64>>(&mut row, "thread_id")?.map(ChannelId),
+        assignees,
+        labels,
+        votes,
+        details: BugDetails {
+            mod_version: take_column(&mut row, "mod_version")?,
+            forge_version: take_column(&mut row, "forge_version")?,
+            minecraft_edition: take_column(&mut row, "minecraft_edition")?,
+            reproduction: take_column(&mut row, "reproduction")?,
+            expected: take_column(&mut row, "expected")?,
+            actual: take_column(&mut row, "actual")?,
+        },
+        snapshot,
     })
 }
 
+/// Gets the bugs tracked before snapshots existed, as `(bug_id, channel_id, message_id)`.
+/// Imported bugs without a message are left out.
+pub async fn get_bugs_without_snapshot(ctx: &Context) -> Option<Vec<(u64, ChannelId, MessageId)>> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.query_map(
+        formatcp!(
+            "SELECT bug_id, channel_id, message_id FROM {} \
+WHERE snapshot_author_id IS NULL AND message_id != 0 ORDER BY bug_id ASC",
+            TABLE_BUG_REPORTS
+        ),
+        |(bug_id, channel_id, message_id)| (bug_id, ChannelId(channel_id), MessageId(message_id)),
+    )
+    .await
+    .ok()
+}
+
+/// Saves the content of the reported message of a bug tracked before snapshots
+/// existed, so that searches can match it. Its attachments are not archived.
+pub async fn add_missing_snapshot(ctx: &Context, bug_id: u64, msg: &Message) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "UPDATE {} SET snapshot_content = :snapshot_content, \
+snapshot_author_id = :snapshot_author_id, snapshot_author_name = :snapshot_author_name \
+WHERE bug_id = :bug_id AND snapshot_author_id IS NULL",
+            TABLE_BUG_REPORTS
+        ),
+        params! {
+            "bug_id" => bug_id,
+            "snapshot_content" => &msg.content,
+            "snapshot_author_id" => msg.author.id.0,
+            "snapshot_author_name" => &msg.author.name,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
 pub async fn add_bug_report(
     ctx: &Context,
+    server_id: GuildId,
     msg: &Message,
     title: String,
     status: BugStatus,
     category: BugCategory,
 ) -> Result<u64, CommandError> {
+    // archive the attachments themselves, in case the message or the files get deleted.
+    // They are downloaded before the transaction, so that it stays short.
+    let mut attachments = Vec::with_capacity(msg.attachments.len());
+    for attachment in &msg.attachments {
+        let data = if attachment.size <= MAX_ARCHIVED_ATTACHMENT_SIZE {
+            match attachment.download().await {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    println!("Could not archive attachment {}: {e}", attachment.filename);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        attachments.push((attachment, data));
+    }
+
     let mut conn = get_database_conn!(ctx);
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
 
-    conn.exec_drop(
+    tx.exec_drop(
         formatcp!(
-            "INSERT INTO {} (channel_id, message_id, title, status, category) \
-VALUES (:channel_id, :message_id, :title, :status, :category)",
+            "INSERT INTO {} (guild_id, channel_id, message_id, title, status, category, \
+snapshot_content, snapshot_author_id, snapshot_author_name) \
+VALUES (:guild_id, :channel_id, :message_id, :title, :status, :category, \
+:snapshot_content, :snapshot_author_id, :snapshot_author_name)",
             TABLE_BUG_REPORTS
         ),
         params! {
+            "guild_id" => server_id.0,
             "channel_id" => msg.channel_id.0,
             "message_id" => msg.id.0,
             "title" => title,
             "status" => status.as_str(),
             "category" => category.as_str(),
+            "snapshot_content" => &msg.content,
+            "snapshot_author_id" => msg.author.id.0,
+            "snapshot_author_name" => &msg.author.name,
         },
     )
     .await?;
 
+    let bug_id = tx
+        .last_insert_id()
+        .ok_or_else(|| CommandError::from("Could not get newest bug id!"))?;
+
+    tx.exec_batch(
+        formatcp!(
+            "INSERT INTO {} (bug_id, url, filename, content_type, data) \
+VALUES (:bug_id, :url, :filename, :content_type, :data)",
+            TABLE_BUG_REPORTS_ATTACHMENTS
+        ),
+        attachments.into_iter().map(|(attachment, data)| {
+            params! {
+                "bug_id" => bug_id,
+                "url" => &attachment.url,
+                "filename" => &attachment.filename,
+                "content_type" => &attachment.content_type,
+                "data" => data,
+            }
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
     if let Err(e) = msg.react(ctx, status.reaction()).await {
         println!("Could not add reaction to bug report: {e}");
     }
 
-    conn.query_first(formatcp!("SELECT MAX(bug_id) FROM {}", TABLE_BUG_REPORTS))
-        .await?
-        .ok_or_else(|| CommandError::from("Could not get newest bug id!"))
+    Ok(bug_id)
+}
+
+/// Gets the attachments archived with a bug report snapshot, as `(filename, data)` pairs
+pub async fn get_archived_attachments(
+    ctx: &Context,
+    bug_id: u64,
+) -> Option<Vec<(String, Vec<u8>)>> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec(
+        formatcp!(
+            "SELECT filename, data FROM {} \
+WHERE bug_id = :bug_id AND data IS NOT NULL ORDER BY attachment_id ASC",
+            TABLE_BUG_REPORTS_ATTACHMENTS
+        ),
+        params! {
+            "bug_id" => bug_id
+        },
+    )
+    .await
+    .ok()
+}
+
+pub async fn set_bug_thread(ctx: &Context, bug_id: u64, thread_id: ChannelId) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "UPDATE {} SET thread_id = :thread_id WHERE bug_id = :bug_id",
+            TABLE_BUG_REPORTS
+        ),
+        params! {
+            "thread_id" => thread_id.0,
+            "bug_id" => bug_id
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Filters applied when listing bug reports
+#[derive(Debug, Clone, Default)]
+pub struct BugFilter {
+    /// Only show bugs tracked in this guild
+    pub guild_id: Option<GuildId>,
+    /// Only show bugs with this status. Shows all open bugs if `None`, unless
+    /// `any_status` is set
+    pub status: Option<BugStatus>,
+    /// Show bugs of every status, open or not, when no status is given
+    pub any_status: bool,
+    pub category: Option<BugCategory>,
+    pub assignee: Option<UserId>,
+    /// Only show bugs that have all of these labels
+    pub labels: Vec<String>,
+    /// Only show bugs without status change, link addition or new subscriber
+    /// for this many days
+    pub inactive_days: Option<u32>,
+}
+
+impl BugFilter {
+    /// Builds the `WHERE` clause of the filter, along with its positional parameters
+    fn conditions(&self, workflow: &StatusWorkflow) -> (String, Vec<Value>) {
+        let mut conditions = vec![if let Some(status) = &self.status {
+            format!("status = '{}'", status.as_str())
+        } else if self.any_status {
+            "TRUE".into()
+        } else {
+            workflow.open_condition("status")
+        }];
+        if let Some(server_id) = self.guild_id {
+            conditions.push(format!("guild_id = {}", server_id.0));
+        }
+        if let Some(c) = self.category {
+            conditions.push(format!("category = '{}'", c.as_str()));
+        }
+        if let Some(user_id) = self.assignee {
+            conditions.push(format!(
+                "bug_id IN (SELECT bug_id FROM {TABLE_BUG_REPORTS_ASSIGNEES} WHERE user_id = {})",
+                user_id.0
+            ));
+        }
+        if let Some(days) = self.inactive_days {
+            // subscriptions made before their time was recorded have no timestamp
+            conditions.push(format!(
+                "GREATEST(timestamp, \
+COALESCE((SELECT MAX(history.timestamp) FROM {TABLE_BUG_REPORTS_HISTORY} AS history \
+WHERE history.bug_id = {TABLE_BUG_REPORTS}.bug_id AND history.change_type IN ('status', 'link_added')), timestamp), \
+COALESCE((SELECT MAX(notifications.timestamp) FROM {TABLE_BUG_REPORTS_NOTIFICATIONS} AS notifications \
+WHERE notifications.bug_id = {TABLE_BUG_REPORTS}.bug_id), timestamp)) \
+< UTC_TIMESTAMP() - INTERVAL {days} DAY"
+            ));
+        }
+        conditions.extend(self.labels.iter().map(|_| {
+            format!("bug_id IN (SELECT bug_id FROM {TABLE_BUG_REPORTS_LABELS} WHERE label = ?)")
+        }));
+
+        (
+            conditions.join(" AND "),
+            self.labels
+                .iter()
+                .map(|l| Value::from(l.as_str()))
+                .collect(),
+        )
+    }
 }
 
 pub async fn get_bug_list(
     ctx: &Context,
-    status: Option<BugStatus>,
+    filter: &BugFilter,
     limit: u32,
     display_order: BugOrder,
-    category: Option<BugCategory>,
     page: u32,
 ) -> Option<(Vec<PartialBugReport>, u32)> {
     let mut conn = get_database_conn!(ctx);
 
+    let workflow = match filter.guild_id {
+        Some(server_id) => load_status_workflow(&mut conn, server_id).await.ok()?,
+        None => StatusWorkflow::default(),
+    };
+    let (conditions, mut params) = filter.conditions(&workflow);
+
     let total: u32 = conn
-        .query_first(format!(
-            "SELECT COUNT(bug_id) FROM {} WHERE {} {category}",
-            TABLE_BUG_REPORTS,
-            if let Some(status) = status {
-                format!("status = '{}'", status.as_str())
-            } else {
-                "status != 'resolved' AND status != 'closed' AND status != 'forgevanilla'".into()
-            },
-            category = if let Some(c) = category {
-                format!("AND category = '{}'", c.as_str())
-            } else {
-                String::new()
-            },
-        ))
+        .exec_first(
+            format!("SELECT COUNT(bug_id) FROM {TABLE_BUG_REPORTS} WHERE {conditions}"),
+            params.clone(),
+        )
         .await
         .ok()??;
 
+    params.push(limit.into());
+    params.push((limit * page).into());
+
     conn.exec_map(
         format!(
-            "SELECT bug_id, title, status, timestamp, category FROM {} \
-WHERE {} {category} ORDER BY {ordering} LIMIT :limit OFFSET :offset",
-            TABLE_BUG_REPORTS,
-            if let Some(status) = status {
-                format!("status = '{}'", status.as_str())
-            } else {
-                "status != 'resolved' AND status != 'closed' AND status != 'forgevanilla'".into()
-            },
-            category = if let Some(c) = category {
-                format!("AND category = '{}'", c.as_str())
-            } else {
-                String::new()
-            },
+            "SELECT bug_id, {PREFIX_SQL}, title, status, timestamp, category FROM {TABLE_BUG_REPORTS} \
+LEFT JOIN {TABLE_BUG_TRACKERS} ON guild_id = server_id \
+WHERE {conditions} ORDER BY {ordering} LIMIT ? OFFSET ?",
             ordering = match display_order {
-                BugOrder::Chronological(false) | BugOrder::None => "timestamp DESC",
-                BugOrder::Chronological(true) => "timestamp ASC",
-                BugOrder::Priority(false) => "status DESC, timestamp DESC",
-                BugOrder::Priority(true) => "status ASC, timestamp DESC",
+                BugOrder::Chronological(false) | BugOrder::None => "timestamp DESC".into(),
+                BugOrder::Chronological(true) => "timestamp ASC".into(),
+                BugOrder::Priority(false) => format!(
+                    "{} DESC, timestamp DESC",
+                    workflow.priority_order("status")
+                ),
+                BugOrder::Priority(true) => format!(
+                    "{} ASC, timestamp DESC",
+                    workflow.priority_order("status")
+                ),
+                BugOrder::Votes => format!(
+                    "(SELECT COUNT(*) FROM {TABLE_BUG_REPORTS_VOTES} AS votes \
+WHERE votes.bug_id = {TABLE_BUG_REPORTS}.bug_id) DESC, timestamp DESC"
+                ),
             },
         ),
-        params! {
-            "limit" => limit,
-            "offset" => limit * page
-        },
-        |(bug_id, title, status, timestamp, category): (
+        params,
+        |(bug_id, prefix, title, status, timestamp, category): (
             u64,
             String,
             String,
+            String,
             NaiveDateTime,
             String,
         )| {
             PartialBugReport::new(
                 bug_id,
+                prefix,
                 title,
-                status
-                    .parse::<BugStatus>()
-                    .expect("Expected a valid bug status from the database"),
+                workflow.resolve(&status),
                 timestamp,
                 category
                     .parse()
@@ -389,225 +743,1626 @@ WHERE {} {category} ORDER BY {ordering} LIMIT :limit OFFSET :offset",
     .map(|v| (v, total))
 }
 
-pub async fn change_bug_status(
-    ctx: &Context,
-    bug_id: u64,
-    new_status: BugStatus,
-) -> Result<BugStatus, CommandError> {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedLink {
+    pub url: String,
+    pub title: String,
+}
+
+/// A bug report in the export format of `!bugs export`, also accepted by `!bugs import`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedBug {
+    /// Ignored on import: imported bugs get a new id
+    #[serde(default)]
+    pub bug_id: Option<u64>,
+    pub title: String,
+    pub status: String,
+    #[serde(default = "default_category")]
+    pub category: String,
+    /// RFC 3339 timestamp. Defaults to the time of the import.
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub channel_id: Option<u64>,
+    #[serde(default)]
+    pub message_id: Option<u64>,
+    #[serde(default)]
+    pub links: Vec<ExportedLink>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Ignored on import
+    #[serde(default)]
+    pub subscribers: u32,
+}
+
+fn default_category() -> String {
+    BugCategory::default().as_str().into()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BugExport {
+    pub bugs: Vec<ExportedBug>,
+}
+
+/// Gets every bug matching the filter, with its links, labels and subscriber count
+pub async fn export_bugs(ctx: &Context, filter: &BugFilter) -> Option<Vec<ExportedBug>> {
+    let (bugs, _) = get_bug_list(ctx, filter, u32::MAX, BugOrder::Chronological(true), 0).await?;
+    if bugs.is_empty() {
+        return Some(Vec::new());
+    }
+
     let mut conn = get_database_conn!(ctx);
 
-    let (old_status_string, channel_id, msg_id): (String, u64, u64) = conn
-        .exec_first(
-            formatcp!(
-                "SELECT status, channel_id, message_id FROM {} WHERE bug_id = :bug_id LIMIT 1",
-                TABLE_BUG_REPORTS
+    let ids = bugs
+        .iter()
+        .map(|b| b.bug_id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let messages: HashMap<u64, (u64, u64)> = conn
+        .query_map(
+            format!(
+                "SELECT bug_id, channel_id, message_id FROM {TABLE_BUG_REPORTS} WHERE bug_id IN ({ids})"
             ),
-            params! {
-                "bug_id" => bug_id
-            },
+            |(bug_id, channel_id, message_id)| (bug_id, (channel_id, message_id)),
         )
-        .await?
-        .ok_or_else(|| CommandError::from("Could not find bug in database"))?;
-
-    let old_status: BugStatus = old_status_string
-        .parse()
-        .expect("Expected a valid bug status from database!");
+        .await
+        .ok()?
+        .into_iter()
+        .collect();
+
+    let mut links: HashMap<u64, Vec<ExportedLink>> = HashMap::new();
+    for (bug_id, url, title) in conn
+        .query::<(u64, String, String), _>(format!(
+            "SELECT bug_id, link_url, link_title FROM {TABLE_BUG_REPORTS_LINKS} \
+WHERE bug_id IN ({ids}) ORDER BY link_id ASC"
+        ))
+        .await
+        .ok()?
+    {
+        links
+            .entry(bug_id)
+            .or_default()
+            .push(ExportedLink { url, title });
+    }
+
+    let mut labels: HashMap<u64, Vec<String>> = HashMap::new();
+    for (bug_id, label) in conn
+        .query::<(u64, String), _>(format!(
+            "SELECT bug_id, label FROM {TABLE_BUG_REPORTS_LABELS} \
+WHERE bug_id IN ({ids}) ORDER BY label ASC"
+        ))
+        .await
+        .ok()?
+    {
+        labels.entry(bug_id).or_default().push(label);
+    }
+
+    let subscribers: HashMap<u64, u32> = conn
+        .query(format!(
+            "SELECT bug_id, COUNT(user_id) FROM {TABLE_BUG_REPORTS_NOTIFICATIONS} \
+WHERE bug_id IN ({ids}) GROUP BY bug_id"
+        ))
+        .await
+        .ok()?
+        .into_iter()
+        .collect();
+
+    Some(
+        bugs.into_iter()
+            .map(|b| {
+                let (channel_id, message_id) = messages.get(&b.bug_id).copied().unzip();
+                ExportedBug {
+                    bug_id: Some(b.bug_id),
+                    title: b.title,
+                    status: b.status.as_str().into(),
+                    category: b.category.as_str().into(),
+                    timestamp: Some(b.timestamp.to_rfc3339()),
+                    channel_id,
+                    message_id,
+                    links: links.remove(&b.bug_id).unwrap_or_default(),
+                    labels: labels.remove(&b.bug_id).unwrap_or_default(),
+                    subscribers: subscribers.get(&b.bug_id).copied().unwrap_or_default(),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Imports bug reports into the bugtracker of a guild in a single transaction,
+/// returning their new ids.
+///
+/// Bugs without an original message are stored with a zero channel and message id.
+pub async fn import_bugs(
+    ctx: &Context,
+    server_id: GuildId,
+    bugs: &[ExportedBug],
+) -> CommandResult<Vec<u64>> {
+    let mut conn = get_database_conn!(ctx);
+    let workflow = load_status_workflow(&mut conn, server_id).await?;
+
+    let mut parsed = Vec::with_capacity(bugs.len());
+    for (i, bug) in bugs.iter().enumerate() {
+        let status = workflow.parse(&bug.status).ok_or_else(|| {
+            CommandError::from(format!(
+                "Invalid status `{}` for bug #{}",
+                bug.status,
+                i + 1
+            ))
+        })?;
+        let category = bug.category.parse::<BugCategory>().map_err(|_| {
+            CommandError::from(format!(
+                "Invalid edition `{}` for bug #{}",
+                bug.category,
+                i + 1
+            ))
+        })?;
+        let timestamp = match &bug.timestamp {
+            Some(t) => DateTime::parse_from_rfc3339(t)
+                .map_err(|_| {
+                    CommandError::from(format!("Invalid timestamp `{}` for bug #{}", t, i + 1))
+                })?
+                .naive_utc(),
+            None => Utc::now().naive_utc(),
+        };
+        parsed.push((bug, status, category, timestamp));
+    }
+
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+
+    let mut ids = Vec::with_capacity(parsed.len());
+    for (bug, status, category, timestamp) in parsed {
+        tx.exec_drop(
+            formatcp!(
+                "INSERT INTO {} (guild_id, channel_id, message_id, title, status, category, timestamp) \
+VALUES (:guild_id, :channel_id, :message_id, :title, :status, :category, :timestamp)",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "guild_id" => server_id.0,
+                "channel_id" => bug.channel_id.unwrap_or_default(),
+                "message_id" => bug.message_id.unwrap_or_default(),
+                "title" => &bug.title,
+                "status" => status.as_str(),
+                "category" => category.as_str(),
+                "timestamp" => timestamp,
+            },
+        )
+        .await?;
+
+        let bug_id = tx
+            .last_insert_id()
+            .ok_or_else(|| CommandError::from("Could not get imported bug id!"))?;
+
+        tx.exec_batch(
+            formatcp!(
+                "INSERT INTO {} (bug_id, link_url, link_title) VALUES (:bug_id, :link_url, :link_title)",
+                TABLE_BUG_REPORTS_LINKS
+            ),
+            bug.links.iter().map(|link| {
+                params! {
+                    "bug_id" => bug_id,
+                    "link_url" => &link.url,
+                    "link_title" => &link.title,
+                }
+            }),
+        )
+        .await?;
+
+        tx.exec_batch(
+            formatcp!(
+                "INSERT IGNORE INTO {} (bug_id, label) VALUES (:bug_id, :label)",
+                TABLE_BUG_REPORTS_LABELS
+            ),
+            bug.labels.iter().map(|label| {
+                params! {
+                    "bug_id" => bug_id,
+                    "label" => label.to_lowercase(),
+                }
+            }),
+        )
+        .await?;
+
+        ids.push(bug_id);
+    }
+
+    tx.commit().await?;
+
+    Ok(ids)
+}
+
+/// Escapes the `LIKE` wildcards of a search term
+fn escape_like(term: &str) -> String {
+    let mut res = String::with_capacity(term.len() + 2);
+    for c in term.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// Searches the bugs of a guild whose title, reported message or link titles
+/// contain every term. The message of bugs tracked before snapshots existed
+/// is only searchable once saved with `!bug snapshots`.
+pub async fn search_bugs(
+    ctx: &Context,
+    server_id: GuildId,
+    terms: &str,
+    status: Option<BugStatus>,
+    category: Option<BugCategory>,
+    limit: u32,
+    page: u32,
+) -> Option<(Vec<PartialBugReport>, u32)> {
+    let mut conn = get_database_conn!(ctx);
+
+    let workflow = load_status_workflow(&mut conn, server_id).await.ok()?;
+
+    let words = terms
+        .split_whitespace()
+        .map(|w| format!("%{}%", escape_like(w)))
+        .collect::<Vec<_>>();
+
+    let mut conditions = Vec::with_capacity(words.len() + 3);
+    conditions.push(format!("guild_id = {}", server_id.0));
+    if let Some(status) = status {
+        conditions.push(format!("status = '{}'", status.as_str()));
+    }
+    if let Some(c) = category {
+        conditions.push(format!("category = '{}'", c.as_str()));
+    }
+    // every search term must appear either in the title, the reported message or a link title
+    conditions.extend(words.iter().map(|_| {
+        format!(
+            "(bugs.title LIKE ? OR bugs.snapshot_content LIKE ? \
+OR EXISTS(SELECT link_id FROM {TABLE_BUG_REPORTS_LINKS} AS links \
+WHERE links.bug_id = bugs.bug_id AND links.link_title LIKE ?))"
+        )
+    }));
+    let conditions = conditions.join(" AND ");
+
+    let mut params = words
+        .iter()
+        .flat_map(|w| {
+            [
+                Value::from(w.as_str()),
+                Value::from(w.as_str()),
+                Value::from(w.as_str()),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let total: u32 = conn
+        .exec_first(
+            format!("SELECT COUNT(bug_id) FROM {TABLE_BUG_REPORTS} AS bugs WHERE {conditions}"),
+            params.clone(),
+        )
+        .await
+        .ok()??;
+
+    params.push(limit.into());
+    params.push((limit * page).into());
+
+    conn.exec_map(
+        format!(
+            "SELECT bug_id, {PREFIX_SQL}, title, status, timestamp, category FROM {TABLE_BUG_REPORTS} AS bugs \
+LEFT JOIN {TABLE_BUG_TRACKERS} ON guild_id = server_id \
+WHERE {conditions} ORDER BY timestamp DESC LIMIT ? OFFSET ?"
+        ),
+        params,
+        |(bug_id, prefix, title, status, timestamp, category): (
+            u64,
+            String,
+            String,
+            String,
+            NaiveDateTime,
+            String,
+        )| {
+            PartialBugReport::new(
+                bug_id,
+                prefix,
+                title,
+                workflow.resolve(&status),
+                timestamp,
+                category
+                    .parse()
+                    .expect("Expected a valid category from the database"),
+            )
+        },
+    )
+    .await
+    .ok()
+    .map(|v| v.into_iter().flatten().collect())
+    .map(|v| (v, total))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BugChange {
+    Status,
+    Title,
+    Category,
+    LinkAdded,
+    LinkRemoved,
+    Duplicate,
+    Assigned,
+    Unassigned,
+    LabelAdded,
+    LabelRemoved,
+    Field,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseChangeError;
+
+impl std::str::FromStr for BugChange {
+    type Err = ParseChangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use BugChange::*;
+
+        Ok(match s {
+            "status" => Status,
+            "title" => Title,
+            "category" => Category,
+            "link_added" => LinkAdded,
+            "link_removed" => LinkRemoved,
+            "duplicate" => Duplicate,
+            "assigned" => Assigned,
+            "unassigned" => Unassigned,
+            "label_added" => LabelAdded,
+            "label_removed" => LabelRemoved,
+            "field" => Field,
+            _ => return Err(ParseChangeError),
+        })
+    }
+}
+
+impl BugChange {
+    pub const fn as_str(self) -> &'static str {
+        use BugChange::*;
+        match self {
+            Status => "status",
+            Title => "title",
+            Category => "category",
+            LinkAdded => "link_added",
+            LinkRemoved => "link_removed",
+            Duplicate => "duplicate",
+            Assigned => "assigned",
+            Unassigned => "unassigned",
+            LabelAdded => "label_added",
+            LabelRemoved => "label_removed",
+            Field => "field",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BugHistoryEntry {
+    pub user_id: UserId,
+    pub timestamp: DateTime<Utc>,
+    pub change: BugChange,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl std::fmt::Display for BugHistoryEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use BugChange::*;
+
+        write!(
+            f,
+            "{} {} ",
+            self.timestamp.format("<t:%s:f>"),
+            self.user_id.mention()
+        )?;
+        let old = self.old_value.as_deref().unwrap_or_default();
+        let new = self.new_value.as_deref().unwrap_or_default();
+        match self.change {
+            Status => write!(f, "changed the status from `{old}` to `{new}`"),
+            Title => write!(f, "renamed the bug from \"{old}\" to \"{new}\""),
+            Category => write!(f, "changed the edition from `{old}` to `{new}`"),
+            LinkAdded => write!(f, "added the link {new}"),
+            LinkRemoved => write!(f, "removed the link {old}"),
+            Duplicate => write!(f, "marked the bug as a duplicate of {new}"),
+            Assigned => write!(f, "assigned {new} to the bug"),
+            Unassigned => write!(f, "unassigned {old} from the bug"),
+            LabelAdded => write!(f, "added the label `{new}`"),
+            LabelRemoved => write!(f, "removed the label `{old}`"),
+            Field => match (&self.old_value, &self.new_value) {
+                (_, Some(new)) => write!(f, "set {new}"),
+                (Some(old), None) => write!(f, "cleared {old}"),
+                (None, None) => write!(f, "edited the bug details"),
+            },
+        }
+    }
+}
+
+async fn record_change(
+    conn: &mut impl Queryable,
+    bug_id: u64,
+    user_id: UserId,
+    change: BugChange,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+) -> Result<(), mysql_async::Error> {
+    conn.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (bug_id, user_id, change_type, old_value, new_value) \
+VALUES (:bug_id, :user_id, :change_type, :old_value, :new_value)",
+            TABLE_BUG_REPORTS_HISTORY
+        ),
+        params! {
+            "bug_id" => bug_id,
+            "user_id" => user_id.0,
+            "change_type" => change.as_str(),
+            "old_value" => old_value,
+            "new_value" => new_value,
+        },
+    )
+    .await
+}
+
+pub async fn get_bug_history(
+    ctx: &Context,
+    bug_id: u64,
+    limit: u32,
+    page: u32,
+) -> Option<(Vec<BugHistoryEntry>, u32)> {
+    let mut conn = get_database_conn!(ctx);
+
+    let total: u32 = conn
+        .exec_first(
+            formatcp!(
+                "SELECT COUNT(history_id) FROM {} WHERE bug_id = :bug_id",
+                TABLE_BUG_REPORTS_HISTORY
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+        )
+        .await
+        .ok()??;
+
+    conn.exec_map(
+        formatcp!(
+            "SELECT user_id, timestamp, change_type, old_value, new_value FROM {} \
+WHERE bug_id = :bug_id ORDER BY timestamp DESC, history_id DESC LIMIT :limit OFFSET :offset",
+            TABLE_BUG_REPORTS_HISTORY
+        ),
+        params! {
+            "bug_id" => bug_id,
+            "limit" => limit,
+            "offset" => limit * page
+        },
+        |(user_id, timestamp, change, old_value, new_value): (
+            u64,
+            NaiveDateTime,
+            String,
+            Option<String>,
+            Option<String>,
+        )| BugHistoryEntry {
+            user_id: UserId(user_id),
+            timestamp: Utc.from_utc_datetime(&timestamp),
+            change: change
+                .parse()
+                .expect("Expected a valid change type from the database"),
+            old_value,
+            new_value,
+        },
+    )
+    .await
+    .ok()
+    .map(|v| (v, total))
+}
+
+pub async fn change_bug_status(
+    ctx: &Context,
+    bug_id: u64,
+    new_status: BugStatus,
+    user_id: UserId,
+) -> Result<BugStatus, CommandError> {
+    let mut conn = get_database_conn!(ctx);
+
+    update_bug_status(&mut conn, &ctx.http, bug_id, new_status, user_id).await
+}
+
+/// Changes the status of a bug, returning its old status
+pub async fn update_bug_status(
+    conn: &mut Conn,
+    http: &Http,
+    bug_id: u64,
+    new_status: BugStatus,
+    user_id: UserId,
+) -> Result<BugStatus, CommandError> {
+    let (guild_id, old_status_string, channel_id, msg_id, thread_id): (
+        u64,
+        String,
+        u64,
+        u64,
+        Option<u64>,
+    ) = conn
+        .exec_first(
+            formatcp!(
+                "SELECT guild_id, status, channel_id, message_id, thread_id FROM {} \
+WHERE bug_id = :bug_id LIMIT 1",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+        )
+        .await?
+        .ok_or_else(|| CommandError::from("Could not find bug in database"))?;
+
+    let old_status = load_status_workflow(conn, GuildId(guild_id))
+        .await?
+        .resolve(&old_status_string);
+
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+    tx.exec_drop(
+        formatcp!(
+            "UPDATE {} SET status = :status WHERE bug_id = :bug_id",
+            TABLE_BUG_REPORTS
+        ),
+        params! {
+            "status" => new_status.as_str(),
+            "bug_id" => bug_id
+        },
+    )
+    .await?;
+
+    if old_status != new_status {
+        record_change(
+            &mut tx,
+            bug_id,
+            user_id,
+            BugChange::Status,
+            Some(old_status.as_str()),
+            Some(new_status.as_str()),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    if old_status != new_status {
+        if let Some(thread_id) = thread_id {
+            post_status_change(
+                http,
+                ChannelId(thread_id),
+                &old_status,
+                &new_status,
+                user_id,
+            )
+            .await;
+        }
+    }
+
+    update_status_reaction(http, channel_id, msg_id, &old_status, &new_status).await;
+
+    Ok(old_status)
+}
+
+/// Posts a status change in the discussion thread of a bug report
+async fn post_status_change(
+    http: &Http,
+    thread_id: ChannelId,
+    old_status: &BugStatus,
+    new_status: &BugStatus,
+    user_id: UserId,
+) {
+    if let Err(e) = thread_id
+        .send_message(http, |m| {
+            m.content(format!(
+                "{} {} changed the status from `{}` to `{}`",
+                new_status.marker(),
+                user_id.mention(),
+                old_status,
+                new_status
+            ))
+            .allowed_mentions(|f| f.empty_parse())
+        })
+        .await
+    {
+        println!("Could not post status change in bug report thread: {e}");
+    }
+}
+
+async fn update_status_reaction(
+    http: &Http,
+    channel_id: u64,
+    msg_id: u64,
+    old_status: &BugStatus,
+    new_status: &BugStatus,
+) {
+    match ChannelId(channel_id).message(http, MessageId(msg_id)).await {
+        Ok(msg) => {
+            if let Err(e) = msg.delete_reaction_emoji(http, old_status.reaction()).await {
+                println!("Could not remove reaction from bug report: {e}");
+            }
+            if let Err(e) = msg.react(http, new_status.reaction()).await {
+                println!("Could not add reaction to bug report: {e}");
+            }
+        }
+        Err(e) => println!("Could not get message for bug report: {e}"),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusChange {
+    pub bug_id: u64,
+    pub title: String,
+    pub old_status: BugStatus,
+}
+
+/// Changes the status of several bugs in a single transaction.
+///
+/// If one of the bugs does not exist, no status is changed.
+pub async fn change_bug_statuses(
+    ctx: &Context,
+    tracker: &BugTracker,
+    bug_ids: &[u64],
+    new_status: BugStatus,
+    user_id: UserId,
+) -> Result<Vec<StatusChange>, CommandError> {
+    let mut conn = get_database_conn!(ctx);
+    let workflow = load_status_workflow(&mut conn, tracker.server_id).await?;
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+
+    let mut changes = Vec::with_capacity(bug_ids.len());
+    let mut messages = Vec::with_capacity(bug_ids.len());
+    for &bug_id in bug_ids {
+        let (old_status, title, channel_id, msg_id, thread_id): (
+            String,
+            String,
+            u64,
+            u64,
+            Option<u64>,
+        ) = tx
+            .exec_first(
+                formatcp!(
+                    "SELECT status, title, channel_id, message_id, thread_id FROM {} \
+WHERE bug_id = :bug_id AND guild_id = :guild_id LIMIT 1 FOR UPDATE",
+                    TABLE_BUG_REPORTS
+                ),
+                params! {
+                    "bug_id" => bug_id,
+                    "guild_id" => tracker.server_id.0,
+                },
+            )
+            .await?
+            .ok_or_else(|| {
+                CommandError::from(format!(
+                    "The bug {}-{bug_id} does not exist!",
+                    tracker.prefix
+                ))
+            })?;
+
+        let old_status = workflow.resolve(&old_status);
+        if old_status == new_status {
+            continue;
+        }
+
+        tx.exec_drop(
+            formatcp!(
+                "UPDATE {} SET status = :status WHERE bug_id = :bug_id",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "status" => new_status.as_str(),
+                "bug_id" => bug_id
+            },
+        )
+        .await?;
+
+        record_change(
+            &mut tx,
+            bug_id,
+            user_id,
+            BugChange::Status,
+            Some(old_status.as_str()),
+            Some(new_status.as_str()),
+        )
+        .await?;
+
+        changes.push(StatusChange {
+            bug_id,
+            title,
+            old_status: old_status.clone(),
+        });
+        messages.push((channel_id, msg_id, thread_id, old_status));
+    }
+
+    tx.commit().await?;
+
+    for (channel_id, msg_id, thread_id, old_status) in messages {
+        if let Some(thread_id) = thread_id {
+            post_status_change(
+                &ctx.http,
+                ChannelId(thread_id),
+                &old_status,
+                &new_status,
+                user_id,
+            )
+            .await;
+        }
+        update_status_reaction(&ctx.http, channel_id, msg_id, &old_status, &new_status).await;
+    }
+
+    Ok(changes)
+}
+
+pub async fn add_link(
+    ctx: &Context,
+    bug_id: u64,
+    link_url: &str,
+    link_title: &str,
+    user_id: UserId,
+) -> Option<u64> {
+    let mut conn = get_database_conn!(ctx);
+
+    insert_link(&mut conn, bug_id, link_url, link_title, user_id).await
+}
+
+/// Adds a link to a bug, returning the new link id
+pub async fn insert_link(
+    conn: &mut Conn,
+    bug_id: u64,
+    link_url: &str,
+    link_title: &str,
+    user_id: UserId,
+) -> Option<u64> {
+    let mut tx = conn.start_transaction(TxOpts::default()).await.ok()?;
+    tx.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (bug_id, link_url, link_title) VALUES (:bug_id, :link_url, :link_title)",
+            TABLE_BUG_REPORTS_LINKS
+        ),
+        params! {
+            "bug_id" => bug_id,
+            "link_url" => link_url,
+            "link_title" => link_title
+        },
+    )
+    .await.ok()?;
+
+    let link_id = tx
+        .exec_first(
+            formatcp!(
+                "SELECT MAX(link_id) FROM {} WHERE bug_id = :bug_id",
+                TABLE_BUG_REPORTS_LINKS
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+        )
+        .await
+        .ok()??;
+
+    let link = BugLink {
+        id: link_id,
+        url: link_url.into(),
+        title: link_title.into(),
+    };
+    record_change(
+        &mut tx,
+        bug_id,
+        user_id,
+        BugChange::LinkAdded,
+        None,
+        Some(&link.to_string()),
+    )
+    .await
+    .ok()?;
+    tx.commit().await.ok()?;
+
+    Some(link_id)
+}
+
+pub async fn remove_link(
+    ctx: &Context,
+    bug_id: u64,
+    link_num: u64,
+    user_id: UserId,
+) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    let link = conn
+        .exec_first(
+            formatcp!(
+                "SELECT link_url, link_title FROM {} WHERE bug_id = :bug_id AND link_id = :link_id",
+                TABLE_BUG_REPORTS_LINKS
+            ),
+            params! {
+                "bug_id" => bug_id,
+                "link_id" => link_num
+            },
+        )
+        .await?
+        .map(|(url, title)| BugLink {
+            id: link_num,
+            url,
+            title,
+        })
+        .ok_or_else(|| CommandError::from("Link does not exist!"))?;
+
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+    tx.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE bug_id = :bug_id AND link_id = :link_id",
+            TABLE_BUG_REPORTS_LINKS
+        ),
+        params! {
+            "bug_id" => bug_id,
+            "link_id" => link_num
+        },
+    )
+    .await?;
+
+    record_change(
+        &mut tx,
+        bug_id,
+        user_id,
+        BugChange::LinkRemoved,
+        Some(&link.to_string()),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn change_title(
+    ctx: &Context,
+    bug_id: u64,
+    new_title: &str,
+    user_id: UserId,
+) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    update_title(&mut conn, bug_id, new_title, user_id).await
+}
+
+pub async fn update_title(
+    conn: &mut Conn,
+    bug_id: u64,
+    new_title: &str,
+    user_id: UserId,
+) -> CommandResult {
+    let old_title: String = conn
+        .exec_first(
+            formatcp!(
+                "SELECT title FROM {} WHERE bug_id = :bug_id",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+        )
+        .await?
+        .ok_or_else(|| CommandError::from("Could not find bug in database"))?;
+
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+    tx.exec_drop(
+        formatcp!(
+            "UPDATE {} SET title = :new_title WHERE bug_id = :bug_id",
+            TABLE_BUG_REPORTS
+        ),
+        params! {
+            "new_title" => new_title,
+            "bug_id" => bug_id
+        },
+    )
+    .await?;
+
+    if old_title != new_title {
+        record_change(
+            &mut tx,
+            bug_id,
+            user_id,
+            BugChange::Title,
+            Some(&old_title),
+            Some(new_title),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct BugCounts {
+    /// Number of bugs with each status, in the order of the workflow. Statuses
+    /// that were removed from the workflow but that some bugs still have come last.
+    pub statuses: Vec<(BugStatus, u32)>,
+    pub total: u32,
+    pub legacy: u32,
+    /// Number of open bugs with each label, most used first
+    pub labels: Vec<(String, u32)>,
+}
+
+impl BugCounts {
+    pub fn open(&self) -> u32 {
+        self.statuses
+            .iter()
+            .filter(|(status, _)| status.is_open)
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+pub async fn get_bug_statistics(ctx: &Context, server_id: GuildId) -> Option<BugCounts> {
+    let mut conn = get_database_conn!(ctx);
+
+    count_bugs(&mut conn, server_id).await
+}
+
+/// Counts the bugs of a guild by status, edition and label
+pub async fn count_bugs(conn: &mut Conn, server_id: GuildId) -> Option<BugCounts> {
+    let workflow = load_status_workflow(conn, server_id).await.ok()?;
+
+    let mut by_status: HashMap<String, u32> = conn
+        .exec(
+            formatcp!(
+                "SELECT status, COUNT(bug_id) FROM {} WHERE guild_id = :guild_id GROUP BY status",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "guild_id" => server_id.0
+            },
+        )
+        .await
+        .ok()?
+        .into_iter()
+        .collect();
+
+    let mut statuses = workflow
+        .statuses
+        .iter()
+        .map(|status| {
+            (
+                status.clone(),
+                by_status.remove(&status.name).unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+    statuses.extend(
+        by_status
+            .into_iter()
+            .map(|(name, count)| (workflow.resolve(&name), count)),
+    );
+
+    let total = statuses.iter().map(|(_, count)| count).sum();
+
+    let legacy = conn
+        .exec_first(
+            formatcp!(
+                "SELECT COUNT(bug_id) FROM {} WHERE category = 'legacy' AND guild_id = :guild_id",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "guild_id" => server_id.0
+            },
+        )
+        .await
+        .ok()??;
+
+    let labels = conn
+        .exec(
+            format!(
+                "SELECT label, COUNT(labels.bug_id) AS label_count FROM {TABLE_BUG_REPORTS_LABELS} AS labels \
+JOIN {TABLE_BUG_REPORTS} AS bugs ON labels.bug_id = bugs.bug_id WHERE bugs.guild_id = :guild_id \
+AND {} GROUP BY label ORDER BY label_count DESC, label ASC",
+                workflow.open_condition("bugs.status")
+            ),
+            params! {
+                "guild_id" => server_id.0
+            },
+        )
+        .await
+        .ok()?;
+
+    Some(BugCounts {
+        statuses,
+        total,
+        legacy,
+        labels,
+    })
+}
+
+/// Sets or clears (with a `None` value) a structured field of a bug report
+pub async fn change_field(
+    ctx: &Context,
+    bug_id: u64,
+    field: BugField,
+    value: Option<&str>,
+    user_id: UserId,
+) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    let old_value: Option<String> = conn
+        .exec_first(
+            format!(
+                "SELECT {} FROM {TABLE_BUG_REPORTS} WHERE bug_id = :bug_id",
+                field.column()
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+        )
+        .await?
+        .ok_or_else(|| CommandError::from("Bug report does not exist!"))?;
+
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+    tx.exec_drop(
+        format!(
+            "UPDATE {TABLE_BUG_REPORTS} SET {} = :value WHERE bug_id = :bug_id",
+            field.column()
+        ),
+        params! {
+            "value" => value,
+            "bug_id" => bug_id
+        },
+    )
+    .await?;
+
+    record_change(
+        &mut tx,
+        bug_id,
+        user_id,
+        BugChange::Field,
+        old_value.map(|v| format!("**{field}**: {v}")).as_deref(),
+        value.map(|v| format!("**{field}**: {v}")).as_deref(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn change_category(
+    ctx: &Context,
+    bug_id: u64,
+    category: BugCategory,
+    user_id: UserId,
+) -> Option<BugCategory> {
+    let mut conn = get_database_conn!(ctx);
+
+    let old_category: BugCategory = conn
+        .exec_first::<String, _, _>(
+            formatcp!(
+                "SELECT category FROM {} WHERE bug_id = :bug_id",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "bug_id" => bug_id,
+            },
+        )
+        .await
+        .ok()??
+        .parse()
+        .expect("Expected a valid bug category from the database");
+
+    let mut tx = conn.start_transaction(TxOpts::default()).await.ok()?;
+    tx.exec_drop(
+        formatcp!(
+            "UPDATE {} SET category = :category WHERE bug_id = :bug_id",
+            TABLE_BUG_REPORTS
+        ),
+        params! {
+            "category" => category.as_str(),
+            "bug_id" => bug_id
+        },
+    )
+    .await
+    .ok()?;
 
-    conn.exec_drop(
+    if old_category != category {
+        record_change(
+            &mut tx,
+            bug_id,
+            user_id,
+            BugChange::Category,
+            Some(old_category.as_str()),
+            Some(category.as_str()),
+        )
+        .await
+        .ok()?;
+    }
+    tx.commit().await.ok()?;
+
+    Some(old_category)
+}
+
+/// Marks a bug report as a duplicate of another one and changes its status, in
+/// a single transaction: its subscribers and votes are moved to the original
+/// report, and its links are copied over. The bugs that were duplicates of it
+/// become duplicates of the original report, so that there are no chains.
+///
+/// Returns the old status of the duplicate and the users who were subscribed to it.
+pub async fn merge_duplicate(
+    ctx: &Context,
+    bug_id: u64,
+    original_id: u64,
+    new_status: BugStatus,
+    user_id: UserId,
+) -> Result<(BugStatus, Vec<UserId>), CommandError> {
+    let prefix = get_bug_prefix(ctx, original_id).await;
+    let original = format!("{prefix}-{original_id}");
+    let mut conn = get_database_conn!(ctx);
+
+    let (guild_id, old_status, channel_id, msg_id, thread_id): (
+        u64,
+        String,
+        u64,
+        u64,
+        Option<u64>,
+    ) = conn
+        .exec_first(
+            formatcp!(
+                "SELECT guild_id, status, channel_id, message_id, thread_id FROM {} \
+WHERE bug_id = :bug_id LIMIT 1",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+        )
+        .await?
+        .ok_or_else(|| CommandError::from("Could not find bug in database"))?;
+    let old_status = load_status_workflow(&mut conn, GuildId(guild_id))
+        .await?
+        .resolve(&old_status);
+
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+
+    tx.exec_drop(
         formatcp!(
-            "UPDATE {} SET status = :status WHERE bug_id = :bug_id",
+            "UPDATE {} SET duplicate_of = :original_id WHERE bug_id = :bug_id",
             TABLE_BUG_REPORTS
         ),
         params! {
-            "status" => new_status.as_str(),
+            "original_id" => original_id,
             "bug_id" => bug_id
         },
     )
     .await?;
+    record_change(
+        &mut tx,
+        bug_id,
+        user_id,
+        BugChange::Duplicate,
+        None,
+        Some(&original),
+    )
+    .await?;
 
-    match ChannelId(channel_id).message(ctx, MessageId(msg_id)).await {
-        Ok(msg) => {
-            if let Err(e) = msg.delete_reaction_emoji(ctx, old_status.reaction()).await {
-                println!("Could not remove reaction from bug report: {e}");
-            }
-            if let Err(e) = msg.react(ctx, new_status.reaction()).await {
-                println!("Could not add reaction to bug report: {e}");
-            }
+    let duplicates: Vec<u64> = tx
+        .exec(
+            formatcp!(
+                "SELECT bug_id FROM {} WHERE duplicate_of = :bug_id FOR UPDATE",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+        )
+        .await?;
+    if !duplicates.is_empty() {
+        tx.exec_drop(
+            formatcp!(
+                "UPDATE {} SET duplicate_of = :original_id WHERE duplicate_of = :bug_id",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "original_id" => original_id,
+                "bug_id" => bug_id
+            },
+        )
+        .await?;
+        let duplicate = format!("{prefix}-{bug_id}");
+        for other_id in duplicates {
+            record_change(
+                &mut tx,
+                other_id,
+                user_id,
+                BugChange::Duplicate,
+                Some(&duplicate),
+                Some(&original),
+            )
+            .await?;
         }
-        Err(e) => println!("Could not get message for bug report: {e}"),
     }
 
-    Ok(old_status)
-}
+    if old_status != new_status {
+        tx.exec_drop(
+            formatcp!(
+                "UPDATE {} SET status = :status WHERE bug_id = :bug_id",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "status" => new_status.as_str(),
+                "bug_id" => bug_id
+            },
+        )
+        .await?;
+        record_change(
+            &mut tx,
+            bug_id,
+            user_id,
+            BugChange::Status,
+            Some(old_status.as_str()),
+            Some(new_status.as_str()),
+        )
+        .await?;
+    }
 
-pub async fn add_link(ctx: &Context, bug_id: u64, link_url: &str, link_title: &str) -> Option<u64> {
-    let mut conn = get_database_conn!(ctx);
+    let subscribers = tx
+        .exec_map(
+            formatcp!(
+                "SELECT user_id FROM {} WHERE bug_id = :bug_id",
+                TABLE_BUG_REPORTS_NOTIFICATIONS
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+            UserId,
+        )
+        .await?;
 
-    conn.exec_drop(
+    tx.exec_drop(
         formatcp!(
-            "INSERT INTO {} (bug_id, link_url, link_title) VALUES (:bug_id, :link_url, :link_title)",
-            TABLE_BUG_REPORTS_LINKS
+            "INSERT INTO {TABLE_BUG_REPORTS_NOTIFICATIONS} (bug_id, user_id, timestamp) \
+SELECT :original_id, user_id, timestamp FROM {TABLE_BUG_REPORTS_NOTIFICATIONS} \
+WHERE bug_id = :bug_id AND user_id NOT IN \
+(SELECT user_id FROM {TABLE_BUG_REPORTS_NOTIFICATIONS} WHERE bug_id = :original_id)"
         ),
         params! {
-            "bug_id" => bug_id,
-            "link_url" => link_url,
-            "link_title" => link_title
+            "original_id" => original_id,
+            "bug_id" => bug_id
         },
     )
-    .await.ok()?;
+    .await?;
 
-    conn.exec_first(
+    tx.exec_drop(
         formatcp!(
-            "SELECT MAX(link_id) FROM {} WHERE bug_id = :bug_id",
-            TABLE_BUG_REPORTS_LINKS
+            "DELETE FROM {} WHERE bug_id = :bug_id",
+            TABLE_BUG_REPORTS_NOTIFICATIONS
+        ),
+        params! {
+            "bug_id" => bug_id
+        },
+    )
+    .await?;
+
+    tx.exec_drop(
+        formatcp!(
+            "INSERT INTO {TABLE_BUG_REPORTS_LINKS} (bug_id, link_url, link_title) \
+SELECT :original_id, link_url, link_title FROM {TABLE_BUG_REPORTS_LINKS} WHERE bug_id = :bug_id"
+        ),
+        params! {
+            "original_id" => original_id,
+            "bug_id" => bug_id
+        },
+    )
+    .await?;
+
+    tx.exec_drop(
+        formatcp!(
+            "INSERT IGNORE INTO {TABLE_BUG_REPORTS_VOTES} (bug_id, user_id, timestamp) \
+SELECT :original_id, user_id, timestamp FROM {TABLE_BUG_REPORTS_VOTES} WHERE bug_id = :bug_id"
+        ),
+        params! {
+            "original_id" => original_id,
+            "bug_id" => bug_id
+        },
+    )
+    .await?;
+
+    tx.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE bug_id = :bug_id",
+            TABLE_BUG_REPORTS_VOTES
         ),
         params! {
             "bug_id" => bug_id
         },
     )
+    .await?;
+
+    tx.commit().await?;
+
+    if old_status != new_status {
+        if let Some(thread_id) = thread_id {
+            post_status_change(
+                &ctx.http,
+                ChannelId(thread_id),
+                &old_status,
+                &new_status,
+                user_id,
+            )
+            .await;
+        }
+        update_status_reaction(&ctx.http, channel_id, msg_id, &old_status, &new_status).await;
+    }
+
+    Ok((old_status, subscribers))
+}
+
+pub async fn is_assignee(ctx: &Context, bug_id: u64, user_id: UserId) -> Option<bool> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_first(
+        formatcp!(
+            "SELECT EXISTS(SELECT assignee_id \
+FROM {} WHERE bug_id = :bug_id AND user_id = :user_id)",
+            TABLE_BUG_REPORTS_ASSIGNEES
+        ),
+        params! {
+                "bug_id" => bug_id,
+                "user_id" => user_id.0
+        },
+    )
     .await
     .ok()?
 }
 
-pub async fn remove_link(ctx: &Context, bug_id: u64, link_num: u64) -> CommandResult {
+pub async fn add_assignee(
+    ctx: &Context,
+    bug_id: u64,
+    user_id: UserId,
+    assigned_by: UserId,
+) -> CommandResult {
     let mut conn = get_database_conn!(ctx);
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
 
-    conn.exec_drop(
+    tx.exec_drop(
         formatcp!(
-            "DELETE FROM {} WHERE bug_id = :bug_id AND link_id = :link_id",
-            TABLE_BUG_REPORTS_LINKS
+            "INSERT INTO {} (bug_id, user_id) VALUES (:bug_id, :user_id)",
+            TABLE_BUG_REPORTS_ASSIGNEES
         ),
         params! {
             "bug_id" => bug_id,
-            "link_id" => link_num
+            "user_id" => user_id.0
         },
     )
     .await?;
 
+    record_change(
+        &mut tx,
+        bug_id,
+        assigned_by,
+        BugChange::Assigned,
+        None,
+        Some(&user_id.mention().to_string()),
+    )
+    .await?;
+    tx.commit().await?;
+
     Ok(())
 }
 
-pub async fn change_title(ctx: &Context, bug_id: u64, new_title: &str) -> CommandResult {
+pub async fn remove_assignee(
+    ctx: &Context,
+    bug_id: u64,
+    user_id: UserId,
+    unassigned_by: UserId,
+) -> CommandResult {
     let mut conn = get_database_conn!(ctx);
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
 
-    conn.exec_drop(
+    tx.exec_drop(
         formatcp!(
-            "UPDATE {} SET title = :new_title WHERE bug_id = :bug_id",
-            TABLE_BUG_REPORTS
+            "DELETE FROM {} WHERE bug_id = :bug_id AND user_id = :user_id",
+            TABLE_BUG_REPORTS_ASSIGNEES
         ),
         params! {
-            "new_title" => new_title,
-            "bug_id" => bug_id
+            "bug_id" => bug_id,
+            "user_id" => user_id.0
         },
     )
     .await?;
 
+    record_change(
+        &mut tx,
+        bug_id,
+        unassigned_by,
+        BugChange::Unassigned,
+        Some(&user_id.mention().to_string()),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+
     Ok(())
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct BugCounts {
-    pub resolved: u32,
-    pub low: u32,
-    pub medium: u32,
-    pub high: u32,
-    pub critical: u32,
-    pub closed: u32,
-    pub forgevanilla: u32,
-    pub total: u32,
-    pub legacy: u32,
+/// Adds a label to a bug report. Returns `false` if the bug already had that label.
+pub async fn add_label(
+    ctx: &Context,
+    bug_id: u64,
+    label: &str,
+    user_id: UserId,
+) -> CommandResult<bool> {
+    let mut conn = get_database_conn!(ctx);
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+
+    tx.exec_drop(
+        formatcp!(
+            "INSERT IGNORE INTO {} (bug_id, label) VALUES (:bug_id, :label)",
+            TABLE_BUG_REPORTS_LABELS
+        ),
+        params! {
+            "bug_id" => bug_id,
+            "label" => label
+        },
+    )
+    .await?;
+
+    if tx.affected_rows() == 0 {
+        return Ok(false);
+    }
+
+    record_change(
+        &mut tx,
+        bug_id,
+        user_id,
+        BugChange::LabelAdded,
+        None,
+        Some(label),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(true)
 }
 
-pub async fn get_bug_statistics(ctx: &Context) -> Option<BugCounts> {
+/// Removes a label from a bug report. Returns `false` if the bug did not have that label.
+pub async fn remove_label(
+    ctx: &Context,
+    bug_id: u64,
+    label: &str,
+    user_id: UserId,
+) -> CommandResult<bool> {
     let mut conn = get_database_conn!(ctx);
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
 
-    let statuses = [
-        "resolved",
-        "low",
-        "medium",
-        "high",
-        "critical",
-        "closed",
-        "forgevanilla",
-    ];
+    tx.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE bug_id = :bug_id AND label = :label",
+            TABLE_BUG_REPORTS_LABELS
+        ),
+        params! {
+            "bug_id" => bug_id,
+            "label" => label
+        },
+    )
+    .await?;
 
-    let mut counts = [0; 9];
+    if tx.affected_rows() == 0 {
+        return Ok(false);
+    }
 
-    for (i, s) in statuses.iter().enumerate() {
-        let x = conn
-            .exec_first(
-                formatcp!(
-                    "SELECT COUNT(bug_id) FROM {} WHERE status = :status",
-                    TABLE_BUG_REPORTS
-                ),
-                params! {
-                    "status" => s
-                },
-            )
-            .await
-            .ok()??;
-        counts[i] = x;
+    record_change(
+        &mut tx,
+        bug_id,
+        user_id,
+        BugChange::LabelRemoved,
+        Some(label),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(true)
+}
+
+/// Gets the assignees of several bugs, along with the bugs they are assigned to
+pub async fn get_assignees_of(ctx: &Context, bug_ids: &[u64]) -> Option<HashMap<UserId, Vec<u64>>> {
+    if bug_ids.is_empty() {
+        return Some(HashMap::new());
     }
 
-    counts[7] = counts.iter().sum();
+    let mut conn = get_database_conn!(ctx);
 
-    counts[8] = conn
-        .query_first(formatcp!(
-            "SELECT COUNT(bug_id) FROM {} WHERE category = 'legacy'",
-            TABLE_BUG_REPORTS
+    let ids = bug_ids
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut res: HashMap<UserId, Vec<u64>> = HashMap::new();
+    for (user_id, bug_id) in conn
+        .query::<(u64, u64), _>(format!(
+            "SELECT user_id, bug_id FROM {TABLE_BUG_REPORTS_ASSIGNEES} \
+WHERE bug_id IN ({ids}) ORDER BY bug_id ASC"
         ))
         .await
-        .ok()??;
+        .ok()?
+    {
+        res.entry(UserId(user_id)).or_default().push(bug_id);
+    }
 
-    Some(BugCounts {
-        resolved: counts[0],
-        low: counts[1],
-        medium: counts[2],
-        high: counts[3],
-        critical: counts[4],
-        closed: counts[5],
-        forgevanilla: counts[6],
-        total: counts[7],
-        legacy: counts[8],
-    })
+    Some(res)
 }
 
-pub async fn change_category(
+async fn count_votes(conn: &mut impl Queryable, bug_id: u64) -> Result<u32, mysql_async::Error> {
+    Ok(conn
+        .exec_first(
+            formatcp!(
+                "SELECT COUNT(*) FROM {} WHERE bug_id = :bug_id",
+                TABLE_BUG_REPORTS_VOTES
+            ),
+            params! {
+                "bug_id" => bug_id
+            },
+        )
+        .await?
+        .unwrap_or_default())
+}
+
+/// Adds the "affects me" vote of a user to a bug, or removes it if they already voted.
+/// Returns whether the user now votes for the bug, and the new vote count.
+pub async fn toggle_vote(
     ctx: &Context,
     bug_id: u64,
-    category: BugCategory,
-) -> Option<BugCategory> {
+    user_id: UserId,
+) -> Result<(bool, u32), CommandError> {
     let mut conn = get_database_conn!(ctx);
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
 
-    let old_category = conn
-        .exec_first::<String, _, _>(
+    // lock the bug, so that concurrent clicks are applied one after the other
+    tx.exec_drop(
+        formatcp!(
+            "SELECT bug_id FROM {} WHERE bug_id = :bug_id FOR UPDATE",
+            TABLE_BUG_REPORTS
+        ),
+        params! {
+            "bug_id" => bug_id
+        },
+    )
+    .await?;
+
+    let had_voted: bool = tx
+        .exec_first(
             formatcp!(
-                "SELECT category FROM {} WHERE bug_id = :bug_id",
-                TABLE_BUG_REPORTS
+                "SELECT EXISTS(SELECT user_id FROM {} WHERE bug_id = :bug_id AND user_id = :user_id)",
+                TABLE_BUG_REPORTS_VOTES
             ),
             params! {
                 "bug_id" => bug_id,
+                "user_id" => user_id.0,
             },
         )
-        .await
-        .ok()??
-        .parse()
-        .expect("Expected a valid bug category from the database");
+        .await?
+        .unwrap_or_default();
+    let voted = !had_voted;
 
-    conn.exec_drop(
-        formatcp!(
-            "UPDATE {} SET category = :category WHERE bug_id = :bug_id",
-            TABLE_BUG_REPORTS
-        ),
+    tx.exec_drop(
+        if voted {
+            formatcp!(
+                "INSERT INTO {} (bug_id, user_id) VALUES (:bug_id, :user_id)",
+                TABLE_BUG_REPORTS_VOTES
+            )
+        } else {
+            formatcp!(
+                "DELETE FROM {} WHERE bug_id = :bug_id AND user_id = :user_id",
+                TABLE_BUG_REPORTS_VOTES
+            )
+        },
         params! {
-            "category" => category.as_str(),
-            "bug_id" => bug_id
+            "bug_id" => bug_id,
+            "user_id" => user_id.0,
         },
     )
-    .await
-    .ok()?;
+    .await?;
 
-    Some(old_category)
+    let votes = count_votes(&mut tx, bug_id).await?;
+    tx.commit().await?;
+
+    Ok((voted, votes))
 }
 
 pub async fn is_notified_user(ctx: &Context, bug_id: u64, user_id: UserId) -> Option<bool> {
@@ -647,19 +2402,31 @@ pub async fn get_notifications_for_user(
         )
         .await?
     } else {
-        conn.exec(
-            formatcp!(
-                "SELECT t1.bug_id FROM {TABLE_BUG_REPORTS_NOTIFICATIONS} AS t1 \
+        let subscriptions: Vec<(u64, u64, String)> = conn
+            .exec(
+                formatcp!(
+                    "SELECT t1.bug_id, t2.guild_id, t2.status FROM {TABLE_BUG_REPORTS_NOTIFICATIONS} AS t1 \
 JOIN {TABLE_BUG_REPORTS} AS t2 \
 ON t1.bug_id = t2.bug_id \
-AND t2.status != 'closed' \
-AND t2.status != 'resolved' \
-AND t2.status != 'forgevanilla' \
 WHERE t1.user_id = :user_id"
-            ),
-            params! {"user_id" => user_id.0},
-        )
-        .await?
+                ),
+                params! {"user_id" => user_id.0},
+            )
+            .await?;
+
+        // which statuses are open depends on the bugtracker of each bug
+        let mut workflows: HashMap<u64, StatusWorkflow> = HashMap::new();
+        let mut open = Vec::with_capacity(subscriptions.len());
+        for (bug_id, guild_id, status) in subscriptions {
+            if !workflows.contains_key(&guild_id) {
+                let workflow = load_status_workflow(&mut conn, GuildId(guild_id)).await?;
+                workflows.insert(guild_id, workflow);
+            }
+            if workflows[&guild_id].resolve(&status).is_open {
+                open.push(bug_id);
+            }
+        }
+        open
     })
 }
 
@@ -686,7 +2453,8 @@ pub async fn add_notified_user(ctx: &Context, bug_id: u64, user_id: UserId) -> C
     Ok(conn
         .exec_drop(
             formatcp!(
-                "INSERT INTO {} (bug_id, user_id) VALUES (:bug_id, :user_id)",
+                "INSERT INTO {} (bug_id, user_id, timestamp) \
+VALUES (:bug_id, :user_id, UTC_TIMESTAMP())",
                 TABLE_BUG_REPORTS_NOTIFICATIONS
             ),
             params! {
@@ -713,3 +2481,536 @@ pub async fn remove_notified_user(ctx: &Context, bug_id: u64, user_id: UserId) -
         )
         .await?)
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum NotificationMode {
+    /// One DM per notification
+    #[default]
+    Immediate,
+    /// Notifications are batched in a single DM every day
+    Daily,
+    Muted,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseModeError;
+
+impl std::str::FromStr for NotificationMode {
+    type Err = ParseModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use NotificationMode::*;
+
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "immediate" | "instant" => Immediate,
+            "daily" | "digest" => Daily,
+            "muted" | "mute" | "off" => Muted,
+            _ => return Err(ParseModeError),
+        })
+    }
+}
+
+impl std::fmt::Display for NotificationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl NotificationMode {
+    pub const fn as_str(self) -> &'static str {
+        use NotificationMode::*;
+        match self {
+            Immediate => "immediate",
+            Daily => "daily",
+            Muted => "muted",
+        }
+    }
+}
+
+pub async fn get_notification_mode(ctx: &Context, user_id: UserId) -> NotificationMode {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_first::<String, _, _>(
+        formatcp!(
+            "SELECT mode FROM {} WHERE user_id = :user_id",
+            TABLE_BUG_REPORTS_NOTIFICATION_SETTINGS
+        ),
+        params! {
+            "user_id" => user_id.0
+        },
+    )
+    .await
+    .ok()
+    .flatten()
+    .and_then(|mode| mode.parse().ok())
+    .unwrap_or_default()
+}
+
+/// Gets the notification mode of several users. Users without settings are omitted.
+pub async fn get_notification_modes(
+    ctx: &Context,
+    users: &[UserId],
+) -> HashMap<UserId, NotificationMode> {
+    if users.is_empty() {
+        return HashMap::new();
+    }
+
+    let mut conn = get_database_conn!(ctx);
+
+    let ids = users
+        .iter()
+        .map(|u| u.0.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    conn.query_map(
+        format!(
+            "SELECT user_id, mode FROM {TABLE_BUG_REPORTS_NOTIFICATION_SETTINGS} \
+WHERE user_id IN ({ids})"
+        ),
+        |(user_id, mode): (u64, String)| (UserId(user_id), mode.parse().unwrap_or_default()),
+    )
+    .await
+    .map(|v| v.into_iter().collect())
+    .unwrap_or_default()
+}
+
+pub async fn set_notification_mode(
+    ctx: &Context,
+    user_id: UserId,
+    mode: NotificationMode,
+) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (user_id, mode) VALUES (:user_id, :mode) \
+ON DUPLICATE KEY UPDATE mode = :mode",
+            TABLE_BUG_REPORTS_NOTIFICATION_SETTINGS
+        ),
+        params! {
+            "user_id" => user_id.0,
+            "mode" => mode.as_str(),
+        },
+    )
+    .await?;
+
+    if mode == NotificationMode::Muted {
+        conn.exec_drop(
+            formatcp!(
+                "DELETE FROM {} WHERE user_id = :user_id",
+                TABLE_BUG_REPORTS_PENDING_NOTIFICATIONS
+            ),
+            params! {
+                "user_id" => user_id.0
+            },
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Queues a notification for the next daily digest of a user
+pub async fn add_pending_notification(
+    ctx: &Context,
+    user_id: UserId,
+    bug_id: u64,
+    message: &str,
+) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (user_id, bug_id, message) VALUES (:user_id, :bug_id, :message)",
+            TABLE_BUG_REPORTS_PENDING_NOTIFICATIONS
+        ),
+        params! {
+            "user_id" => user_id.0,
+            "bug_id" => bug_id,
+            "message" => message,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingNotification {
+    pub pending_id: u64,
+    pub bug_id: u64,
+    pub prefix: String,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Checks whether the pending notifications of a user should be sent now: once
+/// a day for daily digests, or right away for users who switched back to
+/// immediate notifications. After a digest could not reach the user, the next
+/// attempt waits a day, so that a single digest counts as one failed delivery.
+fn is_digest_due(
+    mode: NotificationMode,
+    last_digest: Option<NaiveDateTime>,
+    last_failed_digest: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> bool {
+    let day_ago = now - chrono::Duration::days(1);
+    if last_failed_digest.map_or(false, |failed| failed >= day_ago) {
+        return false;
+    }
+    mode != NotificationMode::Daily || last_digest.map_or(true, |last| last < day_ago)
+}
+
+/// Gets the pending notifications of the users whose digest is due
+pub async fn get_due_pending_notifications(
+    conn: &mut Conn,
+) -> Result<HashMap<UserId, Vec<PendingNotification>>, mysql_async::Error> {
+    let now = Utc::now().naive_utc();
+    let mut res: HashMap<UserId, Vec<PendingNotification>> = HashMap::new();
+
+    for (
+        user_id,
+        pending_id,
+        bug_id,
+        prefix,
+        message,
+        timestamp,
+        mode,
+        last_digest,
+        last_failed_digest,
+    ) in conn
+        .query::<(
+            u64,
+            u64,
+            u64,
+            String,
+            String,
+            NaiveDateTime,
+            Option<String>,
+            Option<NaiveDateTime>,
+            Option<NaiveDateTime>,
+        ), _>(formatcp!(
+            "SELECT pending.user_id, pending.pending_id, pending.bug_id, {}, pending.message, \
+pending.timestamp, settings.mode, settings.last_digest, settings.last_failed_digest \
+FROM {} AS pending \
+LEFT JOIN {} AS settings ON pending.user_id = settings.user_id \
+LEFT JOIN {} AS bugs ON pending.bug_id = bugs.bug_id \
+LEFT JOIN {} ON bugs.guild_id = server_id \
+ORDER BY pending.pending_id ASC",
+            PREFIX_SQL,
+            TABLE_BUG_REPORTS_PENDING_NOTIFICATIONS,
+            TABLE_BUG_REPORTS_NOTIFICATION_SETTINGS,
+            TABLE_BUG_REPORTS,
+            TABLE_BUG_TRACKERS
+        ))
+        .await?
+    {
+        let mode = mode.and_then(|mode| mode.parse().ok()).unwrap_or_default();
+        if !is_digest_due(mode, last_digest, last_failed_digest, now) {
+            continue;
+        }
+        res.entry(UserId(user_id))
+            .or_default()
+            .push(PendingNotification {
+                pending_id,
+                bug_id,
+                prefix,
+                message,
+                timestamp: Utc.from_utc_datetime(&timestamp),
+            });
+    }
+
+    Ok(res)
+}
+
+/// Removes the notifications sent in a digest, and records the digest time
+pub async fn clear_pending_notifications(
+    conn: &mut Conn,
+    user_id: UserId,
+    up_to: u64,
+) -> Result<(), mysql_async::Error> {
+    conn.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE user_id = :user_id AND pending_id <= :up_to",
+            TABLE_BUG_REPORTS_PENDING_NOTIFICATIONS
+        ),
+        params! {
+            "user_id" => user_id.0,
+            "up_to" => up_to,
+        },
+    )
+    .await?;
+
+    conn.exec_drop(
+        formatcp!(
+            "UPDATE {} SET last_digest = UTC_TIMESTAMP(), last_failed_digest = NULL \
+WHERE user_id = :user_id",
+            TABLE_BUG_REPORTS_NOTIFICATION_SETTINGS
+        ),
+        params! {
+            "user_id" => user_id.0
+        },
+    )
+    .await
+}
+
+/// Records that a digest could not reach a user, to wait a day before the next attempt
+pub async fn set_failed_digest(conn: &mut Conn, user_id: UserId) -> Result<(), mysql_async::Error> {
+    conn.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (user_id, last_failed_digest) VALUES (:user_id, UTC_TIMESTAMP()) \
+ON DUPLICATE KEY UPDATE last_failed_digest = UTC_TIMESTAMP()",
+            TABLE_BUG_REPORTS_NOTIFICATION_SETTINGS
+        ),
+        params! {
+            "user_id" => user_id.0
+        },
+    )
+    .await
+}
+
+/// Outcome of a notification DM
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Delivery {
+    Delivered,
+    /// The user cannot be reached: they do not accept DMs from the bot, or
+    /// their account is gone
+    Unreachable(String),
+    /// A failure that may not happen again, such as a network error or an
+    /// outage. It does not count towards the failures of the user.
+    Failed,
+}
+
+impl Delivery {
+    pub fn from_result<T>(result: &Result<T, SerenityError>) -> Self {
+        const CANNOT_SEND_MESSAGES_TO_USER: isize = 50007;
+        const UNKNOWN_CHANNEL: isize = 10003;
+        const UNKNOWN_USER: isize = 10013;
+
+        match result {
+            Ok(_) => Self::Delivered,
+            Err(SerenityError::Http(http_error)) => match http_error.as_ref() {
+                HttpError::UnsuccessfulRequest(ErrorResponse { error, .. })
+                    if matches!(
+                        error.code,
+                        CANNOT_SEND_MESSAGES_TO_USER | UNKNOWN_CHANNEL | UNKNOWN_USER
+                    ) =>
+                {
+                    Self::Unreachable(format!("{} (error code {})", error.message, error.code))
+                }
+                _ => Self::Failed,
+            },
+            Err(_) => Self::Failed,
+        }
+    }
+}
+
+/// Records the outcome of a notification DM to a user.
+///
+/// A successful delivery resets the user's failure count. After
+/// [`MAX_DELIVERY_FAILURES`] consecutive failures where the user could not be
+/// reached, the user is unsubscribed from all their bugs, and the function
+/// returns `true`.
+pub async fn record_delivery(
+    ctx: &Context,
+    user_id: UserId,
+    delivery: &Delivery,
+) -> CommandResult<bool> {
+    let mut conn = get_database_conn!(ctx);
+
+    Ok(track_delivery(&mut conn, user_id, delivery).await?)
+}
+
+/// See [`record_delivery`]
+pub async fn track_delivery(
+    conn: &mut Conn,
+    user_id: UserId,
+    delivery: &Delivery,
+) -> Result<bool, mysql_async::Error> {
+    let error = match delivery {
+        Delivery::Unreachable(error) => error,
+        Delivery::Failed => return Ok(false),
+        Delivery::Delivered => {
+            conn.exec_drop(
+                formatcp!(
+                    "DELETE FROM {} WHERE user_id = :user_id AND unsubscribed IS NULL",
+                    TABLE_BUG_REPORTS_DELIVERY_FAILURES
+                ),
+                params! {
+                    "user_id" => user_id.0
+                },
+            )
+            .await?;
+            return Ok(false);
+        }
+    };
+
+    let error = error.chars().take(255).collect::<String>();
+    conn.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (user_id, failures, last_error, last_failure, unsubscribed) \
+VALUES (:user_id, 1, :error, UTC_TIMESTAMP(), NULL) ON DUPLICATE KEY UPDATE \
+failures = IF(unsubscribed IS NULL, failures + 1, 1), last_error = :error, \
+last_failure = UTC_TIMESTAMP(), unsubscribed = NULL",
+            TABLE_BUG_REPORTS_DELIVERY_FAILURES
+        ),
+        params! {
+            "user_id" => user_id.0,
+            "error" => &error,
+        },
+    )
+    .await?;
+
+    let failures: u32 = conn
+        .exec_first(
+            formatcp!(
+                "SELECT failures FROM {} WHERE user_id = :user_id",
+                TABLE_BUG_REPORTS_DELIVERY_FAILURES
+            ),
+            params! {
+                "user_id" => user_id.0
+            },
+        )
+        .await?
+        .unwrap_or_default();
+
+    if failures < MAX_DELIVERY_FAILURES {
+        return Ok(false);
+    }
+
+    conn.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE user_id = :user_id",
+            TABLE_BUG_REPORTS_NOTIFICATIONS
+        ),
+        params! {
+            "user_id" => user_id.0
+        },
+    )
+    .await?;
+    conn.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE user_id = :user_id",
+            TABLE_BUG_REPORTS_PENDING_NOTIFICATIONS
+        ),
+        params! {
+            "user_id" => user_id.0
+        },
+    )
+    .await?;
+    conn.exec_drop(
+        formatcp!(
+            "UPDATE {} SET unsubscribed = UTC_TIMESTAMP() WHERE user_id = :user_id",
+            TABLE_BUG_REPORTS_DELIVERY_FAILURES
+        ),
+        params! {
+            "user_id" => user_id.0
+        },
+    )
+    .await?;
+
+    Ok(true)
+}
+
+#[derive(Debug, Clone)]
+pub struct DeliveryFailure {
+    pub user_id: UserId,
+    /// Consecutive failed deliveries
+    pub failures: u32,
+    pub last_error: String,
+    pub last_failure: DateTime<Utc>,
+    /// When the user was automatically unsubscribed, if they were
+    pub unsubscribed: Option<DateTime<Utc>>,
+    /// Number of bugs the user is still subscribed to
+    pub subscriptions: u32,
+}
+
+pub async fn get_delivery_failures(ctx: &Context) -> Option<Vec<DeliveryFailure>> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.query_map(
+        formatcp!(
+            "SELECT deliveries.user_id, failures, last_error, last_failure, unsubscribed, \
+(SELECT COUNT(*) FROM {} AS notifications WHERE notifications.user_id = deliveries.user_id) \
+FROM {} AS deliveries ORDER BY unsubscribed IS NULL DESC, failures DESC, last_failure DESC",
+            TABLE_BUG_REPORTS_NOTIFICATIONS,
+            TABLE_BUG_REPORTS_DELIVERY_FAILURES
+        ),
+        |(user_id, failures, last_error, last_failure, unsubscribed, subscriptions): (
+            u64,
+            u32,
+            String,
+            NaiveDateTime,
+            Option<NaiveDateTime>,
+            u32,
+        )| DeliveryFailure {
+            user_id: UserId(user_id),
+            failures,
+            last_error,
+            last_failure: Utc.from_utc_datetime(&last_failure),
+            unsubscribed: unsubscribed.map(|t| Utc.from_utc_datetime(&t)),
+            subscriptions,
+        },
+    )
+    .await
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_digest_due() {
+        let now: NaiveDateTime = "2024-05-15T12:00:00".parse().unwrap();
+        let hours_ago = |hours| Some(now - chrono::Duration::hours(hours));
+
+        assert!(is_digest_due(NotificationMode::Daily, None, None, now));
+        assert!(is_digest_due(
+            NotificationMode::Daily,
+            hours_ago(25),
+            None,
+            now
+        ));
+        assert!(!is_digest_due(
+            NotificationMode::Daily,
+            hours_ago(23),
+            None,
+            now
+        ));
+        // switching back to immediate notifications sends the rest right away
+        assert!(is_digest_due(
+            NotificationMode::Immediate,
+            hours_ago(1),
+            None,
+            now
+        ));
+
+        // an unreachable user is not retried, nor counted as failing, until a day later
+        assert!(!is_digest_due(
+            NotificationMode::Daily,
+            hours_ago(48),
+            hours_ago(0),
+            now
+        ));
+        assert!(!is_digest_due(
+            NotificationMode::Daily,
+            hours_ago(48),
+            hours_ago(23),
+            now
+        ));
+        assert!(!is_digest_due(
+            NotificationMode::Immediate,
+            None,
+            hours_ago(1),
+            now
+        ));
+        assert!(is_digest_due(
+            NotificationMode::Daily,
+            hours_ago(48),
+            hours_ago(25),
+            now
+        ));
+    }
+}
@@ -0,0 +1,272 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+use const_format::formatcp;
+use mysql_async::prelude::*;
+use serenity::client::Context;
+use serenity::model::prelude::*;
+use std::collections::HashMap;
+
+use crate::constants::{TABLE_BUG_REPORTS, TABLE_BUG_REPORTS_HISTORY, TABLE_BUG_TRACKERS};
+use crate::database::bug_reports::{BugCategory, PartialBugReport};
+use crate::database::bug_statuses::{load_status_workflow, BugStatus};
+use crate::database::bug_trackers::PREFIX_SQL;
+use crate::get_database_conn;
+
+/// Number of oldest open bugs listed in the statistics
+const OLDEST_OPEN_BUGS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekCounts {
+    /// Monday of the week
+    pub start: NaiveDate,
+    pub opened: u32,
+    pub resolved: u32,
+}
+
+/// Mean time between the creation of bugs and their last resolution
+#[derive(Debug, Clone, Copy)]
+pub struct ResolutionTime {
+    total: Duration,
+    pub count: u32,
+}
+
+impl Default for ResolutionTime {
+    fn default() -> Self {
+        Self {
+            total: Duration::zero(),
+            count: 0,
+        }
+    }
+}
+
+impl ResolutionTime {
+    fn add(&mut self, time: Duration) {
+        self.total = self.total + time;
+        self.count += 1;
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count != 0).then(|| self.total / self.count as i32)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BugTimeStatistics {
+    /// Oldest week first, ending with the current week
+    pub weeks: Vec<WeekCounts>,
+    pub by_category: Vec<(BugCategory, ResolutionTime)>,
+    /// Resolution times by current closed status
+    pub by_status: Vec<(BugStatus, ResolutionTime)>,
+    pub oldest_open: Vec<PartialBugReport>,
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Counts bug openings and resolutions in each of the `weeks` weeks up to `today`
+fn bucket_weeks(
+    today: NaiveDate,
+    weeks: u32,
+    opened: impl IntoIterator<Item = NaiveDate>,
+    resolved: impl IntoIterator<Item = NaiveDate>,
+) -> Vec<WeekCounts> {
+    let first = week_start(today) - Duration::weeks(weeks.saturating_sub(1) as i64);
+    let mut buckets = (0..weeks)
+        .map(|i| WeekCounts {
+            start: first + Duration::weeks(i as i64),
+            opened: 0,
+            resolved: 0,
+        })
+        .collect::<Vec<_>>();
+
+    let index = |date: NaiveDate| {
+        let days = date.signed_duration_since(first).num_days();
+        (days >= 0).then_some(days as usize / 7)
+    };
+    for date in opened {
+        if let Some(week) = index(date).and_then(|i| buckets.get_mut(i)) {
+            week.opened += 1;
+        }
+    }
+    for date in resolved {
+        if let Some(week) = index(date).and_then(|i| buckets.get_mut(i)) {
+            week.resolved += 1;
+        }
+    }
+
+    buckets
+}
+
+/// Computes the weekly activity over the last `weeks` weeks, the mean time to resolution
+/// and the oldest open bugs of a guild
+pub async fn get_time_statistics(
+    ctx: &Context,
+    server_id: GuildId,
+    weeks: u32,
+) -> Option<BugTimeStatistics> {
+    let mut conn = get_database_conn!(ctx);
+    let workflow = load_status_workflow(&mut conn, server_id).await.ok()?;
+
+    let bugs: Vec<(u64, String, String, String, NaiveDateTime, String)> = conn
+        .exec(
+            formatcp!(
+                "SELECT bug_id, {}, title, status, timestamp, category FROM {} \
+LEFT JOIN {} ON guild_id = server_id WHERE guild_id = :guild_id ORDER BY timestamp ASC",
+                PREFIX_SQL,
+                TABLE_BUG_REPORTS,
+                TABLE_BUG_TRACKERS
+            ),
+            params! {
+                "guild_id" => server_id.0
+            },
+        )
+        .await
+        .ok()?;
+
+    let changes: Vec<(u64, NaiveDateTime, Option<String>, Option<String>)> = conn
+        .exec(
+            formatcp!(
+                "SELECT history.bug_id, history.timestamp, history.old_value, history.new_value \
+FROM {} AS history JOIN {} AS bugs ON history.bug_id = bugs.bug_id \
+WHERE bugs.guild_id = :guild_id AND history.change_type = 'status' \
+ORDER BY history.timestamp ASC",
+                TABLE_BUG_REPORTS_HISTORY,
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "guild_id" => server_id.0
+            },
+        )
+        .await
+        .ok()?;
+
+    let is_open = |status: &Option<String>| {
+        status
+            .as_deref()
+            .map_or(true, |status| workflow.resolve(status).is_open)
+    };
+
+    // a bug is resolved when its status goes from an open status to a closed one
+    let mut resolved_at: HashMap<u64, NaiveDateTime> = HashMap::new();
+    let mut resolutions = Vec::new();
+    for (bug_id, timestamp, old_value, new_value) in &changes {
+        if is_open(old_value) && !is_open(new_value) {
+            resolved_at.insert(*bug_id, *timestamp);
+            resolutions.push(timestamp.date());
+        }
+    }
+
+    let mut by_category: Vec<(BugCategory, ResolutionTime)> =
+        [BugCategory::Renewed, BugCategory::Legacy]
+            .into_iter()
+            .map(|category| (category, ResolutionTime::default()))
+            .collect();
+    let mut by_status: Vec<(BugStatus, ResolutionTime)> = workflow
+        .statuses
+        .iter()
+        .filter(|status| !status.is_open)
+        .map(|status| (status.clone(), ResolutionTime::default()))
+        .collect();
+    let mut oldest_open = Vec::new();
+
+    for (bug_id, prefix, title, status, timestamp, category) in &bugs {
+        let status = workflow.resolve(status);
+        let category = category.parse().unwrap_or_default();
+        if status.is_open {
+            if oldest_open.len() < OLDEST_OPEN_BUGS {
+                oldest_open.extend(PartialBugReport::new(
+                    *bug_id,
+                    prefix.clone(),
+                    title.clone(),
+                    status,
+                    *timestamp,
+                    category,
+                ));
+            }
+        } else if let Some(resolved) = resolved_at.get(bug_id) {
+            // bugs closed before the history was recorded have no resolution time
+            let time = resolved.signed_duration_since(*timestamp);
+            if let Some((_, times)) = by_category.iter_mut().find(|(c, _)| *c == category) {
+                times.add(time);
+            }
+            if let Some((_, times)) = by_status.iter_mut().find(|(s, _)| *s == status) {
+                times.add(time);
+            }
+        }
+    }
+
+    let weeks = bucket_weeks(
+        Utc::now().date_naive(),
+        weeks,
+        bugs.iter().map(|bug| bug.4.date()),
+        resolutions,
+    );
+
+    Some(BugTimeStatistics {
+        weeks,
+        by_category,
+        by_status,
+        oldest_open,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dates(dates: &[&str]) -> Vec<NaiveDate> {
+        dates.iter().map(|date| date.parse().unwrap()).collect()
+    }
+
+    /// Weeks as `("yyyy-mm-dd", opened, resolved)` tuples
+    fn format_weeks(weeks: &[WeekCounts]) -> Vec<(String, u32, u32)> {
+        weeks
+            .iter()
+            .map(|week| (week.start.to_string(), week.opened, week.resolved))
+            .collect()
+    }
+
+    #[test]
+    fn test_bucket_weeks_boundaries() {
+        // a sunday, the last day of the week starting on 2024-05-13
+        let today = "2024-05-19".parse().unwrap();
+        let weeks = bucket_weeks(
+            today,
+            3,
+            // the sunday before the first week, the monday starting it,
+            // the sunday ending it, today and a later date
+            dates(&[
+                "2024-04-28",
+                "2024-04-29",
+                "2024-05-05",
+                "2024-05-19",
+                "2024-05-20",
+            ]),
+            dates(&["2024-05-06", "2024-05-12", "2024-05-13"]),
+        );
+
+        assert_eq!(
+            format_weeks(&weeks),
+            vec![
+                ("2024-04-29".into(), 2, 0),
+                ("2024-05-06".into(), 0, 2),
+                ("2024-05-13".into(), 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bucket_weeks_single_week() {
+        // a monday: only that day belongs to the current week so far
+        let today = "2024-01-01".parse().unwrap();
+        let weeks = bucket_weeks(
+            today,
+            1,
+            dates(&["2023-12-31", "2024-01-01"]),
+            dates(&["2023-12-25"]),
+        );
+
+        assert_eq!(format_weeks(&weeks), vec![("2024-01-01".into(), 1, 0)]);
+        assert!(bucket_weeks(today, 0, dates(&["2024-01-01"]), []).is_empty());
+    }
+}
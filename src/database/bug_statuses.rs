@@ -0,0 +1,420 @@
+//! Status workflows of the bugtrackers.
+//!
+//! Each bugtracker has its own ordered list of statuses, from the lowest to the
+//! highest priority. Bugtrackers that never changed theirs use [`StatusWorkflow::default`].
+
+use const_format::formatcp;
+use mysql_async::prelude::*;
+use mysql_async::{Conn, TxOpts};
+use serenity::client::Context;
+use serenity::framework::standard::{CommandError, CommandResult};
+use serenity::model::prelude::*;
+use serenity::utils::Colour;
+
+use crate::constants::{TABLE_BUG_REPORTS, TABLE_BUG_REPORTS_STATUSES};
+use crate::get_database_conn;
+
+/// Status set by `!resolve`. Every workflow has it, and it is never open.
+pub const RESOLVED: &str = "resolved";
+/// Status set by `!bug close` and `!bug duplicate`. Every workflow has it, and it is never open.
+pub const CLOSED: &str = "closed";
+/// Status of new bug reports, if the workflow has it
+pub const DEFAULT_STATUS: &str = "medium";
+
+#[derive(Debug, Clone)]
+pub struct BugStatus {
+    /// Lowercase name of the status, such as `needs-info`
+    pub name: String,
+    pub colour: Colour,
+    /// Emoji displayed in front of the bugs with this status
+    pub marker: String,
+    /// Reaction added to the messages of the bugs with this status
+    pub reaction: String,
+    /// Whether bugs with this status still need work
+    pub is_open: bool,
+}
+
+impl PartialEq for BugStatus {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for BugStatus {}
+
+impl std::fmt::Display for BugStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut chars = self.name.chars();
+        if let Some(first) = chars.next() {
+            write!(f, "{}", first.to_ascii_uppercase())?;
+        }
+        write!(f, "{}", chars.as_str().replace('-', " "))
+    }
+}
+
+impl BugStatus {
+    fn new(name: &str, colour: u32, marker: &str, reaction: &str, is_open: bool) -> Self {
+        Self {
+            name: name.into(),
+            colour: Colour(colour),
+            marker: marker.into(),
+            reaction: reaction.into(),
+            is_open,
+        }
+    }
+
+    /// Placeholder for a status that was removed from the workflow of its bugtracker
+    pub fn unknown(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            colour: Colour::LIGHT_GREY,
+            marker: ":white_circle:".into(),
+            reaction: "❔".into(),
+            is_open: true,
+        }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn colour(&self) -> Colour {
+        self.colour
+    }
+
+    #[inline]
+    pub fn marker(&self) -> &str {
+        &self.marker
+    }
+
+    pub fn reaction(&self) -> ReactionType {
+        ReactionType::try_from(self.reaction.as_str())
+            .unwrap_or_else(|_| ReactionType::Unicode(self.reaction.clone()))
+    }
+}
+
+/// Parses a status name, which must be 1 to 32 ascii letters, digits or `-`.
+/// Status names are always lowercase.
+pub fn parse_status_name(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty()
+        || name.len() > 32
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusWorkflow {
+    /// Statuses of the bugtracker, from the lowest to the highest priority
+    pub statuses: Vec<BugStatus>,
+}
+
+impl Default for StatusWorkflow {
+    fn default() -> Self {
+        Self {
+            statuses: vec![
+                BugStatus::new(CLOSED, 0x7694cb, ":blue_circle:", "❌", false),
+                // not a V: the [V] emoji
+                BugStatus::new(
+                    "forgevanilla",
+                    0x9f00c5,
+                    ":regional_indicator_v:",
+                    "🇻",
+                    false,
+                ),
+                BugStatus::new(RESOLVED, 0x2fd524, ":green_circle:", "✅", false),
+                BugStatus::new("low", 0xfef001, ":yellow_circle:", "⚠️", true),
+                BugStatus::new(DEFAULT_STATUS, 0xfd9a01, ":orange_circle:", "⚠️", true),
+                BugStatus::new("high", 0xfd6104, ":red_circle:", "⚠️", true),
+                BugStatus::new("critical", 0xff0000, ":bangbang:", "⚠️", true),
+            ],
+        }
+    }
+}
+
+impl StatusWorkflow {
+    pub fn get(&self, name: &str) -> Option<&BugStatus> {
+        self.statuses
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Parses a status given by a user
+    pub fn parse(&self, name: &str) -> Option<BugStatus> {
+        self.get(name)
+            .or_else(|| {
+                // aliases of the default workflow
+                if name.eq_ignore_ascii_case("forge") || name.eq_ignore_ascii_case("vanilla") {
+                    self.get("forgevanilla")
+                } else {
+                    None
+                }
+            })
+            .cloned()
+    }
+
+    /// Gets a status stored in the database, even if it was removed from the workflow
+    pub fn resolve(&self, name: &str) -> BugStatus {
+        self.get(name)
+            .cloned()
+            .unwrap_or_else(|| BugStatus::unknown(name))
+    }
+
+    /// Status of new bug reports: [`DEFAULT_STATUS`], or else the first open status
+    pub fn default_status(&self) -> BugStatus {
+        self.get(DEFAULT_STATUS)
+            .or_else(|| self.statuses.iter().find(|s| s.is_open))
+            .cloned()
+            .unwrap_or_else(|| BugStatus::unknown(DEFAULT_STATUS))
+    }
+
+    pub fn resolved(&self) -> BugStatus {
+        self.resolve(RESOLVED)
+    }
+
+    pub fn closed(&self) -> BugStatus {
+        self.resolve(CLOSED)
+    }
+
+    /// Comma-separated, quoted names of the open or closed statuses, for SQL `IN` lists.
+    ///
+    /// Status names are checked by [`parse_status_name`], so they are safe to inline.
+    pub fn sql_names(&self, open: bool) -> String {
+        let names = self
+            .statuses
+            .iter()
+            .filter(|s| s.is_open == open)
+            .map(|s| format!("'{}'", s.name))
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            "''".into()
+        } else {
+            names.join(", ")
+        }
+    }
+
+    /// SQL condition selecting the open bugs. Bugs with a removed status are open.
+    pub fn open_condition(&self, column: &str) -> String {
+        format!("{column} NOT IN ({})", self.sql_names(false))
+    }
+
+    /// SQL expression sorting the bugs by the priority of their status
+    pub fn priority_order(&self, column: &str) -> String {
+        let names = self
+            .statuses
+            .iter()
+            .map(|s| format!("'{}'", s.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("FIELD({column}, {names})")
+    }
+}
+
+pub async fn load_status_workflow(
+    conn: &mut Conn,
+    server_id: GuildId,
+) -> Result<StatusWorkflow, mysql_async::Error> {
+    let statuses = conn
+        .exec_map(
+            formatcp!(
+                "SELECT name, colour, marker, reaction, is_open FROM {} \
+WHERE server_id = :server_id ORDER BY position ASC",
+                TABLE_BUG_REPORTS_STATUSES
+            ),
+            params! {
+                "server_id" => server_id.0
+            },
+            |(name, colour, marker, reaction, is_open)| BugStatus {
+                name,
+                colour: Colour(colour),
+                marker,
+                reaction,
+                is_open,
+            },
+        )
+        .await?;
+
+    Ok(if statuses.is_empty() {
+        StatusWorkflow::default()
+    } else {
+        StatusWorkflow { statuses }
+    })
+}
+
+pub async fn get_status_workflow(ctx: &Context, server_id: GuildId) -> Option<StatusWorkflow> {
+    let mut conn = get_database_conn!(ctx);
+
+    load_status_workflow(&mut conn, server_id).await.ok()
+}
+
+/// Stores the default workflow for a guild that never changed its own,
+/// so that it can be edited
+async fn store_default_workflow(
+    conn: &mut impl Queryable,
+    server_id: GuildId,
+) -> Result<(), mysql_async::Error> {
+    let count: u32 = conn
+        .exec_first(
+            formatcp!(
+                "SELECT COUNT(name) FROM {} WHERE server_id = :server_id",
+                TABLE_BUG_REPORTS_STATUSES
+            ),
+            params! {
+                "server_id" => server_id.0
+            },
+        )
+        .await?
+        .unwrap_or_default();
+    if count > 0 {
+        return Ok(());
+    }
+
+    conn.exec_batch(
+        formatcp!(
+            "INSERT INTO {} (server_id, name, colour, marker, reaction, is_open, position) \
+VALUES (:server_id, :name, :colour, :marker, :reaction, :is_open, :position)",
+            TABLE_BUG_REPORTS_STATUSES
+        ),
+        StatusWorkflow::default()
+            .statuses
+            .iter()
+            .enumerate()
+            .map(|(position, status)| {
+                params! {
+                    "server_id" => server_id.0,
+                    "name" => &status.name,
+                    "colour" => status.colour.0,
+                    "marker" => &status.marker,
+                    "reaction" => &status.reaction,
+                    "is_open" => status.is_open,
+                    "position" => position as u32,
+                }
+            }),
+    )
+    .await
+}
+
+/// Adds or changes a status of the workflow of a guild.
+///
+/// New statuses get the highest priority, unless a position is given.
+/// Statuses at or after that position move one step up.
+pub async fn set_bug_status(
+    ctx: &Context,
+    server_id: GuildId,
+    status: &BugStatus,
+    position: Option<u32>,
+) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+
+    store_default_workflow(&mut tx, server_id).await?;
+
+    let position: u32 = if let Some(position) = position {
+        tx.exec_drop(
+            formatcp!(
+                "UPDATE {} SET position = position + 1 \
+WHERE server_id = :server_id AND position >= :position AND name != :name",
+                TABLE_BUG_REPORTS_STATUSES
+            ),
+            params! {
+                "server_id" => server_id.0,
+                "position" => position,
+                "name" => &status.name,
+            },
+        )
+        .await?;
+        position
+    } else {
+        tx.exec_first(
+            formatcp!(
+                "SELECT COALESCE(\
+(SELECT position FROM {0} WHERE server_id = :server_id AND name = :name), \
+(SELECT MAX(position) + 1 FROM {0} WHERE server_id = :server_id))",
+                TABLE_BUG_REPORTS_STATUSES
+            ),
+            params! {
+                "server_id" => server_id.0,
+                "name" => &status.name,
+            },
+        )
+        .await?
+        .unwrap_or_default()
+    };
+
+    tx.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (server_id, name, colour, marker, reaction, is_open, position) \
+VALUES (:server_id, :name, :colour, :marker, :reaction, :is_open, :position) \
+ON DUPLICATE KEY UPDATE colour = VALUES(colour), marker = VALUES(marker), \
+reaction = VALUES(reaction), is_open = VALUES(is_open), position = VALUES(position)",
+            TABLE_BUG_REPORTS_STATUSES
+        ),
+        params! {
+            "server_id" => server_id.0,
+            "name" => &status.name,
+            "colour" => status.colour.0,
+            "marker" => &status.marker,
+            "reaction" => &status.reaction,
+            "is_open" => status.is_open,
+            "position" => position,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Removes a status from the workflow of a guild.
+///
+/// Fails if bugs of that guild still have this status.
+pub async fn remove_bug_status(ctx: &Context, server_id: GuildId, name: &str) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+
+    let used: u32 = tx
+        .exec_first(
+            formatcp!(
+                "SELECT COUNT(bug_id) FROM {} WHERE guild_id = :guild_id AND status = :status",
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "guild_id" => server_id.0,
+                "status" => name,
+            },
+        )
+        .await?
+        .unwrap_or_default();
+    if used > 0 {
+        return Err(CommandError::from(format!(
+            "{used} bug{} still {} this status",
+            if used == 1 { "" } else { "s" },
+            if used == 1 { "has" } else { "have" }
+        )));
+    }
+
+    store_default_workflow(&mut tx, server_id).await?;
+
+    tx.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE server_id = :server_id AND name = :name",
+            TABLE_BUG_REPORTS_STATUSES
+        ),
+        params! {
+            "server_id" => server_id.0,
+            "name" => name,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use const_format::formatcp;
+use mysql_async::prelude::*;
+use serenity::client::Context;
+use serenity::framework::standard::{CommandError, CommandResult};
+use serenity::model::prelude::*;
+
+use crate::constants::TABLE_BUG_REPORTS_SUBMISSIONS;
+use crate::database::bug_reports::BugCategory;
+use crate::get_database_conn;
+
+/// A bug report proposed by a member, waiting for an admin to accept or reject it
+#[derive(Debug, Clone)]
+pub struct BugSubmission {
+    pub submission_id: u64,
+    pub guild_id: GuildId,
+    /// Channel of both the reported message and the proposal message
+    pub channel_id: ChannelId,
+    /// The message describing the bug
+    pub message_id: MessageId,
+    /// The message with the accept and reject buttons
+    pub queue_message_id: Option<MessageId>,
+    pub submitter_id: UserId,
+    pub title: String,
+    pub category: BugCategory,
+    pub timestamp: DateTime<Utc>,
+}
+
+type SubmissionRow = (
+    u64,
+    u64,
+    u64,
+    u64,
+    Option<u64>,
+    u64,
+    String,
+    String,
+    NaiveDateTime,
+);
+
+const SUBMISSION_COLUMNS: &str = "submission_id, guild_id, channel_id, message_id, \
+queue_message_id, submitter_id, title, category, timestamp";
+
+fn submission_from_row(
+    (
+        submission_id,
+        guild_id,
+        channel_id,
+        message_id,
+        queue_message_id,
+        submitter_id,
+        title,
+        category,
+        timestamp,
+    ): SubmissionRow,
+) -> BugSubmission {
+    BugSubmission {
+        submission_id,
+        guild_id: GuildId(guild_id),
+        channel_id: ChannelId(channel_id),
+        message_id: MessageId(message_id),
+        queue_message_id: queue_message_id.map(MessageId),
+        submitter_id: UserId(submitter_id),
+        title,
+        category: category.parse().unwrap_or_default(),
+        timestamp: Utc.from_utc_datetime(&timestamp),
+    }
+}
+
+pub async fn add_submission(
+    ctx: &Context,
+    server_id: GuildId,
+    msg: &Message,
+    submitter_id: UserId,
+    title: &str,
+    category: BugCategory,
+) -> Result<u64, CommandError> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (guild_id, channel_id, message_id, submitter_id, title, category) \
+VALUES (:guild_id, :channel_id, :message_id, :submitter_id, :title, :category)",
+            TABLE_BUG_REPORTS_SUBMISSIONS
+        ),
+        params! {
+            "guild_id" => server_id.0,
+            "channel_id" => msg.channel_id.0,
+            "message_id" => msg.id.0,
+            "submitter_id" => submitter_id.0,
+            "title" => title,
+            "category" => category.as_str(),
+        },
+    )
+    .await?;
+
+    conn.last_insert_id()
+        .ok_or_else(|| CommandError::from("Could not get the submission id!"))
+}
+
+pub async fn set_queue_message(
+    ctx: &Context,
+    submission_id: u64,
+    queue_message_id: MessageId,
+) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "UPDATE {} SET queue_message_id = :queue_message_id \
+WHERE submission_id = :submission_id",
+            TABLE_BUG_REPORTS_SUBMISSIONS
+        ),
+        params! {
+            "queue_message_id" => queue_message_id.0,
+            "submission_id" => submission_id,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the pending submissions of a guild, oldest first
+pub async fn get_submissions(ctx: &Context, server_id: GuildId) -> Option<Vec<BugSubmission>> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_map(
+        formatcp!(
+            "SELECT {} FROM {} WHERE guild_id = :guild_id ORDER BY submission_id ASC",
+            SUBMISSION_COLUMNS,
+            TABLE_BUG_REPORTS_SUBMISSIONS
+        ),
+        params! {
+            "guild_id" => server_id.0
+        },
+        submission_from_row,
+    )
+    .await
+    .ok()
+}
+
+/// Reserves a submission for review, returning it. Returns `Ok(None)` if it was
+/// already handled or is being reviewed, so that two admins cannot both accept
+/// or reject it. A review that did not finish after a few minutes can be retried.
+pub async fn claim_submission(
+    ctx: &Context,
+    submission_id: u64,
+) -> Result<Option<BugSubmission>, CommandError> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "UPDATE {} SET review_started = UTC_TIMESTAMP() \
+WHERE submission_id = :submission_id AND (review_started IS NULL \
+OR review_started < UTC_TIMESTAMP() - INTERVAL 5 MINUTE)",
+            TABLE_BUG_REPORTS_SUBMISSIONS
+        ),
+        params! {
+            "submission_id" => submission_id
+        },
+    )
+    .await?;
+    if conn.affected_rows() == 0 {
+        return Ok(None);
+    }
+
+    Ok(conn
+        .exec_first(
+            formatcp!(
+                "SELECT {} FROM {} WHERE submission_id = :submission_id",
+                SUBMISSION_COLUMNS,
+                TABLE_BUG_REPORTS_SUBMISSIONS
+            ),
+            params! {
+                "submission_id" => submission_id
+            },
+        )
+        .await?
+        .map(submission_from_row))
+}
+
+/// Puts back a submission whose review failed, so that it can be reviewed again
+pub async fn release_submission(ctx: &Context, submission_id: u64) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "UPDATE {} SET review_started = NULL WHERE submission_id = :submission_id",
+            TABLE_BUG_REPORTS_SUBMISSIONS
+        ),
+        params! {
+            "submission_id" => submission_id
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Removes a reviewed submission from the queue
+pub async fn remove_submission(ctx: &Context, submission_id: u64) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE submission_id = :submission_id",
+            TABLE_BUG_REPORTS_SUBMISSIONS
+        ),
+        params! {
+            "submission_id" => submission_id
+        },
+    )
+    .await?;
+
+    Ok(())
+}
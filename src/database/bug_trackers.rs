@@ -0,0 +1,132 @@
+use const_format::formatcp;
+use mysql_async::prelude::*;
+use serenity::client::Context;
+use serenity::framework::standard::CommandResult;
+use serenity::model::prelude::*;
+
+use crate::constants::{DEFAULT_BUG_PREFIX, LOTR_DISCORD, TABLE_BUG_REPORTS, TABLE_BUG_TRACKERS};
+use crate::get_database_conn;
+
+/// Bugtracker settings of a guild
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BugTracker {
+    pub server_id: GuildId,
+    /// Prefix of the bug ids, such as `LOTR` in `LOTR-42`
+    pub prefix: String,
+    pub enabled: bool,
+}
+
+impl BugTracker {
+    /// Settings used for guilds that never configured their bugtracker.
+    ///
+    /// Only the LOTR Mod Community Discord has its bugtracker enabled by default.
+    pub fn default_for(server_id: GuildId) -> Self {
+        Self {
+            server_id,
+            prefix: DEFAULT_BUG_PREFIX.into(),
+            enabled: server_id == LOTR_DISCORD,
+        }
+    }
+}
+
+/// SQL expression giving the prefix of a bug, for queries joining
+/// [`TABLE_BUG_REPORTS`] with [`TABLE_BUG_TRACKERS`] on `guild_id = server_id`
+pub const PREFIX_SQL: &str = formatcp!(
+    "COALESCE({}.prefix, '{}')",
+    TABLE_BUG_TRACKERS,
+    DEFAULT_BUG_PREFIX
+);
+
+/// Parses a bug id prefix, which must be 1 to 16 ascii letters or digits.
+/// Prefixes are always uppercase.
+pub fn parse_prefix(prefix: &str) -> Option<String> {
+    let prefix = prefix.trim().to_uppercase();
+    if prefix.is_empty() || prefix.len() > 16 || !prefix.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        None
+    } else {
+        Some(prefix)
+    }
+}
+
+pub async fn get_bug_tracker(ctx: &Context, server_id: GuildId) -> Option<BugTracker> {
+    let mut conn = get_database_conn!(ctx);
+
+    let tracker = conn
+        .exec_first(
+            formatcp!(
+                "SELECT prefix, enabled FROM {} WHERE server_id = :server_id",
+                TABLE_BUG_TRACKERS
+            ),
+            params! {
+                "server_id" => server_id.0
+            },
+        )
+        .await
+        .ok()?
+        .map(|(prefix, enabled)| BugTracker {
+            server_id,
+            prefix,
+            enabled,
+        });
+
+    Some(tracker.unwrap_or_else(|| BugTracker::default_for(server_id)))
+}
+
+pub async fn set_bug_tracker(ctx: &Context, tracker: &BugTracker) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "REPLACE INTO {} (server_id, prefix, enabled) VALUES (:server_id, :prefix, :enabled)",
+            TABLE_BUG_TRACKERS
+        ),
+        params! {
+            "server_id" => tracker.server_id.0,
+            "prefix" => &tracker.prefix,
+            "enabled" => tracker.enabled,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the guild a bug was tracked in
+pub async fn get_bug_guild(ctx: &Context, bug_id: u64) -> Option<GuildId> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_first(
+        formatcp!(
+            "SELECT guild_id FROM {} WHERE bug_id = :bug_id",
+            TABLE_BUG_REPORTS
+        ),
+        params! {
+            "bug_id" => bug_id
+        },
+    )
+    .await
+    .ok()?
+    .map(GuildId)
+}
+
+/// Gets the id prefix of a bug, from the bugtracker it was tracked in
+pub async fn get_bug_prefix(ctx: &Context, bug_id: u64) -> String {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_first(
+        formatcp!(
+            "SELECT {} FROM {} LEFT JOIN {} ON guild_id = server_id WHERE bug_id = :bug_id",
+            PREFIX_SQL,
+            TABLE_BUG_REPORTS,
+            TABLE_BUG_TRACKERS
+        ),
+        params! {
+            "bug_id" => bug_id
+        },
+    )
+    .await
+    .ok()
+    .flatten()
+    .unwrap_or_else(|| DEFAULT_BUG_PREFIX.into())
+}
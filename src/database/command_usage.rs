@@ -0,0 +1,267 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use const_format::formatcp;
+use mysql_async::prelude::*;
+use mysql_async::{Conn, TxOpts};
+use serenity::client::Context;
+use serenity::model::id::GuildId;
+use std::collections::HashMap;
+
+use crate::constants::TABLE_CUSTOM_COMMANDS_USAGE;
+use crate::get_database_conn;
+
+/// Number of uses of a custom command in a guild on a day
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UsageKey {
+    pub server_id: GuildId,
+    pub name: String,
+    pub day: NaiveDate,
+}
+
+/// Uses of a custom command in the last period, and in the period before
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageSummary {
+    pub name: String,
+    pub recent: u32,
+    pub previous: u32,
+}
+
+/// Adds the given counts to the stored usage counters. Either all of them are
+/// added, or none, so that a failed batch can be retried.
+pub async fn add_usage(
+    conn: &mut Conn,
+    counts: &[(UsageKey, u32)],
+) -> Result<(), mysql_async::Error> {
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+    tx.exec_batch(
+        formatcp!(
+            "INSERT INTO {} (server_id, name, day, uses) VALUES (:server_id, :name, :day, :uses) \
+ON DUPLICATE KEY UPDATE uses = uses + VALUES(uses)",
+            TABLE_CUSTOM_COMMANDS_USAGE
+        ),
+        counts.iter().map(|(key, uses)| {
+            params! {
+                "server_id" => key.server_id.0,
+                "name" => &key.name,
+                "day" => key.day,
+                "uses" => uses,
+            }
+        }),
+    )
+    .await?;
+    tx.commit().await
+}
+
+/// Gets the daily uses of the custom commands of a guild since a day, optionally
+/// for a single command
+pub async fn get_usage(
+    ctx: &Context,
+    server_id: GuildId,
+    since: NaiveDate,
+    name: Option<&str>,
+) -> Option<Vec<(String, NaiveDate, u32)>> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec(
+        format!(
+            "SELECT name, day, uses FROM {} WHERE server_id = :server_id AND day >= :since{} \
+ORDER BY day ASC",
+            TABLE_CUSTOM_COMMANDS_USAGE,
+            if name.is_some() {
+                " AND name = :name"
+            } else {
+                ""
+            }
+        ),
+        params! {
+            "server_id" => server_id.0,
+            "since" => since,
+            "name" => name.unwrap_or_default(),
+        },
+    )
+    .await
+    .ok()
+}
+
+/// Gets the last day a custom command was used
+pub async fn get_last_use(ctx: &Context, server_id: GuildId, name: &str) -> Option<NaiveDate> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_first(
+        formatcp!(
+            "SELECT MAX(day) FROM {} WHERE server_id = :server_id AND name = :name",
+            TABLE_CUSTOM_COMMANDS_USAGE
+        ),
+        params! {
+            "server_id" => server_id.0,
+            "name" => name,
+        },
+    )
+    .await
+    .ok()?
+    .flatten()
+}
+
+/// Sums the uses of each command over the `days` days up to `today`, and over the
+/// `days` days before. The most used commands come first.
+pub fn summarize_usage(
+    rows: &[(String, NaiveDate, u32)],
+    today: NaiveDate,
+    days: i64,
+) -> Vec<UsageSummary> {
+    let recent_start = today - Duration::days(days - 1);
+    let previous_start = recent_start - Duration::days(days);
+
+    let mut summaries: HashMap<&str, UsageSummary> = HashMap::new();
+    for (name, day, uses) in rows {
+        if *day < previous_start || *day > today {
+            continue;
+        }
+        let summary = summaries.entry(name).or_insert_with(|| UsageSummary {
+            name: name.clone(),
+            recent: 0,
+            previous: 0,
+        });
+        if *day >= recent_start {
+            summary.recent += uses;
+        } else {
+            summary.previous += uses;
+        }
+    }
+
+    let mut summaries: Vec<UsageSummary> = summaries.into_values().collect();
+    summaries.sort_by(|a, b| {
+        b.recent
+            .cmp(&a.recent)
+            .then(b.previous.cmp(&a.previous))
+            .then(a.name.cmp(&b.name))
+    });
+    summaries
+}
+
+/// Sums the uses of the `weeks` weeks up to the one of `today`, oldest first.
+/// Weeks start on Monday.
+pub fn weekly_usage(
+    rows: &[(String, NaiveDate, u32)],
+    today: NaiveDate,
+    weeks: i64,
+) -> Vec<(NaiveDate, u32)> {
+    let this_week = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    (0..weeks)
+        .rev()
+        .map(|i| {
+            let start = this_week - Duration::weeks(i);
+            let uses = rows
+                .iter()
+                .filter(|(_, day, _)| *day >= start && *day < start + Duration::weeks(1))
+                .map(|(_, _, uses)| uses)
+                .sum();
+            (start, uses)
+        })
+        .collect()
+}
+
+/// The commands that were not used in the recent period of `summaries`
+pub fn unused_commands<'a>(
+    commands: impl IntoIterator<Item = &'a str>,
+    summaries: &[UsageSummary],
+) -> Vec<&'a str> {
+    commands
+        .into_iter()
+        .filter(|name| {
+            !summaries
+                .iter()
+                .any(|summary| summary.name == *name && summary.recent > 0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Usage rows from `(name, "yyyy-mm-dd", uses)` tuples
+    fn rows(uses: &[(&str, &str, u32)]) -> Vec<(String, NaiveDate, u32)> {
+        uses.iter()
+            .map(|(name, day, uses)| (name.to_string(), day.parse().unwrap(), *uses))
+            .collect()
+    }
+
+    #[test]
+    fn test_summarize_usage_period_boundaries() {
+        let today = "2024-06-30".parse().unwrap();
+        // the recent period is June, the previous one May 2nd to 31st
+        let rows = rows(&[
+            ("faq", "2024-05-01", 100),
+            ("faq", "2024-05-02", 1),
+            ("faq", "2024-05-31", 2),
+            ("faq", "2024-06-01", 4),
+            ("faq", "2024-06-30", 8),
+            ("faq", "2024-07-01", 100),
+            ("wiki", "2024-05-31", 3),
+            ("old", "2024-05-01", 5),
+        ]);
+
+        assert_eq!(
+            summarize_usage(&rows, today, 30),
+            vec![
+                UsageSummary {
+                    name: "faq".into(),
+                    recent: 12,
+                    previous: 3
+                },
+                UsageSummary {
+                    name: "wiki".into(),
+                    recent: 0,
+                    previous: 3
+                },
+            ]
+        );
+    }
+
+    fn format_weeks(weeks: Vec<(NaiveDate, u32)>) -> Vec<(String, u32)> {
+        weeks
+            .into_iter()
+            .map(|(start, uses)| (start.to_string(), uses))
+            .collect()
+    }
+
+    #[test]
+    fn test_weekly_usage() {
+        let rows = rows(&[
+            ("faq", "2024-06-02", 1),
+            ("faq", "2024-06-03", 2),
+            ("wiki", "2024-06-09", 4),
+            ("faq", "2024-06-10", 8),
+        ]);
+
+        // on a Monday, the current week only has that day
+        let today = "2024-06-10".parse().unwrap();
+        assert_eq!(
+            format_weeks(weekly_usage(&rows, today, 3)),
+            [("2024-05-27", 1), ("2024-06-03", 6), ("2024-06-10", 8)]
+        );
+        // on a Sunday, the current week is the whole week before
+        let today = "2024-06-09".parse().unwrap();
+        assert_eq!(
+            format_weeks(weekly_usage(&rows, today, 2)),
+            [("2024-05-27", 1), ("2024-06-03", 6)]
+        );
+    }
+
+    #[test]
+    fn test_unused_commands() {
+        let rows = rows(&[
+            ("faq", "2024-06-20", 3),
+            ("wiki", "2024-05-20", 5),
+            ("deleted", "2024-06-20", 1),
+        ]);
+        let summaries = summarize_usage(&rows, "2024-06-30".parse().unwrap(), 30);
+
+        // only used before the recent period, or never used
+        assert_eq!(
+            unused_commands(["faq", "wiki", "new"], &summaries),
+            vec!["wiki", "new"]
+        );
+        assert!(unused_commands([], &summaries).is_empty());
+    }
+}
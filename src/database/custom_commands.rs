@@ -1,9 +1,12 @@
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use const_format::formatcp;
 use mysql_async::prelude::*;
+use mysql_async::TxOpts;
 use serenity::client::Context;
 use serenity::framework::standard::CommandResult;
-use serenity::model::id::GuildId;
+use serenity::model::id::{GuildId, UserId};
 
-use crate::constants::TABLE_CUSTOM_COMMANDS;
+use crate::constants::{TABLE_CUSTOM_COMMANDS, TABLE_CUSTOM_COMMANDS_HISTORY};
 use crate::get_database_conn;
 
 #[derive(Debug, Clone)]
@@ -13,6 +16,18 @@ pub struct CustomCommand {
     pub description: Option<String>,
 }
 
+/// A saved version of a custom command
+#[derive(Debug, Clone)]
+pub struct CommandRevision {
+    /// Revision number, starting at 1 for each command
+    pub revision: u32,
+    /// Unknown for the version a command had before its first tracked change
+    pub author_id: Option<UserId>,
+    pub timestamp: DateTime<Utc>,
+    pub body: String,
+    pub description: String,
+}
+
 pub async fn check_command_exists(ctx: &Context, server_id: GuildId, name: &str) -> Option<bool> {
     let mut conn = get_database_conn!(ctx);
 
@@ -24,12 +39,14 @@ pub async fn check_command_exists(ctx: &Context, server_id: GuildId, name: &str)
     .ok()?
 }
 
+/// Adds or updates a custom command, and saves the new version in its history
 pub async fn add_custom_command(
     ctx: &Context,
     server_id: GuildId,
     name: &str,
     body: &str,
     description: Option<&str>,
+    author_id: UserId,
 ) -> CommandResult {
     let mut conn = get_database_conn!(ctx);
     let query = if check_command_exists(ctx, server_id, name)
@@ -51,7 +68,29 @@ pub async fn add_custom_command(
         )
     };
 
-    conn.exec_drop(
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+
+    // commands defined before the history was kept have no revision yet: save
+    // their current version first, so that it can still be restored
+    tx.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (server_id, name, revision, author_id, command_json, documentation) \
+SELECT server_id, name, 1, NULL, command_json, documentation FROM {} AS commands \
+WHERE server_id = :server_id AND name = :name AND NOT EXISTS (\
+SELECT history_id FROM {} AS history \
+WHERE history.server_id = commands.server_id AND history.name = commands.name)",
+            TABLE_CUSTOM_COMMANDS_HISTORY,
+            TABLE_CUSTOM_COMMANDS,
+            TABLE_CUSTOM_COMMANDS_HISTORY
+        ),
+        params! {
+            "server_id" => server_id.0,
+            "name" => name
+        },
+    )
+    .await?;
+
+    tx.exec_drop(
         query,
         params! {
             "server_id" => server_id.0,
@@ -62,6 +101,41 @@ pub async fn add_custom_command(
     )
     .await?;
 
+    let revision: u32 = tx
+        .exec_first(
+            formatcp!(
+                "SELECT COALESCE(MAX(revision), 0) + 1 FROM {} \
+WHERE server_id = :server_id AND name = :name",
+                TABLE_CUSTOM_COMMANDS_HISTORY
+            ),
+            params! {
+                "server_id" => server_id.0,
+                "name" => name
+            },
+        )
+        .await?
+        .unwrap_or(1);
+
+    // copy the stored documentation, which is kept when no new one is given
+    tx.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (server_id, name, revision, author_id, command_json, documentation) \
+SELECT server_id, name, :revision, :author_id, command_json, documentation FROM {} \
+WHERE server_id = :server_id AND name = :name",
+            TABLE_CUSTOM_COMMANDS_HISTORY,
+            TABLE_CUSTOM_COMMANDS
+        ),
+        params! {
+            "revision" => revision,
+            "author_id" => author_id.0,
+            "server_id" => server_id.0,
+            "name" => name
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
     Ok(())
 }
 
@@ -146,3 +220,68 @@ pub async fn get_custom_commands_list(
     .await
     .ok()
 }
+
+type RevisionRow = (u32, Option<u64>, NaiveDateTime, String, String);
+
+fn revision_from_row(
+    (revision, author_id, timestamp, body, description): RevisionRow,
+) -> CommandRevision {
+    CommandRevision {
+        revision,
+        author_id: author_id.map(UserId),
+        timestamp: Utc.from_utc_datetime(&timestamp),
+        body,
+        description,
+    }
+}
+
+/// Gets the revisions of a custom command, newest first
+pub async fn get_command_history(
+    ctx: &Context,
+    server_id: GuildId,
+    name: &str,
+    limit: u32,
+) -> Option<Vec<CommandRevision>> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_map(
+        formatcp!(
+            "SELECT revision, author_id, timestamp, command_json, documentation FROM {} \
+WHERE server_id = :server_id AND name = :name ORDER BY revision DESC LIMIT :limit",
+            TABLE_CUSTOM_COMMANDS_HISTORY
+        ),
+        params! {
+            "server_id" => server_id.0,
+            "name" => name,
+            "limit" => limit
+        },
+        revision_from_row,
+    )
+    .await
+    .ok()
+}
+
+pub async fn get_command_revision(
+    ctx: &Context,
+    server_id: GuildId,
+    name: &str,
+    revision: u32,
+) -> Option<CommandRevision> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_first(
+        formatcp!(
+            "SELECT revision, author_id, timestamp, command_json, documentation FROM {} \
+WHERE server_id = :server_id AND name = :name AND revision = :revision",
+            TABLE_CUSTOM_COMMANDS_HISTORY
+        ),
+        params! {
+            "server_id" => server_id.0,
+            "name" => name,
+            "revision" => revision
+        },
+    )
+    .await
+    .ok()?
+    .map(revision_from_row)
+}
@@ -0,0 +1,222 @@
+use const_format::formatcp;
+use mysql_async::prelude::*;
+use mysql_async::Conn;
+use serenity::client::Context;
+use serenity::framework::standard::CommandResult;
+use serenity::model::prelude::*;
+use std::collections::HashMap;
+
+use crate::constants::{
+    TABLE_BUG_ISSUE_SYNC, TABLE_BUG_REPORTS, TABLE_BUG_REPORTS_ISSUES, TABLE_BUG_REPORTS_LINKS,
+};
+use crate::database::bug_reports::BugLink;
+use crate::get_database_conn;
+
+/// Title and status of a mirrored bug, as they were on both sides after the last sync
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncState {
+    pub title: String,
+    pub open: bool,
+}
+
+/// A bug report mirrored to an issue
+#[derive(Debug, Clone)]
+pub struct SyncedBug {
+    pub bug_id: u64,
+    pub issue_number: u64,
+    pub issue_url: String,
+    pub title: String,
+    pub status: String,
+    pub links: Vec<BugLink>,
+    pub last: SyncState,
+}
+
+pub async fn get_issue_sync_url(ctx: &Context, server_id: GuildId) -> Option<String> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_first(
+        formatcp!(
+            "SELECT api_url FROM {} WHERE server_id = :server_id",
+            TABLE_BUG_ISSUE_SYNC
+        ),
+        params! {
+            "server_id" => server_id.0
+        },
+    )
+    .await
+    .ok()?
+}
+
+pub async fn set_issue_sync_url(ctx: &Context, server_id: GuildId, api_url: &str) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "REPLACE INTO {} (server_id, api_url) VALUES (:server_id, :api_url)",
+            TABLE_BUG_ISSUE_SYNC
+        ),
+        params! {
+            "server_id" => server_id.0,
+            "api_url" => api_url,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_issue_sync_url(ctx: &Context, server_id: GuildId) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "DELETE FROM {} WHERE server_id = :server_id",
+            TABLE_BUG_ISSUE_SYNC
+        ),
+        params! {
+            "server_id" => server_id.0
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_issue_sync_configs(
+    conn: &mut Conn,
+) -> Result<Vec<(GuildId, String)>, mysql_async::Error> {
+    conn.query_map(
+        formatcp!("SELECT server_id, api_url FROM {}", TABLE_BUG_ISSUE_SYNC),
+        |(server_id, api_url)| (GuildId(server_id), api_url),
+    )
+    .await
+}
+
+/// Gets the number and url of the issue a bug is mirrored to
+pub async fn get_bug_issue(ctx: &Context, bug_id: u64) -> Option<(u64, String)> {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_first(
+        formatcp!(
+            "SELECT issue_number, issue_url FROM {} WHERE bug_id = :bug_id",
+            TABLE_BUG_REPORTS_ISSUES
+        ),
+        params! {
+            "bug_id" => bug_id
+        },
+    )
+    .await
+    .ok()?
+}
+
+pub async fn add_bug_issue(
+    ctx: &Context,
+    bug_id: u64,
+    issue_number: u64,
+    issue_url: &str,
+    state: &SyncState,
+) -> CommandResult {
+    let mut conn = get_database_conn!(ctx);
+
+    conn.exec_drop(
+        formatcp!(
+            "INSERT INTO {} (bug_id, issue_number, issue_url, synced_title, synced_open) \
+VALUES (:bug_id, :issue_number, :issue_url, :synced_title, :synced_open)",
+            TABLE_BUG_REPORTS_ISSUES
+        ),
+        params! {
+            "bug_id" => bug_id,
+            "issue_number" => issue_number,
+            "issue_url" => issue_url,
+            "synced_title" => &state.title,
+            "synced_open" => state.open,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the mirrored bugs of a guild, with their current title, status and links
+pub async fn get_synced_bugs(
+    conn: &mut Conn,
+    server_id: GuildId,
+) -> Result<Vec<SyncedBug>, mysql_async::Error> {
+    let mut bugs = conn
+        .exec_map(
+            formatcp!(
+                "SELECT bugs.bug_id, issues.issue_number, issues.issue_url, bugs.title, bugs.status, \
+issues.synced_title, issues.synced_open FROM {} AS issues \
+JOIN {} AS bugs ON issues.bug_id = bugs.bug_id WHERE bugs.guild_id = :guild_id",
+                TABLE_BUG_REPORTS_ISSUES,
+                TABLE_BUG_REPORTS
+            ),
+            params! {
+                "guild_id" => server_id.0
+            },
+            |(bug_id, issue_number, issue_url, title, status, synced_title, synced_open)| {
+                SyncedBug {
+                    bug_id,
+                    issue_number,
+                    issue_url,
+                    title,
+                    status,
+                    links: Vec::new(),
+                    last: SyncState {
+                        title: synced_title,
+                        open: synced_open,
+                    },
+                }
+            },
+        )
+        .await?;
+
+    let mut links: HashMap<u64, Vec<BugLink>> = HashMap::new();
+    for (bug_id, id, url, title) in conn
+        .exec::<(u64, u64, String, String), _, _>(
+            formatcp!(
+                "SELECT links.bug_id, links.link_id, links.link_url, links.link_title FROM {} AS links \
+JOIN {} AS bugs ON links.bug_id = bugs.bug_id \
+WHERE bugs.guild_id = :guild_id AND links.bug_id IN (SELECT bug_id FROM {}) \
+ORDER BY links.link_id ASC",
+                TABLE_BUG_REPORTS_LINKS,
+                TABLE_BUG_REPORTS,
+                TABLE_BUG_REPORTS_ISSUES
+            ),
+            params! {
+                "guild_id" => server_id.0
+            },
+        )
+        .await?
+    {
+        links
+            .entry(bug_id)
+            .or_default()
+            .push(BugLink { id, url, title });
+    }
+    for bug in &mut bugs {
+        bug.links = links.remove(&bug.bug_id).unwrap_or_default();
+    }
+
+    Ok(bugs)
+}
+
+pub async fn set_sync_state(
+    conn: &mut Conn,
+    bug_id: u64,
+    state: &SyncState,
+) -> Result<(), mysql_async::Error> {
+    conn.exec_drop(
+        formatcp!(
+            "UPDATE {} SET synced_title = :synced_title, synced_open = :synced_open, \
+synced_at = CURRENT_TIMESTAMP WHERE bug_id = :bug_id",
+            TABLE_BUG_REPORTS_ISSUES
+        ),
+        params! {
+            "synced_title" => &state.title,
+            "synced_open" => state.open,
+            "bug_id" => bug_id,
+        },
+    )
+    .await
+}
@@ -2,10 +2,17 @@
 
 pub mod admin_data;
 pub mod blacklist;
+pub mod bug_digest;
 pub mod bug_reports;
+pub mod bug_statistics;
+pub mod bug_statuses;
+pub mod bug_submissions;
+pub mod bug_trackers;
+pub mod command_usage;
 pub mod config;
 pub mod custom_commands;
 pub mod floppa;
+pub mod issue_sync;
 pub mod qa_data;
 pub mod roles;
 
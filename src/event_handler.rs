@@ -164,6 +164,7 @@ impl EventHandler for Handler {
                 .strip_prefix("bug_unsubscribe__")
                 .and_then(|s| s.parse::<u64>().ok())
             {
+                let prefix = crate::database::bug_trackers::get_bug_prefix(&ctx, bug_id).await;
                 if crate::database::bug_reports::is_notified_user(&ctx, bug_id, user.id).await
                     != Some(true)
                 {
@@ -171,7 +172,7 @@ impl EventHandler for Handler {
                         .say_ephemeral(
                             &ctx,
                             format!(
-                                ":x: You are not subscribed to bug LOTR-{bug_id}.
+                                ":x: You are not subscribed to bug {prefix}-{bug_id}.
 
 To see all your active notifications type  `!bug notifications`"
                             ),
@@ -182,7 +183,7 @@ To see all your active notifications type  `!bug notifications`"
                 {
                     println!(
                         "=== ERROR ===\nCould not remove {} {:?} \
-from LOTR-{} notifications\nError: {}\n=== END ===",
+from bug {} notifications\nError: {}\n=== END ===",
                         user.tag(),
                         user.id,
                         bug_id,
@@ -193,17 +194,56 @@ from LOTR-{} notifications\nError: {}\n=== END ===",
                         .say_ephemeral(
                             &ctx,
                             format!(
-                                "You have successfully been unsubscribed from bug LOTR-{bug_id}.
+                                "You have successfully been unsubscribed from bug {prefix}-{bug_id}.
 
 To see all your active notifications type  `!bug notifications`"
                             ),
                         )
                         .await;
                 }
+            } else if let Some((submission_id, accept)) = custom_id
+                .strip_prefix("bug_accept__")
+                .map(|s| (s, true))
+                .or_else(|| custom_id.strip_prefix("bug_reject__").map(|s| (s, false)))
+                .and_then(|(s, accept)| Some((s.parse::<u64>().ok()?, accept)))
+            {
+                if let Err(e) = crate::commands::bug_reports::review_submission(
+                    &ctx,
+                    component_interaction,
+                    submission_id,
+                    accept,
+                )
+                .await
+                {
+                    println!(
+                        "=== ERROR ===\nCould not review bug submission {} for {} {:?}\nError: {}\n=== END ===",
+                        submission_id,
+                        user.tag(),
+                        user.id,
+                        e
+                    );
+                }
+            } else if let Some(bug_id) = custom_id
+                .strip_prefix("bug_vote__")
+                .and_then(|s| s.parse::<u64>().ok())
+            {
+                if let Err(e) =
+                    crate::commands::bug_reports::vote_for_bug(&ctx, component_interaction, bug_id)
+                        .await
+                {
+                    println!(
+                        "=== ERROR ===\nCould not record the vote of {} {:?} for bug {}\nError: {}\n=== END ===",
+                        user.tag(),
+                        user.id,
+                        bug_id,
+                        e
+                    );
+                }
             } else if let Some(bug_id) = custom_id
                 .strip_prefix("bug_subscribe__")
                 .and_then(|s| s.parse::<u64>().ok())
             {
+                let prefix = crate::database::bug_trackers::get_bug_prefix(&ctx, bug_id).await;
                 if crate::database::bug_reports::is_notified_user(&ctx, bug_id, user.id).await
                     != Some(false)
                 {
@@ -211,7 +251,7 @@ To see all your active notifications type  `!bug notifications`"
                         .say_ephemeral(
                             &ctx,
                             format!(
-                                ":x: You are already subscribed to bug LOTR-{bug_id}.
+                                ":x: You are already subscribed to bug {prefix}-{bug_id}.
 
 To see all your active notifications type  `!bug notifications`"
                             ),
@@ -222,7 +262,7 @@ To see all your active notifications type  `!bug notifications`"
                 {
                     println!(
                         "=== ERROR ===\nCould not add {} {:?} \
-to LOTR-{} notifications\nError: {}\n=== END ===",
+to bug {} notifications\nError: {}\n=== END ===",
                         user.tag(),
                         user.id,
                         bug_id,
@@ -233,7 +273,7 @@ to LOTR-{} notifications\nError: {}\n=== END ===",
                         .say_ephemeral(
                             &ctx,
                             format!(
-                                "You have successfully been subscribed to bug LOTR-{bug_id}.
+                                "You have successfully been subscribed to bug {prefix}-{bug_id}.
 
 To see all your active notifications type  `!bug notifications`"
                             ),
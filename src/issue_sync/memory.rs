@@ -0,0 +1,52 @@
+//! In-memory [`IssueBackend`], for tests
+
+use serenity::async_trait;
+use serenity::framework::standard::{CommandError, CommandResult};
+use std::sync::Mutex;
+
+use super::{Issue, IssueBackend, IssueRef};
+
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    issues: Mutex<Vec<Issue>>,
+}
+
+impl MemoryBackend {
+    /// Edits an issue as a developer would on the issue tracker
+    pub fn edit(&self, number: u64, f: impl FnOnce(&mut Issue)) {
+        let mut issues = self.issues.lock().unwrap();
+        f(&mut issues[number as usize - 1]);
+    }
+}
+
+#[async_trait]
+impl IssueBackend for MemoryBackend {
+    async fn create_issue(&self, issue: &Issue, _description: &str) -> CommandResult<IssueRef> {
+        let mut issues = self.issues.lock().unwrap();
+        issues.push(issue.clone());
+        let number = issues.len() as u64;
+        Ok(IssueRef {
+            number,
+            url: format!("memory://issues/{number}"),
+        })
+    }
+
+    async fn get_issue(&self, number: u64) -> CommandResult<Issue> {
+        let issues = self.issues.lock().unwrap();
+        number
+            .checked_sub(1)
+            .and_then(|i| issues.get(i as usize))
+            .cloned()
+            .ok_or_else(|| CommandError::from(format!("Issue #{number} does not exist")))
+    }
+
+    async fn update_issue(&self, number: u64, issue: &Issue) -> CommandResult {
+        let mut issues = self.issues.lock().unwrap();
+        let slot = number
+            .checked_sub(1)
+            .and_then(|i| issues.get_mut(i as usize))
+            .ok_or_else(|| CommandError::from(format!("Issue #{number} does not exist")))?;
+        *slot = issue.clone();
+        Ok(())
+    }
+}
@@ -0,0 +1,413 @@
+//! Mirroring of bug reports to an external issue tracker, configured with `!bug issues`.
+//!
+//! Bugs are mirrored one by one with `!bug mirror`. A background task then keeps the
+//! title, status and links of each mirrored bug in sync with its issue, both ways.
+//! When both sides changed since the last sync, the bugtracker wins.
+//! Links are only ever added on either side, never removed.
+
+#[cfg(test)]
+mod memory;
+pub mod rest;
+
+use serenity::async_trait;
+use serenity::framework::standard::CommandResult;
+use serenity::http::Http;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::ReqwestClient;
+use crate::constants::BOT_ID;
+use crate::database::bug_reports::{insert_link, update_bug_status, update_title, BugLink};
+use crate::database::bug_statuses::{load_status_workflow, StatusWorkflow};
+use crate::database::issue_sync::{
+    get_issue_sync_configs, get_synced_bugs, set_sync_state, SyncState, SyncedBug,
+};
+use crate::database::DatabasePool;
+
+/// How often the task syncs the mirrored bugs
+const SYNC_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueLink {
+    pub url: String,
+    pub title: String,
+}
+
+impl From<&BugLink> for IssueLink {
+    fn from(link: &BugLink) -> Self {
+        Self {
+            url: link.url.clone(),
+            title: link.title.clone(),
+        }
+    }
+}
+
+/// The synced fields of an issue, or of a bug report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub title: String,
+    pub open: bool,
+    pub links: Vec<IssueLink>,
+}
+
+/// An issue created by a backend
+#[derive(Debug, Clone)]
+pub struct IssueRef {
+    pub number: u64,
+    pub url: String,
+}
+
+#[async_trait]
+pub trait IssueBackend: Send + Sync {
+    /// Creates an issue. The description is only used as the initial issue body.
+    async fn create_issue(&self, issue: &Issue, description: &str) -> CommandResult<IssueRef>;
+
+    async fn get_issue(&self, number: u64) -> CommandResult<Issue>;
+
+    async fn update_issue(&self, number: u64, issue: &Issue) -> CommandResult;
+}
+
+/// Gets the token of an issue tracker. The `ISSUE_TRACKER_TOKEN` environment variable
+/// is only sent to the api url in `ISSUE_TRACKER_URL`: the api urls are chosen by
+/// the admins of each guild, who must not be able to get the token.
+fn token_for(api_url: &str) -> Option<String> {
+    let token_url = env::var("ISSUE_TRACKER_URL").ok()?;
+    if token_url.trim_end_matches('/') == api_url.trim_end_matches('/') {
+        env::var("ISSUE_TRACKER_TOKEN").ok()
+    } else {
+        None
+    }
+}
+
+/// Hosts that the admins of a guild may sync their bugs with: the one of
+/// `ISSUE_TRACKER_URL`, and those of the comma-separated `ISSUE_TRACKER_HOSTS`
+/// environment variable
+fn allowed_hosts() -> Vec<String> {
+    let configured = env::var("ISSUE_TRACKER_URL").ok().and_then(|url| {
+        reqwest::Url::parse(&url)
+            .ok()?
+            .host_str()
+            .map(str::to_string)
+    });
+    let extra = env::var("ISSUE_TRACKER_HOSTS").unwrap_or_default();
+    configured
+        .into_iter()
+        .chain(
+            extra
+                .split(',')
+                .map(str::trim)
+                .filter(|host| !host.is_empty())
+                .map(str::to_string),
+        )
+        .collect()
+}
+
+fn is_allowed_url(api_url: &str, hosts: &[String]) -> bool {
+    let Ok(url) = reqwest::Url::parse(api_url) else {
+        return false;
+    };
+    url.scheme() == "https"
+        && url.host_str().map_or(false, |host| {
+            hosts.iter().any(|h| h.eq_ignore_ascii_case(host))
+        })
+}
+
+/// Checks that an api url is on an allowed issue tracker host. The sync task
+/// sends requests to these urls from the bot host, so guild admins must not be
+/// able to point it at arbitrary or internal hosts.
+pub fn is_allowed_api_url(api_url: &str) -> bool {
+    is_allowed_url(api_url, &allowed_hosts())
+}
+
+/// Gets the backend of an issue tracker, authenticated if it is the one of
+/// the `ISSUE_TRACKER_URL` environment variable
+pub fn backend_for(client: reqwest::Client, api_url: &str) -> Box<dyn IssueBackend> {
+    Box::new(rest::RestBackend::new(client, api_url, token_for(api_url)))
+}
+
+/// Result of comparing a bug report with its issue since their last sync
+#[derive(Debug, Clone)]
+pub struct Reconciled {
+    /// What both sides should look like after the sync
+    pub merged: Issue,
+    pub update_issue: bool,
+    pub update_bug_title: bool,
+    pub update_bug_status: bool,
+    /// Links of the issue that the bug report does not have yet
+    pub new_bug_links: Vec<IssueLink>,
+}
+
+pub fn reconcile(bug: &Issue, issue: &Issue, last: &SyncState) -> Reconciled {
+    let title = if bug.title != last.title || issue.title == last.title {
+        &bug.title
+    } else {
+        &issue.title
+    };
+    let open = if bug.open != last.open || issue.open == last.open {
+        bug.open
+    } else {
+        issue.open
+    };
+
+    let new_bug_links = issue
+        .links
+        .iter()
+        .filter(|link| !bug.links.iter().any(|l| l.url == link.url))
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut links = bug.links.clone();
+    links.extend(new_bug_links.iter().cloned());
+
+    let update_issue = *title != issue.title
+        || open != issue.open
+        || links
+            .iter()
+            .any(|link| !issue.links.iter().any(|l| l.url == link.url));
+
+    Reconciled {
+        update_bug_title: *title != bug.title,
+        update_bug_status: open != bug.open,
+        update_issue,
+        new_bug_links,
+        merged: Issue {
+            title: title.clone(),
+            open,
+            links,
+        },
+    }
+}
+
+/// Brings an issue up to date with a bug report, returning the changes
+/// that the bug report needs in turn
+pub async fn sync_issue(
+    backend: &dyn IssueBackend,
+    number: u64,
+    bug: &Issue,
+    last: &SyncState,
+) -> CommandResult<Reconciled> {
+    let issue = backend.get_issue(number).await?;
+    let reconciled = reconcile(bug, &issue, last);
+    if reconciled.update_issue {
+        backend.update_issue(number, &reconciled.merged).await?;
+    }
+    Ok(reconciled)
+}
+
+async fn sync_bug(
+    conn: &mut mysql_async::Conn,
+    http: &Http,
+    backend: &dyn IssueBackend,
+    workflow: &StatusWorkflow,
+    bug: &SyncedBug,
+) -> CommandResult {
+    let local = Issue {
+        title: bug.title.clone(),
+        open: workflow.resolve(&bug.status).is_open,
+        links: bug
+            .links
+            .iter()
+            .filter(|link| link.url != bug.issue_url)
+            .map(IssueLink::from)
+            .collect(),
+    };
+    let reconciled = sync_issue(backend, bug.issue_number, &local, &bug.last).await?;
+
+    if reconciled.update_bug_title {
+        update_title(conn, bug.bug_id, &reconciled.merged.title, BOT_ID).await?;
+    }
+    if reconciled.update_bug_status {
+        let status = if reconciled.merged.open {
+            workflow.default_status()
+        } else {
+            workflow.resolved()
+        };
+        update_bug_status(conn, http, bug.bug_id, status, BOT_ID).await?;
+    }
+    for link in &reconciled.new_bug_links {
+        // the sync state is only saved once every link was added, so that
+        // the missing ones are added again on the next sync
+        insert_link(conn, bug.bug_id, &link.url, &link.title, BOT_ID)
+            .await
+            .ok_or_else(|| format!("Could not add the issue link {}", link.url))?;
+    }
+
+    set_sync_state(
+        conn,
+        bug.bug_id,
+        &SyncState {
+            title: reconciled.merged.title,
+            open: reconciled.merged.open,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn sync_all(
+    pool: &DatabasePool,
+    http: &Http,
+    client: &ReqwestClient,
+) -> Result<(), mysql_async::Error> {
+    let mut conn = pool.get_conn().await?;
+
+    for (server_id, api_url) in get_issue_sync_configs(&mut conn).await? {
+        // urls saved before the host allowlist existed
+        if !is_allowed_api_url(&api_url) {
+            println!(
+                "=== ERROR ===\nSkipped the issue tracker of {server_id}: {api_url} is not an allowed host\n=== END ==="
+            );
+            continue;
+        }
+        let backend = backend_for(client.inner(), &api_url);
+        let workflow = load_status_workflow(&mut conn, server_id).await?;
+        for bug in get_synced_bugs(&mut conn, server_id).await? {
+            if let Err(e) = sync_bug(&mut conn, http, backend.as_ref(), &workflow, &bug).await {
+                println!(
+                    "=== ERROR ===\nCould not sync bug {} with issue #{}: {e}\n=== END ===",
+                    bug.bug_id, bug.issue_number
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Syncs the mirrored bugs with their issues at a regular interval. Never returns.
+pub async fn sync_task(data: Arc<RwLock<TypeMap>>, http: Arc<Http>) {
+    let (pool, client) = {
+        let data_read = data.read().await;
+        (
+            data_read
+                .get::<DatabasePool>()
+                .expect("Expected a database pool in the type map")
+                .clone(),
+            data_read
+                .get::<ReqwestClient>()
+                .expect("Expected a reqwest client in the type map")
+                .clone(),
+        )
+    };
+
+    let mut interval = tokio::time::interval(SYNC_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = sync_all(&pool, &http, &client).await {
+            println!("=== ERROR ===\nCould not sync mirrored bugs: {e}\n=== END ===");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::memory::MemoryBackend;
+    use super::*;
+
+    #[test]
+    fn test_allowed_urls() {
+        let hosts = vec!["api.github.com".to_string()];
+        assert!(is_allowed_url(
+            "https://api.github.com/repos/owner/repo",
+            &hosts
+        ));
+        assert!(is_allowed_url("https://API.github.com/repos", &hosts));
+        assert!(!is_allowed_url("http://api.github.com/repos", &hosts));
+        assert!(!is_allowed_url("https://169.254.169.254/latest", &hosts));
+        assert!(!is_allowed_url("https://api.github.com.evil.com/", &hosts));
+        assert!(!is_allowed_url("https://api.github.com@localhost/", &hosts));
+        assert!(!is_allowed_url("not a url", &hosts));
+        assert!(!is_allowed_url("https://api.github.com/repos", &[]));
+    }
+
+    fn link(url: &str) -> IssueLink {
+        IssueLink {
+            url: url.into(),
+            title: url.into(),
+        }
+    }
+
+    fn bug(title: &str, open: bool, links: &[&str]) -> Issue {
+        Issue {
+            title: title.into(),
+            open,
+            links: links.iter().map(|url| link(url)).collect(),
+        }
+    }
+
+    fn state(title: &str, open: bool) -> SyncState {
+        SyncState {
+            title: title.into(),
+            open,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bug_changes_are_pushed() {
+        let backend = MemoryBackend::default();
+        let created = backend
+            .create_issue(&bug("Crash", true, &[]), "")
+            .await
+            .unwrap();
+
+        let local = bug("Crash on startup", false, &["https://a"]);
+        let reconciled = sync_issue(&backend, created.number, &local, &state("Crash", true))
+            .await
+            .unwrap();
+
+        assert!(reconciled.update_issue);
+        assert!(!reconciled.update_bug_title && !reconciled.update_bug_status);
+        assert_eq!(backend.get_issue(created.number).await.unwrap(), local);
+    }
+
+    #[tokio::test]
+    async fn test_issue_changes_are_pulled() {
+        let backend = MemoryBackend::default();
+        let created = backend
+            .create_issue(&bug("Crash", true, &["https://a"]), "")
+            .await
+            .unwrap();
+        backend.edit(created.number, |issue| {
+            issue.title = "Crash with shaders".into();
+            issue.open = false;
+            issue.links.push(link("https://b"));
+        });
+
+        let local = bug("Crash", true, &["https://a"]);
+        let reconciled = sync_issue(&backend, created.number, &local, &state("Crash", true))
+            .await
+            .unwrap();
+
+        assert!(!reconciled.update_issue);
+        assert!(reconciled.update_bug_title && reconciled.update_bug_status);
+        assert_eq!(reconciled.new_bug_links, vec![link("https://b")]);
+        assert_eq!(
+            reconciled.merged,
+            bug("Crash with shaders", false, &["https://a", "https://b"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bugtracker_wins_conflicts() {
+        let backend = MemoryBackend::default();
+        let created = backend
+            .create_issue(&bug("Crash", true, &[]), "")
+            .await
+            .unwrap();
+        backend.edit(created.number, |issue| issue.title = "Remote title".into());
+
+        let local = bug("Local title", true, &[]);
+        let reconciled = sync_issue(&backend, created.number, &local, &state("Crash", true))
+            .await
+            .unwrap();
+
+        assert!(!reconciled.update_bug_title);
+        assert_eq!(
+            backend.get_issue(created.number).await.unwrap().title,
+            "Local title"
+        );
+    }
+}
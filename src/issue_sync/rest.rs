@@ -0,0 +1,206 @@
+//! [`IssueBackend`] for GitHub-style REST APIs, with an api url such as
+//! `https://api.github.com/repos/<owner>/<repo>`.
+//!
+//! The links of a bug are kept in a section of the issue body delimited by
+//! HTML comments, so that the rest of the body can be freely edited.
+
+use reqwest::{Method, RequestBuilder};
+use serde::Deserialize;
+use serde_json::json;
+use serenity::async_trait;
+use serenity::framework::standard::{CommandError, CommandResult};
+
+use super::{Issue, IssueBackend, IssueLink, IssueRef};
+
+const LINKS_START: &str = "<!-- bugtracker links -->";
+const LINKS_END: &str = "<!-- end of bugtracker links -->";
+
+#[derive(Debug, Deserialize)]
+struct RestIssue {
+    number: u64,
+    html_url: String,
+    title: String,
+    state: String,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RestBackend {
+    client: reqwest::Client,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl RestBackend {
+    pub fn new(client: reqwest::Client, api_url: &str, token: Option<String>) -> Self {
+        Self {
+            client,
+            api_url: api_url.trim_end_matches('/').into(),
+            token,
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self
+            .client
+            .request(method, format!("{}{}", self.api_url, path))
+            .header("accept", "application/vnd.github+json");
+        if let Some(token) = &self.token {
+            request.header("authorization", format!("Bearer {token}"))
+        } else {
+            request
+        }
+    }
+
+    async fn send(
+        &self,
+        request: RequestBuilder,
+        body: Option<serde_json::Value>,
+    ) -> CommandResult<RestIssue> {
+        let request = if let Some(body) = body {
+            request
+                .header("content-type", "application/json")
+                .body(body.to_string())
+        } else {
+            request
+        };
+        let response = request.send().await?.error_for_status()?.text().await?;
+        serde_json::from_str(&response).map_err(CommandError::from)
+    }
+
+    async fn get(&self, number: u64) -> CommandResult<RestIssue> {
+        self.send(
+            self.request(Method::GET, &format!("/issues/{number}")),
+            None,
+        )
+        .await
+    }
+}
+
+fn state(open: bool) -> &'static str {
+    if open {
+        "open"
+    } else {
+        "closed"
+    }
+}
+
+fn render_links(links: &[IssueLink]) -> String {
+    let mut section = format!("{LINKS_START}\n");
+    if !links.is_empty() {
+        section.push_str("**Links**\n");
+    }
+    for link in links {
+        section.push_str(&format!("- [{}]({})\n", link.title, link.url));
+    }
+    section.push_str(LINKS_END);
+    section
+}
+
+/// Replaces the links section of an issue body, or appends one
+fn with_links(body: &str, links: &[IssueLink]) -> String {
+    let section = render_links(links);
+    match (body.find(LINKS_START), body.find(LINKS_END)) {
+        (Some(start), Some(end)) if start < end => format!(
+            "{}{}{}",
+            &body[..start],
+            section,
+            &body[end + LINKS_END.len()..]
+        ),
+        _ if body.is_empty() => section,
+        _ => format!("{body}\n\n{section}"),
+    }
+}
+
+fn parse_links(body: &str) -> Vec<IssueLink> {
+    let (Some(start), Some(end)) = (body.find(LINKS_START), body.find(LINKS_END)) else {
+        return Vec::new();
+    };
+    if start > end {
+        return Vec::new();
+    }
+    body[start + LINKS_START.len()..end]
+        .lines()
+        .filter_map(|line| {
+            let (title, url) = line.trim().strip_prefix("- [")?.rsplit_once("](")?;
+            Some(IssueLink {
+                url: url.strip_suffix(')')?.into(),
+                title: title.into(),
+            })
+        })
+        .collect()
+}
+
+#[async_trait]
+impl IssueBackend for RestBackend {
+    async fn create_issue(&self, issue: &Issue, description: &str) -> CommandResult<IssueRef> {
+        let created = self
+            .send(
+                self.request(Method::POST, "/issues"),
+                Some(json!({
+                    "title": issue.title,
+                    "body": with_links(description, &issue.links),
+                })),
+            )
+            .await?;
+
+        if !issue.open {
+            self.send(
+                self.request(Method::PATCH, &format!("/issues/{}", created.number)),
+                Some(json!({ "state": state(false) })),
+            )
+            .await?;
+        }
+
+        Ok(IssueRef {
+            number: created.number,
+            url: created.html_url,
+        })
+    }
+
+    async fn get_issue(&self, number: u64) -> CommandResult<Issue> {
+        let issue = self.get(number).await?;
+        Ok(Issue {
+            title: issue.title,
+            open: issue.state == "open",
+            links: parse_links(issue.body.as_deref().unwrap_or_default()),
+        })
+    }
+
+    async fn update_issue(&self, number: u64, issue: &Issue) -> CommandResult {
+        let current = self.get(number).await?;
+        self.send(
+            self.request(Method::PATCH, &format!("/issues/{number}")),
+            Some(json!({
+                "title": issue.title,
+                "state": state(issue.open),
+                "body": with_links(current.body.as_deref().unwrap_or_default(), &issue.links),
+            })),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_section() {
+        let links = vec![IssueLink {
+            url: "https://example.com/crash.log".into(),
+            title: "Crash log [1.7.10]".into(),
+        }];
+
+        let body = with_links("Steps to reproduce", &links);
+        assert!(body.starts_with("Steps to reproduce\n\n"));
+        assert_eq!(parse_links(&body), links);
+
+        let edited = format!("{body}\nEdited by a developer");
+        let updated = with_links(&edited, &[]);
+        assert!(updated.ends_with("\nEdited by a developer"));
+        assert!(parse_links(&updated).is_empty());
+    }
+}
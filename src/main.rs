@@ -9,13 +9,18 @@
 
 pub mod announcement;
 pub mod api;
+pub mod bug_digest;
+pub mod chart;
 pub mod check;
+pub mod command_usage;
 pub mod commands;
 pub mod constants;
 pub mod database;
 pub mod event_handler;
+pub mod issue_sync;
 pub mod qa_answers;
 pub mod role_cache;
+pub mod template;
 pub mod utils;
 
 use mysql_async::{OptsBuilder, PoolConstraints, PoolOpts, SslOpts};
@@ -29,6 +34,7 @@ use std::sync::Arc;
 
 use api::ReqwestClient;
 use check::{after_hook, dispatch_error_hook};
+use command_usage::CommandUsage;
 use commands::{
     admin::*, announcements::*, bug_reports::*, custom_commands::*, general::*, help::*, meme::*,
     qa_setup::*, roles::*, servers::*, wiki::*,
@@ -153,6 +159,7 @@ async fn main() {
     let role_cache = RoleCache::new();
     let prefix_cache = PrefixCache::new();
     let qa_channels_cache = QaChannelsCache::new();
+    let command_usage = CommandUsage::new();
 
     // initialize bot framework
     let framework = StandardFramework::new()
@@ -208,6 +215,7 @@ async fn main() {
     .type_map_insert::<RoleCache>(role_cache)
     .type_map_insert::<PrefixCache>(prefix_cache)
     .type_map_insert::<QaChannelsCache>(qa_channels_cache)
+    .type_map_insert::<CommandUsage>(command_usage)
     .type_map_insert::<FrameworkKey>(framework)
     .await
     .expect("Error creating client");
@@ -216,18 +224,41 @@ async fn main() {
         // Ctrl+C listener
 
         let shard_manager = client.shard_manager.clone();
+        let data = client.data.clone();
         tokio::spawn(async move {
             tokio::signal::ctrl_c().await.unwrap();
             println!("Shutting down...");
+            command_usage::flush_usage(&data).await;
             shard_manager.clone().lock().await.shutdown_all().await;
         });
     }
 
+    {
+        // Weekly bug digests and daily notification digests
+        let data = client.data.clone();
+        let http = client.cache_and_http.http.clone();
+        tokio::spawn(bug_digest::digest_task(data, http));
+    }
+
+    {
+        // Issue tracker sync of the mirrored bugs
+        let data = client.data.clone();
+        let http = client.cache_and_http.http.clone();
+        tokio::spawn(issue_sync::sync_task(data, http));
+    }
+
+    {
+        // Batched writes of the custom command usage counters
+        let data = client.data.clone();
+        tokio::spawn(command_usage::flush_task(data));
+    }
+
     #[cfg(unix)]
     {
         // Sigterm listener
 
         let shard_manager = client.shard_manager.clone();
+        let data = client.data.clone();
         tokio::spawn(async move {
             tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
                 .unwrap()
@@ -235,6 +266,7 @@ async fn main() {
                 .await
                 .unwrap();
             println!("Shutting down...");
+            command_usage::flush_usage(&data).await;
             shard_manager.lock().await.shutdown_all().await;
         });
     }
@@ -0,0 +1,335 @@
+//! Template engine of the custom commands.
+//!
+//! Templates are the text fields of a custom command, in which these placeholders
+//! are replaced when the command is used:
+//! - `$me`: the name of the user of the command
+//! - `$ping`: a mention of the user of the command
+//! - `$channel`: a mention of the channel the command is used in
+//! - `$args`: all the arguments of the command
+//! - `$as_url`: all the arguments of the command, url-encoded
+//! - `$0`, `$1`, ...: a single argument of the command
+//!
+//! Placeholders can also be written `${1}`, and take a default value with
+//! `${1:default value}`, used when the argument is not given.
+//! `\$` is a literal dollar sign.
+//!
+//! Unknown placeholders, and arguments that are not given and have no default,
+//! are left as written: commands defined before the placeholders were checked
+//! keep working. New commands are checked with [`validate`].
+
+use std::fmt;
+
+/// A placeholder name, such as `me` or `0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    Me,
+    Ping,
+    Channel,
+    Args,
+    AsUrl,
+    Arg(usize),
+}
+
+impl Placeholder {
+    fn parse(name: &str) -> Option<Self> {
+        use Placeholder::*;
+
+        Some(match name {
+            "me" => Me,
+            "ping" => Ping,
+            "channel" => Channel,
+            "args" => Args,
+            "as_url" => AsUrl,
+            _ if !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()) => {
+                Arg(name.parse().ok()?)
+            }
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment<'a> {
+    Text(&'a str),
+    /// A literal dollar sign, from `\$`
+    Dollar,
+    Placeholder {
+        placeholder: Placeholder,
+        default: Option<&'a str>,
+        /// The placeholder as written in the template
+        source: &'a str,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateErrorKind {
+    UnknownPlaceholder,
+    Unterminated,
+}
+
+/// An error in a template, pointing to the offending placeholder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    pub kind: TemplateErrorKind,
+    /// The placeholder as written in the template
+    pub placeholder: String,
+    /// Character position of the placeholder in the template
+    pub position: usize,
+    /// The template text around the placeholder
+    pub context: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use TemplateErrorKind::*;
+
+        match self.kind {
+            UnknownPlaceholder => write!(f, "unknown placeholder `{}`", self.placeholder)?,
+            Unterminated => write!(f, "unterminated placeholder `{}`", self.placeholder)?,
+        }
+        write!(
+            f,
+            " at character {}, in `{}`",
+            self.position + 1,
+            self.context
+        )
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+fn error(kind: TemplateErrorKind, template: &str, start: usize, end: usize) -> TemplateError {
+    const CONTEXT: usize = 20;
+
+    let context_start = template[..start]
+        .char_indices()
+        .rev()
+        .nth(CONTEXT - 1)
+        .map_or(0, |(i, _)| i);
+    let context_end = template[end..]
+        .char_indices()
+        .nth(CONTEXT)
+        .map_or(template.len(), |(i, _)| end + i);
+    TemplateError {
+        kind,
+        placeholder: template[start..end].to_string(),
+        position: template[..start].chars().count(),
+        context: format!(
+            "{}{}{}",
+            if context_start > 0 { "…" } else { "" },
+            &template[context_start..context_end],
+            if context_end < template.len() {
+                "…"
+            } else {
+                ""
+            }
+        ),
+    }
+}
+
+/// Splits a template into text and placeholders. Unless `strict`, malformed
+/// placeholders are kept as text instead of being errors.
+fn parse(template: &str, strict: bool) -> Result<Vec<Segment<'_>>, TemplateError> {
+    use TemplateErrorKind::*;
+
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+    let bytes = template.as_bytes();
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if bytes.get(i + 1) == Some(&b'$') => {
+                segments.push(Segment::Text(&template[text_start..i]));
+                segments.push(Segment::Dollar);
+                i += 2;
+                text_start = i;
+            }
+            b'$' => {
+                let start = i;
+                let (name, default, end) = if bytes.get(i + 1) == Some(&b'{') {
+                    let Some(len) = template[i + 2..].find('}') else {
+                        if strict {
+                            return Err(error(Unterminated, template, start, template.len()));
+                        }
+                        i += 1;
+                        continue;
+                    };
+                    let inner = &template[i + 2..i + 2 + len];
+                    let (name, default) = match inner.split_once(':') {
+                        Some((name, default)) => (name.trim(), Some(default)),
+                        None => (inner.trim(), None),
+                    };
+                    (name, default, i + 3 + len)
+                } else {
+                    let len = template[i + 1..]
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                        .unwrap_or(template.len() - i - 1);
+                    (&template[i + 1..i + 1 + len], None, i + 1 + len)
+                };
+
+                if end == start + 1 {
+                    // a lone dollar sign
+                    i += 1;
+                    continue;
+                }
+                let Some(placeholder) = Placeholder::parse(name) else {
+                    if strict {
+                        return Err(error(UnknownPlaceholder, template, start, end));
+                    }
+                    i = end;
+                    continue;
+                };
+
+                segments.push(Segment::Text(&template[text_start..start]));
+                segments.push(Segment::Placeholder {
+                    placeholder,
+                    default,
+                    source: &template[start..end],
+                });
+                i = end;
+                text_start = i;
+            }
+            _ => i += 1,
+        }
+    }
+    segments.push(Segment::Text(&template[text_start..]));
+    segments.retain(|s| *s != Segment::Text(""));
+
+    Ok(segments)
+}
+
+/// Checks that a template has no unknown or malformed placeholder
+pub fn validate(template: &str) -> Result<(), TemplateError> {
+    parse(template, true).map(|_| ())
+}
+
+/// Values of the placeholders for a use of a custom command
+#[derive(Debug, Clone, Default)]
+pub struct Placeholders {
+    pub me: String,
+    pub ping: String,
+    pub channel: String,
+    /// All the arguments, as written
+    pub args: String,
+    /// Each argument
+    pub positional: Vec<String>,
+    /// Defaults of the positional arguments, from the `"default_args"` field
+    pub default_args: Vec<String>,
+    /// All the arguments, url-encoded
+    pub as_url: String,
+}
+
+impl Placeholders {
+    fn get(&self, placeholder: Placeholder) -> Option<&str> {
+        use Placeholder::*;
+
+        fn non_empty(s: &str) -> Option<&str> {
+            (!s.is_empty()).then_some(s)
+        }
+
+        match placeholder {
+            Me => Some(&self.me),
+            Ping => Some(&self.ping),
+            Channel => Some(&self.channel),
+            Args => non_empty(&self.args),
+            AsUrl => non_empty(&self.as_url),
+            Arg(i) => self.positional.get(i).map(String::as_str),
+        }
+    }
+
+    /// Renders a template, substituting every known placeholder
+    pub fn render(&self, template: &str) -> String {
+        let mut result = String::with_capacity(template.len());
+
+        // a lenient parse never fails
+        for segment in parse(template, false).unwrap_or_default() {
+            match segment {
+                Segment::Text(text) => result.push_str(text),
+                Segment::Dollar => result.push('$'),
+                Segment::Placeholder {
+                    placeholder,
+                    default,
+                    source,
+                } => {
+                    let value = self.get(placeholder).or(default).or(match placeholder {
+                        Placeholder::Arg(i) => self.default_args.get(i).map(String::as_str),
+                        Placeholder::Args | Placeholder::AsUrl => Some(""),
+                        _ => None,
+                    });
+                    result.push_str(value.unwrap_or(source));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(args: &[&str]) -> Placeholders {
+        Placeholders {
+            me: "Aldan".into(),
+            ping: "<@1>".into(),
+            channel: "<#2>".into(),
+            args: args.join(" "),
+            positional: args.iter().map(|s| s.to_string()).collect(),
+            default_args: vec!["legacy".into()],
+            as_url: args.join("%20"),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let p = placeholders(&["$1", "\"quoted\""]);
+        assert_eq!(
+            p.render("Hi $me ($ping) in $channel: $0 $1, $args"),
+            "Hi Aldan (<@1>) in <#2>: $1 \"quoted\", $1 \"quoted\""
+        );
+        assert_eq!(p.render("${ 1 } ${2:none} ${0:x}"), "\"quoted\" none $1");
+        assert_eq!(
+            p.render("costs \\$5, or $ 5, or 5$"),
+            "costs $5, or $ 5, or 5$"
+        );
+        assert_eq!(
+            p.render("https://x.org/?q=$as_url"),
+            "https://x.org/?q=$1%20\"quoted\""
+        );
+
+        let p = placeholders(&[]);
+        assert_eq!(p.render("$0 [$args]"), "legacy []");
+    }
+
+    #[test]
+    fn test_render_leniently() {
+        let p = placeholders(&["a"]);
+
+        // text written before the placeholders existed is kept as is
+        assert_eq!(
+            p.render("Hello $mee, it costs $5!"),
+            "Hello $mee, it costs $5!"
+        );
+        assert_eq!(p.render("$0 and $3 or ${4}"), "a and $3 or ${4}");
+        assert_eq!(p.render("see ${1:default and $0"), "see ${1:default and a");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("$me: ${1:none} \\$5").is_ok());
+
+        let e = validate("Hello $mee!").unwrap_err();
+        assert_eq!(e.kind, TemplateErrorKind::UnknownPlaceholder);
+        assert_eq!(e.placeholder, "$mee");
+        assert_eq!(e.position, 6);
+
+        let e = validate("see ${1:default").unwrap_err();
+        assert_eq!(e.kind, TemplateErrorKind::Unterminated);
+        assert_eq!(e.position, 4);
+        assert!(e
+            .to_string()
+            .ends_with("at character 5, in `see ${1:default`"));
+    }
+}
@@ -251,13 +251,6 @@ pub fn parse_motd(motd: impl AsRef<str>) -> String {
     res
 }
 
-pub fn to_json_safe_string(s: impl AsRef<str>) -> String {
-    // serialize as string to get string escapes
-    let s = serde_json::ser::to_string(s.as_ref()).unwrap();
-    // remove the surrounding quotes
-    s[1..s.len() - 1].to_string()
-}
-
 use serenity::utils::Colour;
 
 const WEB_COLOURS: [(&str, u32); 147] = [
@@ -427,6 +420,82 @@ fn test_web_colour_search() {
     }
 }
 
+/// Line-based diff of two texts, with `-` and `+` prefixed lines for removed and
+/// added lines. Unchanged lines further than `context` lines from a change are elided.
+pub fn line_diff(old: &str, new: &str, context: usize) -> String {
+    use std::fmt::Write;
+
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // longest common subsequence lengths of the suffixes
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', old[i]));
+            i += 1;
+        } else {
+            lines.push(('+', new[j]));
+            j += 1;
+        }
+    }
+
+    let changes: Vec<usize> = (0..lines.len()).filter(|&k| lines[k].0 != ' ').collect();
+    let mut res = String::new();
+    let mut elided = false;
+    for (k, (prefix, line)) in lines.into_iter().enumerate() {
+        let near_change = changes
+            .binary_search_by(|&c| {
+                if c + context < k {
+                    std::cmp::Ordering::Less
+                } else if c > k + context {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok();
+        if near_change {
+            writeln!(res, "{prefix} {line}").unwrap();
+            elided = false;
+        } else if !elided {
+            res.push_str("@@ ... @@\n");
+            elided = true;
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+#[test]
+fn test_line_diff() {
+    let old = "a\nb\nc\nd\ne\nf";
+    let new = "a\nb\nC\nd\ne\nf\ng";
+
+    assert_eq!(
+        line_diff(old, new, 1),
+        "@@ ... @@\n  b\n- c\n+ C\n  d\n@@ ... @@\n  f\n+ g\n"
+    );
+    assert_eq!(line_diff(old, old, 3), "@@ ... @@\n");
+    assert_eq!(line_diff("", "x", 0), "+ x\n");
+}
+
 use serenity::async_trait;
 use serenity::builder::CreateInteractionResponse;
 use serenity::http::Http;
@@ -485,15 +554,3 @@ Error: {}
         }
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::to_json_safe_string;
-
-    #[test]
-    fn test_json_safe_string() {
-        let s = "\"holà\"\n}";
-
-        assert_eq!(to_json_safe_string(s), "\\\"holà\\\"\\n}");
-    }
-}